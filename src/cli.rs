@@ -0,0 +1,204 @@
+//! Command line arguments for opening a database when the application is
+//! launched
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, bail};
+use gpui::{App, Global};
+
+/// Usage text printed for `--help` and invalid arguments
+pub const USAGE: &str = "\
+Usage: crabbyqlite [OPTIONS] [DATABASE]
+
+Arguments:
+  [DATABASE]          Path of the database file to open
+
+Options:
+  --readonly          Open the database as read-only
+  --key-file <FILE>   Read the key of an encrypted database from FILE
+  --sql <FILE>        Open the SQL file FILE within the query editor
+  -h, --help          Print this help
+  --                  Treat the following arguments as paths";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    /// Path of the database to open
+    pub database: Option<PathBuf>,
+    /// Whether to open the database as read-only
+    pub readonly: bool,
+    /// File containing the key of an encrypted database
+    pub key_file: Option<PathBuf>,
+    /// SQL file to open within the query editor
+    pub sql: Option<PathBuf>,
+    /// Whether the usage was requested
+    pub help: bool,
+}
+
+impl CliArgs {
+    /// Parse the command line `args`, excluding the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut cli_args = CliArgs::default();
+        let mut args = args.into_iter();
+        let mut options_ended = false;
+
+        while let Some(arg) = args.next() {
+            // Arguments after "--" are paths even when starting with '-'
+            if options_ended {
+                cli_args.set_database(arg)?;
+                continue;
+            }
+
+            // Support both "--option value" and "--option=value"
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => {
+                    (name.to_string(), Some(value.to_string()))
+                }
+                _ => (arg, None),
+            };
+
+            let mut value = |name: &str| -> anyhow::Result<PathBuf> {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .map(PathBuf::from)
+                    .with_context(|| format!("missing value for '{name}'"))
+            };
+
+            match name.as_str() {
+                "--" => options_ended = true,
+                "-h" | "--help" => cli_args.help = true,
+                "--readonly" => cli_args.readonly = true,
+                "--key-file" => cli_args.key_file = Some(value("--key-file")?),
+                "--sql" => cli_args.sql = Some(value("--sql")?),
+                name if name.starts_with('-') => bail!("unknown option '{name}'"),
+                _ => cli_args.set_database(name)?,
+            }
+        }
+
+        if cli_args.database.is_none() && !cli_args.help {
+            if cli_args.key_file.is_some() {
+                bail!("'--key-file' requires a database");
+            }
+
+            if cli_args.sql.is_some() {
+                bail!("'--sql' requires a database");
+            }
+        }
+
+        Ok(cli_args)
+    }
+
+    /// Set the positional database argument, only one can be given
+    fn set_database(&mut self, path: String) -> anyhow::Result<()> {
+        if self.database.is_some() {
+            bail!("unexpected argument '{path}', only one database can be opened");
+        }
+
+        self.database = Some(PathBuf::from(path));
+        Ok(())
+    }
+
+    /// Read the database key from the key file, excluding the trailing
+    /// line break
+    pub fn read_key(&self) -> anyhow::Result<Option<String>> {
+        let Some(key_file) = &self.key_file else {
+            return Ok(None);
+        };
+
+        let key = fs::read_to_string(key_file)
+            .with_context(|| format!("failed to read key file '{}'", key_file.display()))?;
+
+        Ok(Some(key.trim_end_matches(['\r', '\n']).to_string()))
+    }
+}
+
+/// SQL file from the command line waiting to be opened within the query
+/// editor of the database opened on launch
+#[derive(Default)]
+pub struct StartupSqlFile(Option<PathBuf>);
+
+impl Global for StartupSqlFile {}
+
+pub fn init_startup_sql_file(cx: &mut App, path: Option<PathBuf>) {
+    cx.set_global(StartupSqlFile(path));
+}
+
+/// Take the SQL file from the command line, only the first caller gets the
+/// file
+pub fn take_startup_sql_file(cx: &mut App) -> Option<PathBuf> {
+    cx.try_global::<StartupSqlFile>()?;
+    cx.global_mut::<StartupSqlFile>().0.take()
+}
+
+/// Error parsing the command line waiting to be shown within the main
+/// window, console output isn't visible when launched without a terminal
+#[derive(Default)]
+pub struct StartupError(Option<String>);
+
+impl Global for StartupError {}
+
+pub fn init_startup_error(cx: &mut App, error: Option<String>) {
+    cx.set_global(StartupError(error));
+}
+
+/// Take the error from the command line, only the first caller gets the
+/// error
+pub fn take_startup_error(cx: &mut App) -> Option<String> {
+    cx.try_global::<StartupError>()?;
+    cx.global_mut::<StartupError>().0.take()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::CliArgs;
+
+    fn parse(args: &[&str]) -> anyhow::Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+
+        let args = parse(&[
+            "app.db",
+            "--readonly",
+            "--key-file",
+            "key.txt",
+            "--sql=q.sql",
+        ])
+        .unwrap();
+        assert_eq!(
+            args,
+            CliArgs {
+                database: Some(PathBuf::from("app.db")),
+                readonly: true,
+                key_file: Some(PathBuf::from("key.txt")),
+                sql: Some(PathBuf::from("q.sql")),
+                help: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_args() {
+        assert!(parse(&["--unknown"]).is_err());
+        assert!(parse(&["app.db", "--sql"]).is_err());
+        assert!(parse(&["a.db", "b.db"]).is_err());
+        assert!(parse(&["--sql", "q.sql"]).is_err());
+        assert!(parse(&["--", "a.db", "b.db"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_end_of_options() {
+        let args = parse(&["--readonly", "--", "-app.db"]).unwrap();
+        assert_eq!(args.database, Some(PathBuf::from("-app.db")));
+        assert!(args.readonly);
+
+        let args = parse(&["--", "--help"]).unwrap();
+        assert_eq!(args.database, Some(PathBuf::from("--help")));
+        assert!(!args.help);
+    }
+}
@@ -1,9 +1,10 @@
 pub mod sqlite;
 
-use std::{any::Any, rc::Rc};
+use std::{any::Any, fmt::Display, rc::Rc, time::Duration};
 
 use async_trait::async_trait;
 use gpui::SharedString;
+use tokio::sync::watch;
 
 #[derive(Debug, Clone)]
 pub struct DatabaseTable {
@@ -13,6 +14,8 @@ pub struct DatabaseTable {
     pub sql: String,
     /// Columns on the database table
     pub columns: Vec<DatabaseTableColumn>,
+    /// Foreign keys referencing other tables
+    pub foreign_keys: Vec<DatabaseForeignKey>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +24,80 @@ pub struct DatabaseTableColumn {
     pub column_type: String,
     pub not_null: bool,
     pub primary_key: bool,
+    /// SQL expression of the default value
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseForeignKey {
+    /// Column on the table holding the reference
+    pub from: String,
+    /// Name of the referenced table
+    pub table: String,
+    /// Referenced column, [None] when referencing the primary key
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabaseObjectKind {
+    Index,
+    View,
+    Trigger,
+}
+
+/// Schema object other than a table (index, view or trigger)
+#[derive(Debug, Clone)]
+pub struct DatabaseObject {
+    pub kind: DatabaseObjectKind,
+    /// Name of the object
+    pub name: String,
+    /// Name of the table the object belongs to (For views this is the view name)
+    pub table_name: String,
+    /// SQL used to create the object
+    pub sql: String,
+    /// Columns of the view, empty for indexes and triggers
+    pub columns: Vec<DatabaseTableColumn>,
+}
+
+/// Snapshot of the database schema
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseSchema {
+    /// Tables within the database
+    pub tables: Vec<DatabaseTable>,
+    /// Indexes, views and triggers within the database
+    pub objects: Vec<DatabaseObject>,
+    /// Names of the database schemas (main, temp and attached databases)
+    pub schemas: Vec<String>,
+    /// Tables and views of the temp and attached schemas
+    pub schema_tables: Vec<DatabaseSchemaTable>,
+    /// Functions available on the connection, including those of extensions
+    pub functions: Vec<DatabaseFunction>,
+}
+
+/// Table or view within a schema other than main
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatabaseSchemaTable {
+    /// Name of the schema (temp or an attached database)
+    pub schema: String,
+    pub name: String,
+    /// Whether the table is a view
+    pub view: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseFunction {
+    pub name: String,
+    /// Number of arguments, -1 when the function accepts any number
+    pub arguments: i32,
+}
+
+impl DatabaseSchema {
+    /// Views within the schema
+    pub fn views(&self) -> impl Iterator<Item = &DatabaseObject> {
+        self.objects
+            .iter()
+            .filter(|object| object.kind == DatabaseObjectKind::View)
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -28,6 +105,9 @@ pub struct DatabaseOptions {
     /// Path to the database file
     pub path: String,
 
+    /// Absolute path to the database file, [None] for in memory databases
+    pub full_path: Option<String>,
+
     /// Whether the db is readonly
     pub readonly: bool,
 
@@ -35,15 +115,56 @@ pub struct DatabaseOptions {
     pub encrypted: bool,
 }
 
+impl DatabaseOptions {
+    /// Location identifying the database, the full path of the database file
+    /// or the path of in memory databases
+    pub fn location(&self) -> String {
+        self.full_path.clone().unwrap_or_else(|| self.path.clone())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct DatabaseQueryResult {
     pub column_names: Vec<SharedString>,
     pub rows: Vec<DatabaseRow>,
+
+    /// Time taken to execute the query and collect the rows
+    pub elapsed: Duration,
+    /// Number of rows inserted, updated or deleted by the query
+    pub changes: u64,
+    /// Rowid of the most recent successful insert into a rowid table
+    pub last_insert_rowid: i64,
+    /// Whether the query made no direct changes to the database
+    pub readonly: bool,
 }
 
+/// Error from executing a query, with the location of the error within
+/// the query when the database reports it
+#[derive(Debug, Clone)]
+pub struct DatabaseQueryError {
+    pub message: String,
+    /// Primary result code of the error
+    pub code: Option<i32>,
+    /// Extended result code of the error
+    pub extended_code: Option<i32>,
+    /// Byte offset within the query of the token causing the error
+    pub offset: Option<usize>,
+}
+
+impl Display for DatabaseQueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.extended_code {
+            Some(code) => write!(f, "{} (code {code})", self.message),
+            None => self.message.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseQueryError {}
+
 #[derive(Debug, Clone)]
 pub struct DatabaseRow {
-    pub values: Vec<SharedString>,
+    pub values: Vec<DatabaseValue>,
 }
 
 #[derive(Debug, Clone)]
@@ -51,6 +172,173 @@ pub struct DatabaseTableQuery {
     pub table: String,
 }
 
+/// Value stored in a database column, mirroring the SQLite storage classes
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatabaseValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Display for DatabaseValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DatabaseValue::Null => f.write_str("NULL"),
+            DatabaseValue::Integer(value) => value.fmt(f),
+            DatabaseValue::Real(value) => value.fmt(f),
+            DatabaseValue::Text(value) => write!(f, "{value:?}"),
+            DatabaseValue::Blob(value) => write!(f, "{value:?}"),
+        }
+    }
+}
+
+/// Receiver for rows streamed from a query, allows processing the rows of
+/// a query without collecting the entire result into memory
+pub trait DatabaseRowSink: Send {
+    /// Called once with the result column names before any rows
+    fn columns(&mut self, columns: &[String]) -> anyhow::Result<()>;
+
+    /// Called for each row within the result
+    fn row(&mut self, values: &[DatabaseValue]) -> anyhow::Result<()>;
+
+    /// Called once after the last row
+    fn finish(&mut self) -> anyhow::Result<()>;
+}
+
+/// Stream the rows of an already loaded query `result` into the `sink`
+pub fn stream_query_result(
+    result: &DatabaseQueryResult,
+    sink: &mut dyn DatabaseRowSink,
+) -> anyhow::Result<u64> {
+    let columns: Vec<String> = result
+        .column_names
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+
+    sink.columns(&columns)?;
+
+    for row in &result.rows {
+        sink.row(&row.values)?;
+    }
+
+    sink.finish()?;
+
+    Ok(result.rows.len() as u64)
+}
+
+/// Rows of an insert request, read lazily as the rows are inserted
+pub type DatabaseInsertRows = Box<dyn Iterator<Item = anyhow::Result<DatabaseInsertRow>> + Send>;
+
+/// Request to insert a collection of rows into a table
+pub struct DatabaseInsertRequest {
+    /// Name of the table to insert into
+    pub table: String,
+    /// Optional SQL to create the table before inserting
+    pub create_sql: Option<String>,
+    /// Columns the row values are inserted into
+    pub columns: Vec<String>,
+    /// Rows to insert
+    pub rows: DatabaseInsertRows,
+    /// Total number of rows to insert
+    pub total: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseInsertRow {
+    /// Line or record number the row originated from, used when
+    /// reporting errors back to the user
+    pub source: usize,
+    /// Values for each of the request columns
+    pub values: Vec<DatabaseValue>,
+}
+
+/// Progress of an insert request
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatabaseInsertProgress {
+    /// Number of rows that have been processed
+    pub processed: usize,
+    /// Total number of rows to process
+    pub total: usize,
+}
+
+/// Outcome of an insert request
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseInsertReport {
+    /// Number of rows that were inserted
+    pub inserted: usize,
+    /// Rows that failed to insert
+    pub errors: Vec<DatabaseInsertRowError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseInsertRowError {
+    /// Line or record number of the row that failed
+    pub source: usize,
+    /// Error message from the database
+    pub message: String,
+}
+
+/// Statement within a SQL script being executed
+#[derive(Debug, Clone)]
+pub struct DatabaseScriptStatement {
+    /// SQL of the statement
+    pub sql: String,
+    /// Line within the script the statement starts on
+    pub line: usize,
+    /// Number of bytes of the script read up to and including this statement
+    pub bytes_read: u64,
+}
+
+/// Statements of a script, read lazily as the script executes
+pub type DatabaseScriptStatements =
+    Box<dyn Iterator<Item = anyhow::Result<DatabaseScriptStatement>> + Send>;
+
+/// Request to execute a script of statements against the database
+pub struct DatabaseScriptRequest {
+    /// Statements to execute
+    pub statements: DatabaseScriptStatements,
+    /// Total size of the script in bytes
+    pub total_bytes: u64,
+    /// Whether to stop executing at the first failing statement
+    pub stop_on_error: bool,
+    /// Whether to execute the script within a transaction, the transaction
+    /// is rolled back when stopping due to an error
+    pub transaction: bool,
+}
+
+/// Progress of a script execution
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatabaseScriptProgress {
+    /// Number of bytes of the script read
+    pub bytes_read: u64,
+    /// Total size of the script in bytes
+    pub total_bytes: u64,
+    /// Number of statements executed (Including failed statements)
+    pub statements: usize,
+}
+
+/// Outcome of a script execution
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseScriptReport {
+    /// Number of statements that executed successfully
+    pub executed: usize,
+    /// Statements that failed to execute
+    pub errors: Vec<DatabaseScriptError>,
+    /// Whether the transaction was rolled back
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseScriptError {
+    /// Line within the script of the statement that failed
+    pub line: usize,
+    /// Error message from the database
+    pub message: String,
+}
+
 pub struct PragmaDefinition {
     pub name: &'static str,
     pub url: &'static str,
@@ -81,6 +369,28 @@ pub trait Database: Send + Sync + 'static {
     /// List tables within the database
     async fn database_tables(&self) -> anyhow::Result<Vec<DatabaseTable>>;
 
+    /// List the indexes, views and triggers within the database, excludes
+    /// indexes created automatically for constraints
+    async fn database_objects(&self) -> anyhow::Result<Vec<DatabaseObject>>;
+
+    /// List the names of the schemas within the database connection (main,
+    /// temp and any attached databases)
+    async fn database_schemas(&self) -> anyhow::Result<Vec<String>>;
+
+    /// List the tables and views of the temp and attached schemas
+    async fn database_schema_tables(&self) -> anyhow::Result<Vec<DatabaseSchemaTable>>;
+
+    /// List the functions available on the database connection, one entry
+    /// for each supported number of arguments
+    async fn database_functions(&self) -> anyhow::Result<Vec<DatabaseFunction>>;
+
+    /// Prepare each of the `statements` without executing them, returning
+    /// the error message for the statements that failed to prepare
+    async fn prepare_statements(
+        &self,
+        statements: Vec<String>,
+    ) -> anyhow::Result<Vec<Option<String>>>;
+
     /// Perform a query against the database
     async fn query(&self, query: &str) -> anyhow::Result<DatabaseQueryResult>;
 
@@ -94,4 +404,29 @@ pub trait Database: Send + Sync + 'static {
 
     /// Query the total number of rows within a table
     async fn query_table_rows_count(&self, query: DatabaseTableQuery) -> anyhow::Result<i64>;
+
+    /// Perform a query against the database streaming the resulting rows
+    /// into the `sink`, returns the number of rows streamed
+    async fn stream_query(
+        &self,
+        query: &str,
+        sink: Box<dyn DatabaseRowSink>,
+    ) -> anyhow::Result<u64>;
+
+    /// Insert rows into a table within a single transaction, rows that fail
+    /// to insert are reported and skipped rather than aborting the insert,
+    /// failing to read the rows rolls back the entire insert
+    async fn insert_rows(
+        &self,
+        request: DatabaseInsertRequest,
+        progress: watch::Sender<DatabaseInsertProgress>,
+    ) -> anyhow::Result<DatabaseInsertReport>;
+
+    /// Execute a script of statements one by one, failing statements are
+    /// reported and either skipped or stop the execution
+    async fn execute_script(
+        &self,
+        request: DatabaseScriptRequest,
+        progress: watch::Sender<DatabaseScriptProgress>,
+    ) -> anyhow::Result<DatabaseScriptReport>;
 }
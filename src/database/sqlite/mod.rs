@@ -1,13 +1,19 @@
 use crate::database::{
-    Database, DatabaseOptions, DatabaseQueryResult, DatabaseRow, DatabaseTable,
-    DatabaseTableColumn, DatabaseTableQuery,
+    Database, DatabaseForeignKey, DatabaseFunction, DatabaseInsertProgress, DatabaseInsertReport,
+    DatabaseInsertRequest, DatabaseInsertRowError, DatabaseObject, DatabaseObjectKind,
+    DatabaseOptions, DatabaseQueryError, DatabaseQueryResult, DatabaseRow, DatabaseRowSink,
+    DatabaseSchemaTable, DatabaseScriptError, DatabaseScriptProgress, DatabaseScriptReport,
+    DatabaseScriptRequest, DatabaseTable, DatabaseTableColumn, DatabaseTableQuery, DatabaseValue,
 };
 use async_trait::async_trait;
 use gpui::SharedString;
 use itertools::Itertools;
-use std::{any::Any, path::Path, rc::Rc};
-use tokio::sync::{Mutex, MutexGuard};
-use tokio_rusqlite::{Connection, OpenFlags, params, rusqlite, types::ValueRef};
+use std::{any::Any, path::Path, rc::Rc, time::Instant};
+use tokio::sync::{Mutex, MutexGuard, watch};
+use tokio_rusqlite::{
+    Connection, OpenFlags, params, params_from_iter, rusqlite,
+    types::{ToSql, ToSqlOutput, ValueRef},
+};
 
 pub mod pragma;
 
@@ -48,6 +54,11 @@ impl SqliteDatabase {
                 .await?;
         }
 
+        let full_path = std::path::absolute(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .to_string_lossy()
+            .to_string();
+
         let path = path
             .file_name()
             .map(|value| value.to_string_lossy().to_string())
@@ -57,6 +68,7 @@ impl SqliteDatabase {
             connection: Mutex::new(connection),
             options: DatabaseOptions {
                 path,
+                full_path: Some(full_path),
                 readonly,
                 encrypted,
             },
@@ -87,7 +99,7 @@ fn query_table_columns(
     table_name: &str,
 ) -> Result<Vec<DatabaseTableColumn>, rusqlite::Error> {
     let mut statement = connection.prepare(
-        r#"SELECT "name", "type", "notnull", "pk"  FROM pragma_table_info(?1) ORDER BY "cid""#,
+        r#"SELECT "name", "type", "notnull", "pk", "dflt_value" FROM pragma_table_info(?1) ORDER BY "cid""#,
     )?;
 
     let results: Vec<DatabaseTableColumn> = statement
@@ -97,6 +109,92 @@ fn query_table_columns(
                 column_type: row.get(1)?,
                 not_null: row.get(2)?,
                 primary_key: row.get(3)?,
+                default_value: row.get(4)?,
+            })
+        })?
+        .try_collect()?;
+
+    Ok(results)
+}
+
+fn query_table_foreign_keys(
+    connection: &mut rusqlite::Connection,
+    table_name: &str,
+) -> Result<Vec<DatabaseForeignKey>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        r#"SELECT "from", "table", "to" FROM pragma_foreign_key_list(?1) ORDER BY "id", "seq""#,
+    )?;
+
+    let results: Vec<DatabaseForeignKey> = statement
+        .query_map(params![table_name], |row| {
+            Ok(DatabaseForeignKey {
+                from: row.get(0)?,
+                table: row.get(1)?,
+                to: row.get(2)?,
+            })
+        })?
+        .try_collect()?;
+
+    Ok(results)
+}
+
+fn query_objects(
+    connection: &mut rusqlite::Connection,
+) -> Result<Vec<DatabaseObject>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        r#"
+        SELECT "type", "name", "tbl_name", "sql"
+        FROM sqlite_master
+        WHERE type IN ('index', 'view', 'trigger') AND sql IS NOT NULL
+        ORDER BY "name"
+        "#,
+    )?;
+
+    let results: Vec<DatabaseObject> = statement
+        .query_map(params![], |row| {
+            let kind: String = row.get(0)?;
+            let kind = match kind.as_str() {
+                "index" => DatabaseObjectKind::Index,
+                "view" => DatabaseObjectKind::View,
+                _ => DatabaseObjectKind::Trigger,
+            };
+
+            Ok(DatabaseObject {
+                kind,
+                name: row.get(1)?,
+                table_name: row.get(2)?,
+                sql: row.get(3)?,
+                columns: Vec::new(),
+            })
+        })?
+        .try_collect()?;
+
+    Ok(results)
+}
+
+fn query_schemas(connection: &mut rusqlite::Connection) -> Result<Vec<String>, rusqlite::Error> {
+    let mut statement =
+        connection.prepare(r#"SELECT "name" FROM pragma_database_list ORDER BY "seq""#)?;
+
+    let results: Vec<String> = statement
+        .query_map(params![], |row| row.get(0))?
+        .try_collect()?;
+
+    Ok(results)
+}
+
+fn query_functions(
+    connection: &mut rusqlite::Connection,
+) -> Result<Vec<DatabaseFunction>, rusqlite::Error> {
+    let mut statement = connection.prepare(
+        r#"SELECT DISTINCT "name", "narg" FROM pragma_function_list ORDER BY "name", "narg""#,
+    )?;
+
+    let results: Vec<DatabaseFunction> = statement
+        .query_map(params![], |row| {
+            Ok(DatabaseFunction {
+                name: row.get(0)?,
+                arguments: row.get(1)?,
             })
         })?
         .try_collect()?;
@@ -104,6 +202,51 @@ fn query_table_columns(
     Ok(results)
 }
 
+/// Query the tables and views within the sqlite_master of each schema other
+/// than main
+fn query_schema_tables(
+    connection: &mut rusqlite::Connection,
+) -> Result<Vec<DatabaseSchemaTable>, rusqlite::Error> {
+    let mut results = Vec::new();
+
+    for schema in query_schemas(connection)? {
+        if schema == "main" {
+            continue;
+        }
+
+        let mut statement = connection.prepare(&format!(
+            r#"
+            SELECT "type", "name"
+            FROM {}.sqlite_master
+            WHERE type IN ('table', 'view') AND name NOT LIKE 'sqlite_%'
+            ORDER BY "name"
+            "#,
+            quote_identifier(&schema)
+        ))?;
+
+        let tables: Vec<DatabaseSchemaTable> = statement
+            .query_map(params![], |row| {
+                let kind: String = row.get(0)?;
+
+                Ok(DatabaseSchemaTable {
+                    schema: schema.clone(),
+                    name: row.get(1)?,
+                    view: kind == "view",
+                })
+            })?
+            .try_collect()?;
+
+        results.extend(tables);
+    }
+
+    Ok(results)
+}
+
+/// Quote an identifier (table or column name) for use within a SQL statement
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
 fn query_tables(
     connection: &mut rusqlite::Connection,
 ) -> Result<Vec<DatabaseTable>, rusqlite::Error> {
@@ -122,6 +265,7 @@ fn query_tables(
                 name: row.get(0)?,
                 sql: row.get(1)?,
                 columns: Vec::new(),
+                foreign_keys: Vec::new(),
             })
         })?
         .try_collect()?;
@@ -149,6 +293,9 @@ impl Database for SqliteDatabase {
                 for table in &mut tables {
                     let columns = query_table_columns(connection, &table.name)?;
                     table.columns = columns;
+
+                    let foreign_keys = query_table_foreign_keys(connection, &table.name)?;
+                    table.foreign_keys = foreign_keys;
                 }
 
                 Ok::<_, rusqlite::Error>(tables)
@@ -158,13 +305,84 @@ impl Database for SqliteDatabase {
         Ok(result)
     }
 
+    async fn database_objects(&self) -> anyhow::Result<Vec<DatabaseObject>> {
+        let connection = self.connection.lock().await;
+
+        let result = connection
+            .call(|connection| {
+                let mut objects = query_objects(connection)?;
+
+                for object in &mut objects {
+                    if object.kind == DatabaseObjectKind::View {
+                        object.columns = query_table_columns(connection, &object.name)?;
+                    }
+                }
+
+                Ok::<_, rusqlite::Error>(objects)
+            })
+            .await?;
+
+        Ok(result)
+    }
+
+    async fn database_schemas(&self) -> anyhow::Result<Vec<String>> {
+        let connection = self.connection.lock().await;
+
+        let result = connection.call(query_schemas).await?;
+
+        Ok(result)
+    }
+
+    async fn database_schema_tables(&self) -> anyhow::Result<Vec<DatabaseSchemaTable>> {
+        let connection = self.connection.lock().await;
+
+        let result = connection.call(query_schema_tables).await?;
+
+        Ok(result)
+    }
+
+    async fn database_functions(&self) -> anyhow::Result<Vec<DatabaseFunction>> {
+        let connection = self.connection.lock().await;
+
+        let result = connection.call(query_functions).await?;
+
+        Ok(result)
+    }
+
+    async fn prepare_statements(
+        &self,
+        statements: Vec<String>,
+    ) -> anyhow::Result<Vec<Option<String>>> {
+        let connection = self.connection.lock().await;
+
+        let result = connection
+            .call(move |connection| {
+                let errors = statements
+                    .iter()
+                    .map(|statement| {
+                        let error = connection.prepare(statement).err();
+                        error.map(|error| error.to_string())
+                    })
+                    .collect();
+
+                Ok::<_, rusqlite::Error>(errors)
+            })
+            .await?;
+
+        Ok(result)
+    }
+
     async fn query(&self, query: &str) -> anyhow::Result<DatabaseQueryResult> {
         let connection = self.connection.lock().await;
         let query = query.to_string();
 
         let result = connection
             .call(move |connection| {
+                let start = Instant::now();
+                let total_changes = connection.total_changes();
+
                 let mut statement = connection.prepare(&query)?;
+                let readonly = statement.readonly();
 
                 // Collect the available column names
                 let column_names: Vec<SharedString> = statement
@@ -180,8 +398,7 @@ impl Database for SqliteDatabase {
 
                     for i in 0..column_count {
                         let value = row.get_ref(i)?;
-                        let value = value_to_string(value);
-                        values.push(value.into());
+                        values.push(value_to_database_value(value));
                     }
 
                     Ok(DatabaseRow { values })
@@ -193,9 +410,27 @@ impl Database for SqliteDatabase {
                     rows.push(row_result?);
                 }
 
-                Ok::<_, rusqlite::Error>(DatabaseQueryResult { column_names, rows })
+                // The changes of the last insert, update or delete are kept when
+                // other statements run, so only use them when this query made any
+                let changes = match connection.total_changes() != total_changes {
+                    true => connection.changes(),
+                    false => 0,
+                };
+
+                Ok::<_, rusqlite::Error>(DatabaseQueryResult {
+                    column_names,
+                    rows,
+                    elapsed: start.elapsed(),
+                    changes,
+                    last_insert_rowid: connection.last_insert_rowid(),
+                    readonly,
+                })
             })
-            .await?;
+            .await
+            .map_err(|error| match error {
+                tokio_rusqlite::Error::Error(error) => query_error(error).into(),
+                error => anyhow::Error::from(error),
+            })?;
 
         Ok(result)
     }
@@ -226,21 +461,335 @@ impl Database for SqliteDatabase {
 
         Ok(count)
     }
+
+    async fn stream_query(
+        &self,
+        query: &str,
+        mut sink: Box<dyn DatabaseRowSink>,
+    ) -> anyhow::Result<u64> {
+        let connection = self.connection.lock().await;
+        let query = query.to_string();
+
+        let count = connection
+            .call(move |connection| {
+                let mut statement = connection.prepare(&query)?;
+
+                let column_names: Vec<String> = statement
+                    .column_names()
+                    .into_iter()
+                    .map(|value| value.to_string())
+                    .collect();
+                let column_count = column_names.len();
+
+                sink.columns(&column_names)?;
+
+                let mut rows = statement.query(params![])?;
+                let mut values = Vec::with_capacity(column_count);
+                let mut count: u64 = 0;
+
+                while let Some(row) = rows.next()? {
+                    values.clear();
+
+                    for i in 0..column_count {
+                        values.push(value_to_database_value(row.get_ref(i)?));
+                    }
+
+                    sink.row(&values)?;
+                    count += 1;
+                }
+
+                sink.finish()?;
+
+                Ok::<_, anyhow::Error>(count)
+            })
+            .await
+            .map_err(call_error)?;
+
+        Ok(count)
+    }
+
+    async fn insert_rows(
+        &self,
+        request: DatabaseInsertRequest,
+        progress: watch::Sender<DatabaseInsertProgress>,
+    ) -> anyhow::Result<DatabaseInsertReport> {
+        let connection = self.connection.lock().await;
+
+        let report = connection
+            .call(move |connection| {
+                let DatabaseInsertRequest {
+                    table,
+                    create_sql,
+                    columns,
+                    rows,
+                    total,
+                } = request;
+
+                // Dropping the transaction without committing rolls it back
+                let transaction = connection.transaction()?;
+
+                if let Some(create_sql) = &create_sql {
+                    transaction.execute_batch(create_sql)?;
+                }
+
+                let sql = format!(
+                    "INSERT INTO {table} ({columns}) VALUES ({values})",
+                    table = quote_identifier(&table),
+                    columns = columns
+                        .iter()
+                        .map(|column| quote_identifier(column))
+                        .join(", "),
+                    values = (1..=columns.len())
+                        .map(|index| format!("?{index}"))
+                        .join(", "),
+                );
+
+                let mut report = DatabaseInsertReport::default();
+
+                {
+                    let mut statement = transaction.prepare(&sql)?;
+
+                    for (index, row) in rows.enumerate() {
+                        let row = row?;
+
+                        match statement.execute(params_from_iter(row.values.iter())) {
+                            Ok(_) => report.inserted += 1,
+                            Err(error) => report.errors.push(DatabaseInsertRowError {
+                                source: row.source,
+                                message: error.to_string(),
+                            }),
+                        }
+
+                        progress.send_replace(DatabaseInsertProgress {
+                            processed: index + 1,
+                            total: total.max(index + 1),
+                        });
+                    }
+                }
+
+                transaction.commit()?;
+
+                Ok::<_, anyhow::Error>(report)
+            })
+            .await
+            .map_err(call_error)?;
+
+        Ok(report)
+    }
+
+    async fn execute_script(
+        &self,
+        request: DatabaseScriptRequest,
+        progress: watch::Sender<DatabaseScriptProgress>,
+    ) -> anyhow::Result<DatabaseScriptReport> {
+        let connection = self.connection.lock().await;
+
+        let report = connection
+            .call(move |connection| {
+                let DatabaseScriptRequest {
+                    statements,
+                    total_bytes,
+                    stop_on_error,
+                    transaction,
+                } = request;
+
+                if transaction {
+                    connection.execute_batch("BEGIN")?;
+                }
+
+                let mut report = DatabaseScriptReport::default();
+                let mut statements_count = 0;
+                let mut failed = false;
+
+                for statement in statements {
+                    let statement = match statement {
+                        Ok(value) => value,
+                        Err(error) => {
+                            // Failed to read the script, undo the partial execution
+                            if transaction && !connection.is_autocommit() {
+                                connection.execute_batch("ROLLBACK")?;
+                            }
+
+                            return Err(error);
+                        }
+                    };
+
+                    match connection.execute_batch(&statement.sql) {
+                        Ok(_) => report.executed += 1,
+                        Err(error) => {
+                            report.errors.push(DatabaseScriptError {
+                                line: statement.line,
+                                message: error.to_string(),
+                            });
+                            failed = true;
+                        }
+                    }
+
+                    // The rest of the script would run outside of the transaction
+                    // and could no longer be rolled back
+                    if transaction && connection.is_autocommit() {
+                        anyhow::bail!(
+                            "the transaction of the script was ended by the statement on line {}",
+                            statement.line
+                        );
+                    }
+
+                    statements_count += 1;
+                    progress.send_replace(DatabaseScriptProgress {
+                        bytes_read: statement.bytes_read,
+                        total_bytes,
+                        statements: statements_count,
+                    });
+
+                    if failed && stop_on_error {
+                        break;
+                    }
+                }
+
+                // The script itself may have ended the transaction
+                if transaction && !connection.is_autocommit() {
+                    if failed && stop_on_error {
+                        connection.execute_batch("ROLLBACK")?;
+                        report.rolled_back = true;
+                    } else {
+                        connection.execute_batch("COMMIT")?;
+                    }
+                }
+
+                Ok::<_, anyhow::Error>(report)
+            })
+            .await
+            .map_err(call_error)?;
+
+        Ok(report)
+    }
+}
+
+impl ToSql for DatabaseValue {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(ToSqlOutput::Borrowed(match self {
+            DatabaseValue::Null => ValueRef::Null,
+            DatabaseValue::Integer(value) => ValueRef::Integer(*value),
+            DatabaseValue::Real(value) => ValueRef::Real(*value),
+            DatabaseValue::Text(value) => ValueRef::Text(value.as_bytes()),
+            DatabaseValue::Blob(value) => ValueRef::Blob(value),
+        }))
+    }
 }
 
-fn value_to_string(value: ValueRef<'_>) -> String {
+fn value_to_database_value(value: ValueRef<'_>) -> DatabaseValue {
     match value {
-        ValueRef::Null => "NULL".to_string(),
-        ValueRef::Integer(value) => value.to_string(),
-        ValueRef::Real(value) => value.to_string(),
-        ValueRef::Text(items) => {
-            let value = match std::str::from_utf8(items) {
-                Ok(value) => value,
-                Err(err) => return format!("Failed to decode Text column: {err}"),
-            };
+        ValueRef::Null => DatabaseValue::Null,
+        ValueRef::Integer(value) => DatabaseValue::Integer(value),
+        ValueRef::Real(value) => DatabaseValue::Real(value),
+        ValueRef::Text(items) => DatabaseValue::Text(String::from_utf8_lossy(items).to_string()),
+        ValueRef::Blob(items) => DatabaseValue::Blob(items.to_vec()),
+    }
+}
 
-            format!("{:?}", value)
-        }
-        ValueRef::Blob(items) => format!("{:?}", items),
+/// Flatten an error from a connection call that produced an [anyhow::Error]
+fn call_error(error: tokio_rusqlite::Error<anyhow::Error>) -> anyhow::Error {
+    match error {
+        tokio_rusqlite::Error::Error(error) => error,
+        error => anyhow::anyhow!("{error}"),
+    }
+}
+
+/// Convert an error from executing a query into a [DatabaseQueryError]
+/// keeping the error codes and offset of the error
+fn query_error(error: rusqlite::Error) -> DatabaseQueryError {
+    match error {
+        rusqlite::Error::SqlInputError {
+            error, msg, offset, ..
+        } => DatabaseQueryError {
+            message: msg,
+            code: Some(error.extended_code & 0xff),
+            extended_code: Some(error.extended_code),
+            offset: usize::try_from(offset).ok(),
+        },
+        rusqlite::Error::SqliteFailure(error, msg) => DatabaseQueryError {
+            message: msg.unwrap_or_else(|| error.to_string()),
+            code: Some(error.extended_code & 0xff),
+            extended_code: Some(error.extended_code),
+            offset: None,
+        },
+        error => DatabaseQueryError {
+            message: error.to_string(),
+            code: None,
+            extended_code: None,
+            offset: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use tokio::sync::watch;
+    use tokio_rusqlite::rusqlite::Connection;
+
+    use super::{SqliteDatabase, query_schema_tables};
+    use crate::{
+        database::{Database, DatabaseSchemaTable, DatabaseScriptRequest},
+        import::sql::SqlFileStatements,
+    };
+
+    #[test]
+    fn test_query_schema_tables() {
+        let mut connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE main_table (a);
+                ATTACH ':memory:' AS aux;
+                CREATE TABLE aux.users (id);
+                CREATE VIEW aux.active_users AS SELECT * FROM users;
+                CREATE TEMP TABLE scratch (b);",
+            )
+            .unwrap();
+
+        let tables = query_schema_tables(&mut connection).unwrap();
+        let table = |schema: &str, name: &str, view: bool| DatabaseSchemaTable {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            view,
+        };
+
+        assert_eq!(
+            tables,
+            [
+                table("temp", "scratch", false),
+                table("aux", "active_users", true),
+                table("aux", "users", false),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_script_ending_transaction() {
+        let database = SqliteDatabase::memory().await.unwrap();
+        let script =
+            "CREATE TABLE a (id);\nINSERT INTO a VALUES (1);\nCOMMIT;\nINSERT INTO a VALUES (2);";
+        let (progress, _) = watch::channel(Default::default());
+
+        let result = database
+            .execute_script(
+                DatabaseScriptRequest {
+                    statements: Box::new(SqlFileStatements::new(Cursor::new(script))),
+                    total_bytes: script.len() as u64,
+                    stop_on_error: true,
+                    transaction: true,
+                },
+                progress,
+            )
+            .await;
+
+        let error = result
+            .err()
+            .expect("ending the transaction fails the script");
+        assert!(error.to_string().contains("line 3"), "{error}");
+
+        let rows = database.query("SELECT count(*) FROM a").await.unwrap().rows;
+        assert_eq!(rows[0].values, [DatabaseValue::Integer(1)]);
     }
 }
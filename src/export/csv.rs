@@ -0,0 +1,174 @@
+//! Writing of query results to CSV files
+
+use std::io::Write;
+
+use crate::{
+    database::{DatabaseRowSink, DatabaseValue},
+    export::{BlobEncoding, encode_blob},
+};
+
+/// When fields should be quoted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvQuoting {
+    /// Only quote fields containing the delimiter, quotes or new lines
+    #[default]
+    Necessary,
+    /// Quote every field
+    Always,
+    /// Quote every field that isn't numeric
+    NonNumeric,
+    /// Never quote fields
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvExportOptions {
+    /// Character separating the fields of a record
+    pub delimiter: u8,
+    /// When fields should be quoted
+    pub quoting: CsvQuoting,
+    /// Whether to write a header record with the column names
+    pub header: bool,
+    /// Text written for NULL values
+    pub null_value: String,
+    /// Line ending written after each record
+    pub line_ending: LineEnding,
+    /// Encoding used for blob values
+    pub blob_encoding: BlobEncoding,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quoting: CsvQuoting::default(),
+            header: true,
+            null_value: String::new(),
+            line_ending: LineEnding::default(),
+            blob_encoding: BlobEncoding::default(),
+        }
+    }
+}
+
+/// [DatabaseRowSink] writing the rows as CSV records
+pub struct CsvExporter<W: Write> {
+    writer: csv::Writer<W>,
+    options: CsvExportOptions,
+}
+
+impl<W: Write> CsvExporter<W> {
+    pub fn new(writer: W, options: CsvExportOptions) -> Self {
+        let writer = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .quote_style(match options.quoting {
+                CsvQuoting::Necessary => csv::QuoteStyle::Necessary,
+                CsvQuoting::Always => csv::QuoteStyle::Always,
+                CsvQuoting::NonNumeric => csv::QuoteStyle::NonNumeric,
+                CsvQuoting::Never => csv::QuoteStyle::Never,
+            })
+            .terminator(match options.line_ending {
+                LineEnding::Lf => csv::Terminator::Any(b'\n'),
+                LineEnding::CrLf => csv::Terminator::CRLF,
+            })
+            .from_writer(writer);
+
+        Self { writer, options }
+    }
+
+    fn field(&self, value: &DatabaseValue) -> String {
+        match value {
+            DatabaseValue::Null => self.options.null_value.clone(),
+            DatabaseValue::Integer(value) => value.to_string(),
+            // Debug formatting keeps the fraction (1.0 rather than 1) so
+            // reals are read back as reals
+            DatabaseValue::Real(value) => format!("{value:?}"),
+            DatabaseValue::Text(value) => value.clone(),
+            DatabaseValue::Blob(value) => encode_blob(value, self.options.blob_encoding),
+        }
+    }
+}
+
+impl<W: Write + Send> DatabaseRowSink for CsvExporter<W> {
+    fn columns(&mut self, columns: &[String]) -> anyhow::Result<()> {
+        if self.options.header {
+            self.writer.write_record(columns)?;
+        }
+
+        Ok(())
+    }
+
+    fn row(&mut self, values: &[DatabaseValue]) -> anyhow::Result<()> {
+        let record: Vec<String> = values.iter().map(|value| self.field(value)).collect();
+        self.writer.write_record(&record)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CsvExportOptions, CsvExporter, LineEnding};
+    use crate::{
+        database::{DatabaseRowSink, DatabaseValue},
+        export::BlobEncoding,
+    };
+
+    #[test]
+    fn test_csv_exporter() {
+        let mut output = Vec::new();
+
+        {
+            let mut exporter = CsvExporter::new(
+                &mut output,
+                CsvExportOptions {
+                    null_value: "NULL".to_string(),
+                    line_ending: LineEnding::CrLf,
+                    blob_encoding: BlobEncoding::Base64,
+                    ..Default::default()
+                },
+            );
+
+            exporter
+                .columns(&["id".to_string(), "name".to_string(), "data".to_string()])
+                .unwrap();
+            exporter
+                .row(&[
+                    DatabaseValue::Integer(1),
+                    DatabaseValue::Text("a, b".to_string()),
+                    DatabaseValue::Blob(vec![1, 2, 3]),
+                ])
+                .unwrap();
+            exporter
+                .row(&[
+                    DatabaseValue::Real(2.5),
+                    DatabaseValue::Null,
+                    DatabaseValue::Null,
+                ])
+                .unwrap();
+            exporter
+                .row(&[
+                    DatabaseValue::Real(1.0),
+                    DatabaseValue::Real(1e20),
+                    DatabaseValue::Real(-0.1),
+                ])
+                .unwrap();
+            exporter.finish().unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "id,name,data\r\n1,\"a, b\",AQID\r\n2.5,NULL,NULL\r\n1.0,1e20,-0.1\r\n"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Writing of query results to JSON and newline delimited JSON files
+
+use std::{collections::HashSet, io::Write};
+
+use serde_json::Value;
+
+use crate::{
+    database::{DatabaseRowSink, DatabaseValue},
+    export::{BlobEncoding, encode_blob},
+};
+
+/// Layout of the exported JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonFormat {
+    /// Single JSON array containing an object for each row
+    #[default]
+    Array,
+    /// Newline delimited JSON, one object per line
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct JsonExportOptions {
+    /// Layout of the exported JSON
+    pub format: JsonFormat,
+    /// Whether text values containing valid JSON objects or arrays should be
+    /// written as nested values rather than strings
+    pub parse_json_text: bool,
+    /// Encoding used for blob values
+    pub blob_encoding: BlobEncoding,
+}
+
+/// [DatabaseRowSink] writing each row as a JSON object keyed by column name
+pub struct JsonExporter<W: Write> {
+    writer: W,
+    options: JsonExportOptions,
+    /// Unique keys for the columns of the rows
+    columns: Vec<String>,
+    /// Number of rows written so far
+    written: u64,
+}
+
+impl<W: Write> JsonExporter<W> {
+    pub fn new(writer: W, options: JsonExportOptions) -> Self {
+        Self {
+            writer,
+            options,
+            columns: Vec::new(),
+            written: 0,
+        }
+    }
+
+    fn value(&self, value: &DatabaseValue) -> Value {
+        match value {
+            DatabaseValue::Null => Value::Null,
+            DatabaseValue::Integer(value) => Value::from(*value),
+            // Non finite values have no JSON representation and become null
+            DatabaseValue::Real(value) => Value::from(*value),
+            DatabaseValue::Text(value) => {
+                if self.options.parse_json_text
+                    && let Some(value) = parse_json_text(value)
+                {
+                    return value;
+                }
+
+                Value::String(value.clone())
+            }
+            DatabaseValue::Blob(value) => {
+                Value::String(encode_blob(value, self.options.blob_encoding))
+            }
+        }
+    }
+}
+
+/// Parse `value` as JSON if it contains a JSON object or array, scalar JSON
+/// text is left as a string to avoid turning strings like "1" into numbers
+fn parse_json_text(value: &str) -> Option<Value> {
+    let trimmed = value.trim_start();
+    if !trimmed.starts_with(['{', '[']) {
+        return None;
+    }
+
+    serde_json::from_str(value).ok()
+}
+
+/// Make the `columns` usable as object keys, later duplicates of a name
+/// (e.g. from `SELECT a.id, b.id`) get a numbered suffix ("id", "id_2")
+fn unique_keys(columns: &[String]) -> Vec<String> {
+    let mut used: HashSet<String> = columns.iter().cloned().collect();
+    let mut seen = HashSet::new();
+
+    columns
+        .iter()
+        .map(|column| {
+            if seen.insert(column) {
+                return column.clone();
+            }
+
+            let key = (2..)
+                .map(|index| format!("{column}_{index}"))
+                .find(|key| !used.contains(key))
+                .expect("unbounded suffixes always find an unused key");
+            used.insert(key.clone());
+            key
+        })
+        .collect()
+}
+
+impl<W: Write + Send> DatabaseRowSink for JsonExporter<W> {
+    fn columns(&mut self, columns: &[String]) -> anyhow::Result<()> {
+        self.columns = unique_keys(columns);
+
+        if self.options.format == JsonFormat::Array {
+            self.writer.write_all(b"[")?;
+        }
+
+        Ok(())
+    }
+
+    fn row(&mut self, values: &[DatabaseValue]) -> anyhow::Result<()> {
+        match self.options.format {
+            JsonFormat::Array if self.written > 0 => self.writer.write_all(b",\n")?,
+            JsonFormat::Array => self.writer.write_all(b"\n")?,
+            JsonFormat::Ndjson => {}
+        }
+
+        // Objects are written field by field to preserve the column order
+        self.writer.write_all(b"{")?;
+
+        for (index, (column, value)) in self.columns.iter().zip(values).enumerate() {
+            if index > 0 {
+                self.writer.write_all(b",")?;
+            }
+
+            let value = self.value(value);
+
+            serde_json::to_writer(&mut self.writer, column)?;
+            self.writer.write_all(b":")?;
+            serde_json::to_writer(&mut self.writer, &value)?;
+        }
+
+        self.writer.write_all(b"}")?;
+
+        if self.options.format == JsonFormat::Ndjson {
+            self.writer.write_all(b"\n")?;
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.options.format == JsonFormat::Array {
+            self.writer.write_all(b"\n]\n")?;
+        }
+
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JsonExportOptions, JsonExporter, JsonFormat, unique_keys};
+    use crate::{
+        database::{DatabaseRowSink, DatabaseValue},
+        export::BlobEncoding,
+    };
+
+    fn export(options: JsonExportOptions, rows: &[Vec<DatabaseValue>]) -> String {
+        let mut output = Vec::new();
+
+        {
+            let mut exporter = JsonExporter::new(&mut output, options);
+            exporter
+                .columns(&["id".to_string(), "data".to_string()])
+                .unwrap();
+            for row in rows {
+                exporter.row(row).unwrap();
+            }
+            exporter.finish().unwrap();
+        }
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_json_exporter() {
+        let rows = vec![
+            vec![
+                DatabaseValue::Integer(1),
+                DatabaseValue::Text("{\"a\": [1, 2]}".to_string()),
+            ],
+            vec![DatabaseValue::Real(2.5), DatabaseValue::Blob(vec![1, 2, 3])],
+            vec![DatabaseValue::Null, DatabaseValue::Text("12".to_string())],
+        ];
+
+        assert_eq!(
+            export(JsonExportOptions::default(), &rows),
+            "[\n{\"id\":1,\"data\":\"{\\\"a\\\": [1, 2]}\"},\n\
+             {\"id\":2.5,\"data\":\"010203\"},\n\
+             {\"id\":null,\"data\":\"12\"}\n]\n"
+        );
+
+        assert_eq!(
+            export(
+                JsonExportOptions {
+                    format: JsonFormat::Ndjson,
+                    parse_json_text: true,
+                    blob_encoding: BlobEncoding::Base64,
+                },
+                &rows
+            ),
+            "{\"id\":1,\"data\":{\"a\":[1,2]}}\n\
+             {\"id\":2.5,\"data\":\"AQID\"}\n\
+             {\"id\":null,\"data\":\"12\"}\n"
+        );
+
+        assert_eq!(export(JsonExportOptions::default(), &[]), "[\n]\n");
+    }
+
+    #[test]
+    fn test_unique_keys() {
+        let columns = ["id", "name", "id", "id_2", "id"].map(String::from);
+        assert_eq!(
+            unique_keys(&columns),
+            ["id", "name", "id_3", "id_2", "id_4"].map(String::from)
+        );
+    }
+}
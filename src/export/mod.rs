@@ -0,0 +1,41 @@
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+pub mod csv;
+pub mod json;
+pub mod sql;
+
+/// File format to export rows as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Ndjson,
+}
+
+impl ExportFormat {
+    /// File extension for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// Encoding used when writing blob values to text based formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BlobEncoding {
+    #[default]
+    Hex,
+    Base64,
+}
+
+/// Encode a blob `value` as text using the provided `encoding`
+pub fn encode_blob(value: &[u8], encoding: BlobEncoding) -> String {
+    match encoding {
+        BlobEncoding::Hex => value.iter().map(|byte| format!("{byte:02X}")).collect(),
+        BlobEncoding::Base64 => STANDARD.encode(value),
+    }
+}
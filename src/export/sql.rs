@@ -0,0 +1,647 @@
+//! Writing of SQL dumps (equivalent of the sqlite3 `.dump` command)
+
+use std::{
+    collections::{HashMap, HashSet},
+    io::Write,
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+
+use crate::database::{
+    AnySharedDatabase, DatabaseObject, DatabaseObjectKind, DatabaseRowSink, DatabaseTable,
+    DatabaseValue, sqlite::quote_identifier,
+};
+
+#[derive(Debug, Clone)]
+pub struct SqlDumpOptions {
+    /// Whether to write the CREATE statements
+    pub schema: bool,
+    /// Whether to write INSERT statements for the table rows
+    pub data: bool,
+    /// Tables to include in the dump, [None] to include every table
+    pub tables: Option<Vec<String>>,
+    /// Whether to wrap the dump in a BEGIN / COMMIT transaction
+    pub transaction: bool,
+}
+
+impl Default for SqlDumpOptions {
+    fn default() -> Self {
+        Self {
+            schema: true,
+            data: true,
+            tables: None,
+            transaction: true,
+        }
+    }
+}
+
+/// Format a `value` as a SQL literal
+pub fn sql_literal(value: &DatabaseValue) -> String {
+    match value {
+        DatabaseValue::Null => "NULL".to_string(),
+        DatabaseValue::Integer(value) => value.to_string(),
+        DatabaseValue::Real(value) if value.is_nan() => "NULL".to_string(),
+        DatabaseValue::Real(value) if value.is_infinite() => {
+            if value.is_sign_positive() {
+                "1e999".to_string()
+            } else {
+                "-1e999".to_string()
+            }
+        }
+        // Debug formatting keeps the decimal point so the value stays a REAL
+        DatabaseValue::Real(value) => format!("{value:?}"),
+        DatabaseValue::Text(value) => format!("'{}'", value.replace('\'', "''")),
+        DatabaseValue::Blob(value) => {
+            let hex: String = value.iter().map(|byte| format!("{byte:02X}")).collect();
+            format!("X'{hex}'")
+        }
+    }
+}
+
+/// Order `tables` so that tables are created after the tables their
+/// foreign keys reference, cycles are broken using the existing order
+pub fn order_tables(tables: Vec<DatabaseTable>) -> Vec<DatabaseTable> {
+    let dependencies: Vec<Vec<String>> = tables
+        .iter()
+        .map(|table| {
+            table
+                .foreign_keys
+                .iter()
+                .map(|foreign_key| foreign_key.table.to_lowercase())
+                .collect()
+        })
+        .collect();
+
+    let names: Vec<String> = tables
+        .iter()
+        .map(|table| table.name.to_lowercase())
+        .collect();
+    let order = dependency_order(&names, &dependencies);
+
+    let mut tables: Vec<Option<DatabaseTable>> = tables.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| tables[index].take())
+        .collect()
+}
+
+/// Order `views` so that views are created after any other views they
+/// select from
+pub fn order_views(views: Vec<DatabaseObject>) -> Vec<DatabaseObject> {
+    let names: Vec<String> = views.iter().map(|view| view.name.to_lowercase()).collect();
+    let dependencies: Vec<Vec<String>> = views
+        .iter()
+        .map(|view| {
+            let identifiers = sql_identifiers(&view.sql);
+            names
+                .iter()
+                .filter(|name| identifiers.contains(*name))
+                .cloned()
+                .collect()
+        })
+        .collect();
+
+    let order = dependency_order(&names, &dependencies);
+
+    let mut views: Vec<Option<DatabaseObject>> = views.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| views[index].take())
+        .collect()
+}
+
+/// Depth first ordering of the items `names` such that each item comes
+/// after the items named in its `dependencies`, returns the item indexes
+fn dependency_order(names: &[String], dependencies: &[Vec<String>]) -> Vec<usize> {
+    let lookup: HashMap<&str, usize> = names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| (name.as_str(), index))
+        .collect();
+
+    fn visit(
+        index: usize,
+        lookup: &HashMap<&str, usize>,
+        dependencies: &[Vec<String>],
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) {
+        if visited[index] {
+            return;
+        }
+
+        visited[index] = true;
+
+        for dependency in &dependencies[index] {
+            if let Some(&dependency) = lookup.get(dependency.as_str()) {
+                visit(dependency, lookup, dependencies, visited, order);
+            }
+        }
+
+        order.push(index);
+    }
+
+    let mut visited = vec![false; names.len()];
+    let mut order = Vec::with_capacity(names.len());
+
+    for index in 0..names.len() {
+        visit(index, &lookup, dependencies, &mut visited, &mut order);
+    }
+
+    order
+}
+
+/// Collect the lowercase identifiers (bare and quoted) within `sql`
+fn sql_identifiers(sql: &str) -> HashSet<String> {
+    let mut identifiers = HashSet::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        let close = match char {
+            '"' => '"',
+            '`' => '`',
+            '[' => ']',
+            '\'' => {
+                // Skip string literals
+                for char in chars.by_ref() {
+                    if char == '\'' {
+                        break;
+                    }
+                }
+                continue;
+            }
+            char if char.is_alphanumeric() || char == '_' => {
+                let mut identifier = String::from(char);
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '$') {
+                        break;
+                    }
+                    identifier.push(next);
+                    chars.next();
+                }
+                identifiers.insert(identifier.to_lowercase());
+                continue;
+            }
+            _ => continue,
+        };
+
+        let identifier: String = chars.by_ref().take_while(|char| *char != close).collect();
+        identifiers.insert(identifier.to_lowercase());
+    }
+
+    identifiers
+}
+
+/// [Write] implementation shared between the dump writer and the row sinks
+/// streaming table data into the same output
+struct SharedWriter<W>(Arc<Mutex<W>>);
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<W: Write> Write for SharedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.lock().flush()
+    }
+}
+
+/// [DatabaseRowSink] writing each row as an INSERT statement into a table,
+/// the values are inserted into the columns named by the query result
+pub struct SqlInsertExporter<W: Write> {
+    writer: W,
+    /// Quoted name of the table to insert into
+    table: String,
+    /// Quoted and comma separated names of the columns to insert into
+    columns: String,
+}
+
+impl<W: Write> SqlInsertExporter<W> {
+    pub fn new(writer: W, table: &str) -> Self {
+        Self {
+            writer,
+            table: quote_identifier(table),
+            columns: String::new(),
+        }
+    }
+}
+
+impl<W: Write + Send> DatabaseRowSink for SqlInsertExporter<W> {
+    fn columns(&mut self, columns: &[String]) -> anyhow::Result<()> {
+        self.columns = quoted_columns(columns);
+        Ok(())
+    }
+
+    fn row(&mut self, values: &[DatabaseValue]) -> anyhow::Result<()> {
+        let values: Vec<String> = values.iter().map(sql_literal).collect();
+        writeln!(
+            self.writer,
+            "INSERT INTO {}({}) VALUES({});",
+            self.table,
+            self.columns,
+            values.join(",")
+        )?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+fn quoted_columns(columns: &[String]) -> String {
+    columns
+        .iter()
+        .map(|column| quote_identifier(column))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Write a SQL dump of the `database` into the `writer`, returns the number
+/// of rows written
+pub async fn write_sql_dump<W: Write + Send + 'static>(
+    database: AnySharedDatabase,
+    writer: W,
+    options: SqlDumpOptions,
+) -> anyhow::Result<u64> {
+    let tables = database.database_tables().await?;
+    let objects = database.database_objects().await?;
+    let shadow_tables = shadow_tables(&database).await?;
+
+    // Shadow tables are recreated by their virtual table
+    let tables = tables
+        .into_iter()
+        .filter(|table| !shadow_tables.contains(&table.name.to_lowercase()));
+
+    let tables: Vec<DatabaseTable> = match &options.tables {
+        Some(selected) => tables
+            .filter(|table| selected.contains(&table.name))
+            .collect(),
+        None => tables.collect(),
+    };
+    let tables = order_tables(tables);
+
+    let table_names: HashSet<String> = tables
+        .iter()
+        .map(|table| table.name.to_lowercase())
+        .collect();
+    let view_names: HashSet<String> = objects
+        .iter()
+        .filter(|object| object.kind == DatabaseObjectKind::View)
+        .map(|object| object.name.to_lowercase())
+        .collect();
+
+    // Views are only included when dumping the entire database, triggers on
+    // views (INSTEAD OF) follow the same rule as the views themselves
+    let include_views = options.tables.is_none();
+    let (views, objects): (Vec<DatabaseObject>, Vec<DatabaseObject>) = objects
+        .into_iter()
+        .filter(|object| {
+            let target = object.table_name.to_lowercase();
+            match object.kind {
+                DatabaseObjectKind::View => include_views,
+                DatabaseObjectKind::Trigger if view_names.contains(&target) => include_views,
+                _ => table_names.contains(&target),
+            }
+        })
+        .partition(|object| object.kind == DatabaseObjectKind::View);
+    let views = order_views(views);
+
+    // Tables with an AUTOINCREMENT counter within sqlite_sequence
+    let sequence_tables: Vec<String> = tables
+        .iter()
+        .filter(|table| sql_identifiers(&table.sql).contains("autoincrement"))
+        .map(|table| sql_literal(&DatabaseValue::Text(table.name.clone())))
+        .collect();
+
+    let mut writer = SharedWriter(Arc::new(Mutex::new(writer)));
+    let mut rows = 0;
+
+    writeln!(writer, "PRAGMA foreign_keys=OFF;")?;
+    if options.transaction {
+        writeln!(writer, "BEGIN TRANSACTION;")?;
+    }
+
+    for table in &tables {
+        if options.schema {
+            writeln!(writer, "{};", table.sql)?;
+        }
+
+        if options.data {
+            // Only the columns that can be inserted into, the table columns
+            // exclude generated columns and hidden columns of virtual tables
+            let columns: Vec<String> = table
+                .columns
+                .iter()
+                .map(|column| column.name.clone())
+                .collect();
+            let query = format!(
+                "SELECT {} FROM {}",
+                quoted_columns(&columns),
+                quote_identifier(&table.name)
+            );
+            let sink = SqlInsertExporter::new(writer.clone(), &table.name);
+            rows += database.stream_query(&query, Box::new(sink)).await?;
+        }
+    }
+
+    // Restore the AUTOINCREMENT counters the same way the sqlite3 CLI does
+    if options.data && !sequence_tables.is_empty() {
+        let names = sequence_tables.join(",");
+        writeln!(
+            writer,
+            "DELETE FROM sqlite_sequence WHERE name IN ({names});"
+        )?;
+
+        let query = format!(r#"SELECT "name", "seq" FROM sqlite_sequence WHERE name IN ({names})"#);
+        let sink = SqlInsertExporter::new(writer.clone(), "sqlite_sequence");
+        database.stream_query(&query, Box::new(sink)).await?;
+    }
+
+    if options.schema {
+        let indexes = objects
+            .iter()
+            .filter(|object| object.kind == DatabaseObjectKind::Index);
+        let triggers = objects
+            .iter()
+            .filter(|object| object.kind == DatabaseObjectKind::Trigger);
+
+        // Triggers may reference views so they are created last
+        for object in indexes.chain(views.iter()).chain(triggers) {
+            writeln!(writer, "{};", object.sql)?;
+        }
+    }
+
+    if options.transaction {
+        writeln!(writer, "COMMIT;")?;
+    }
+
+    writer.flush()?;
+
+    Ok(rows)
+}
+
+/// Lowercase names of the shadow tables backing the virtual tables of the
+/// `database`
+async fn shadow_tables(database: &AnySharedDatabase) -> anyhow::Result<HashSet<String>> {
+    let result = database
+        .query(
+            r#"SELECT "name" FROM pragma_table_list WHERE "schema" = 'main' AND "type" = 'shadow'"#,
+        )
+        .await?;
+
+    Ok(result
+        .rows
+        .into_iter()
+        .filter_map(|row| match row.values.into_iter().next() {
+            Some(DatabaseValue::Text(name)) => Some(name.to_lowercase()),
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Cursor, Write},
+        rc::Rc,
+        sync::Arc,
+    };
+
+    use parking_lot::Mutex;
+    use tokio::sync::watch;
+
+    use super::{SqlDumpOptions, order_tables, order_views, sql_literal, write_sql_dump};
+    use crate::{
+        database::{
+            AnySharedDatabase, DatabaseForeignKey, DatabaseObject, DatabaseObjectKind,
+            DatabaseScriptRequest, DatabaseTable, DatabaseValue, sqlite::SqliteDatabase,
+        },
+        import::sql::SqlFileStatements,
+    };
+
+    fn table(name: &str, references: &[&str]) -> DatabaseTable {
+        DatabaseTable {
+            name: name.to_string(),
+            sql: String::new(),
+            columns: Vec::new(),
+            foreign_keys: references
+                .iter()
+                .map(|reference| DatabaseForeignKey {
+                    from: "id".to_string(),
+                    table: reference.to_string(),
+                    to: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn view(name: &str, sql: &str) -> DatabaseObject {
+        DatabaseObject {
+            kind: DatabaseObjectKind::View,
+            name: name.to_string(),
+            table_name: name.to_string(),
+            sql: sql.to_string(),
+            columns: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_sql_literal() {
+        assert_eq!(sql_literal(&DatabaseValue::Null), "NULL");
+        assert_eq!(sql_literal(&DatabaseValue::Integer(-4)), "-4");
+        assert_eq!(sql_literal(&DatabaseValue::Real(2.0)), "2.0");
+        assert_eq!(sql_literal(&DatabaseValue::Real(f64::INFINITY)), "1e999");
+        assert_eq!(
+            sql_literal(&DatabaseValue::Text("it's".to_string())),
+            "'it''s'"
+        );
+        assert_eq!(sql_literal(&DatabaseValue::Blob(vec![0, 255])), "X'00FF'");
+    }
+
+    #[test]
+    fn test_dependency_order() {
+        let tables = order_tables(vec![
+            table("a", &["c"]),
+            table("b", &["b"]),
+            table("c", &["B"]),
+        ]);
+        let names: Vec<&str> = tables.iter().map(|table| table.name.as_str()).collect();
+        assert_eq!(names, ["b", "c", "a"]);
+
+        let views = order_views(vec![
+            view(
+                "active_users",
+                "CREATE VIEW active_users AS SELECT * FROM \"Users_View\"",
+            ),
+            view(
+                "users_view",
+                "CREATE VIEW users_view AS SELECT 'active_users' FROM users",
+            ),
+        ]);
+        let names: Vec<&str> = views.iter().map(|view| view.name.as_str()).collect();
+        assert_eq!(names, ["users_view", "active_users"]);
+    }
+
+    /// Writer collecting the dump output so it can be read after the dump
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Execute the `sql` script against the `database`, failing on any
+    /// statement that errors
+    async fn execute(database: &AnySharedDatabase, sql: &str) {
+        let (progress, _) = watch::channel(Default::default());
+        let report = database
+            .execute_script(
+                DatabaseScriptRequest {
+                    statements: Box::new(SqlFileStatements::new(Cursor::new(sql.to_string()))),
+                    total_bytes: sql.len() as u64,
+                    stop_on_error: true,
+                    transaction: false,
+                },
+                progress,
+            )
+            .await
+            .unwrap();
+
+        assert!(report.errors.is_empty(), "{:?}\n{sql}", report.errors);
+    }
+
+    async fn dump(database: &AnySharedDatabase, options: SqlDumpOptions) -> String {
+        let output = SharedOutput::default();
+        write_sql_dump(database.clone(), output.clone(), options)
+            .await
+            .unwrap();
+
+        let bytes = output.0.lock().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    async fn query(database: &AnySharedDatabase, sql: &str) -> Vec<Vec<DatabaseValue>> {
+        database
+            .query(sql)
+            .await
+            .unwrap()
+            .rows
+            .into_iter()
+            .map(|row| row.values)
+            .collect()
+    }
+
+    async fn memory_database() -> AnySharedDatabase {
+        Rc::new(SqliteDatabase::memory().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_dump_round_trip() {
+        let source = memory_database().await;
+        execute(
+            &source,
+            "CREATE TABLE users (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT,
+                upper_name TEXT GENERATED ALWAYS AS (upper(name)) VIRTUAL
+            );
+            CREATE TABLE posts (id INTEGER PRIMARY KEY, user_id INTEGER REFERENCES users (id), body TEXT);
+            CREATE INDEX posts_user ON posts (user_id);
+            CREATE VIEW user_posts AS SELECT users.name, posts.body FROM posts JOIN users ON users.id = posts.user_id;
+            CREATE TRIGGER user_posts_insert INSTEAD OF INSERT ON user_posts
+            BEGIN
+                INSERT INTO posts (body) VALUES (NEW.body);
+            END;
+            CREATE VIRTUAL TABLE notes USING fts5(body);
+            INSERT INTO users (name) VALUES ('crab'), ('removed');
+            DELETE FROM users WHERE name = 'removed';
+            INSERT INTO posts (user_id, body) VALUES (1, 'it''s a post'), (1, X'00FF');
+            INSERT INTO notes (body) VALUES ('searchable note');",
+        )
+        .await;
+
+        let sql = dump(&source, SqlDumpOptions::default()).await;
+        assert!(sql.contains(r#"INSERT INTO "users"("id","name") VALUES(1,'crab');"#));
+        assert!(!sql.contains("notes_data"), "shadow tables are skipped");
+
+        let target = memory_database().await;
+        execute(&target, &sql).await;
+
+        let schema = "SELECT type, name, sql FROM sqlite_master ORDER BY name";
+        assert_eq!(query(&target, schema).await, query(&source, schema).await);
+
+        for sql in [
+            "SELECT * FROM users",
+            "SELECT * FROM posts",
+            "SELECT * FROM user_posts",
+            "SELECT * FROM sqlite_sequence",
+            "SELECT body FROM notes WHERE notes MATCH 'searchable'",
+        ] {
+            assert_eq!(
+                query(&target, sql).await,
+                query(&source, sql).await,
+                "{sql}"
+            );
+        }
+
+        // The AUTOINCREMENT counter isn't reset to the largest remaining id
+        execute(&target, "INSERT INTO users (name) VALUES ('next');").await;
+        assert_eq!(
+            query(&target, "SELECT max(id) FROM users").await,
+            [[DatabaseValue::Integer(3)]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dump_selected_tables() {
+        let source = memory_database().await;
+        execute(
+            &source,
+            "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+            CREATE TABLE posts (id INTEGER PRIMARY KEY, body TEXT);
+            CREATE VIEW all_posts AS SELECT * FROM posts;
+            CREATE TRIGGER all_posts_insert INSTEAD OF INSERT ON all_posts
+            BEGIN
+                INSERT INTO posts (body) VALUES (NEW.body);
+            END;
+            INSERT INTO posts (body) VALUES ('post');",
+        )
+        .await;
+
+        let sql = dump(
+            &source,
+            SqlDumpOptions {
+                tables: Some(vec!["posts".to_string()]),
+                ..Default::default()
+            },
+        )
+        .await;
+        assert!(!sql.contains("users"));
+        assert!(
+            !sql.contains("all_posts"),
+            "views and their triggers are skipped"
+        );
+
+        let target = memory_database().await;
+        execute(&target, &sql).await;
+        assert_eq!(
+            query(&target, "SELECT body FROM posts").await,
+            [[DatabaseValue::Text("post".to_string())]]
+        );
+    }
+}
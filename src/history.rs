@@ -0,0 +1,363 @@
+//! History of the queries executed within the query executor, persisted as
+//! JSON lines within the user data directory
+
+use std::{
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use chrono::{Local, TimeZone};
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    database::{DatabaseQueryError, DatabaseQueryResult},
+    import::sql::skip_leading_comments,
+    utils::dirs::data_dir,
+};
+
+/// Name of the history file within the data directory
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// Maximum number of entries kept within the history
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// SQLCipher pragmas setting or changing the encryption key
+const KEY_PRAGMAS: &[&str] = &["KEY", "REKEY", "HEXKEY", "HEXREKEY", "TEXTKEY", "TEXTREKEY"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryHistoryEntry {
+    pub query: String,
+    /// Path of the database the query was executed against
+    pub database_path: String,
+    /// Unix timestamp in seconds of when the query was executed
+    pub executed_at: u64,
+    /// Time taken to execute the query in milliseconds
+    pub duration_ms: f64,
+    /// Error message when the query failed
+    pub error: Option<String>,
+    /// Number of rows returned by the query when it succeeded
+    pub row_count: Option<usize>,
+}
+
+impl QueryHistoryEntry {
+    pub fn new(
+        query: String,
+        database_path: String,
+        duration: Duration,
+        result: &anyhow::Result<DatabaseQueryResult>,
+    ) -> Self {
+        let executed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        let (error, row_count) = match result {
+            Ok(result) => (None, Some(result.rows.len())),
+            Err(error) => {
+                let message = match error.downcast_ref::<DatabaseQueryError>() {
+                    Some(error) => error.message.clone(),
+                    None => error.to_string(),
+                };
+                (Some(message), None)
+            }
+        };
+
+        Self {
+            query,
+            database_path,
+            executed_at,
+            duration_ms: duration.as_secs_f64() * 1000.0,
+            error,
+            row_count,
+        }
+    }
+}
+
+/// Previously executed queries, oldest first
+#[derive(Default)]
+pub struct QueryHistory {
+    pub entries: Vec<QueryHistoryEntry>,
+}
+
+impl Global for QueryHistory {}
+
+impl QueryHistory {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(HISTORY_FILE_NAME))
+    }
+
+    /// Load the history from the history file, skipping any invalid lines
+    pub fn load() -> Self {
+        match Self::path() {
+            Some(path) => Self::load_from(&path),
+            None => Self::default(),
+        }
+    }
+
+    /// Load the history from the history file at `path`, rewriting the file
+    /// when it has more entries than are kept
+    fn load_from(path: &Path) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(error) => {
+                tracing::error!(?error, "failed to read history file");
+                return Self::default();
+            }
+        };
+
+        let mut entries: Vec<QueryHistoryEntry> = contents
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        // Rewrite the file without the entries that no longer fit
+        if entries.len() > MAX_HISTORY_ENTRIES {
+            entries.drain(..entries.len() - MAX_HISTORY_ENTRIES);
+
+            let history = Self { entries };
+            if let Err(error) = history.save_to(path) {
+                tracing::error!(?error, "failed to save history file");
+            }
+
+            return history;
+        }
+
+        Self { entries }
+    }
+
+    /// Write all the entries to the history file
+    fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown data directory")?;
+        self.save_to(&path)
+    }
+
+    fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let mut contents = String::new();
+        for entry in &self.entries {
+            contents.push_str(&serde_json::to_string(entry).context("failed to serialize entry")?);
+            contents.push('\n');
+        }
+
+        fs::write(path, contents).context("failed to write history file")?;
+        Ok(())
+    }
+
+    /// Append a single entry to the history file
+    fn append(entry: &QueryHistoryEntry) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown data directory")?;
+        Self::append_to(&path, entry)
+    }
+
+    fn append_to(path: &Path, entry: &QueryHistoryEntry) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("failed to create data directory")?;
+        }
+
+        let mut line = serde_json::to_string(entry).context("failed to serialize entry")?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context("failed to open history file")?;
+        file.write_all(line.as_bytes())
+            .context("failed to write history file")?;
+
+        Ok(())
+    }
+
+    /// Add the `entry` dropping the oldest entry once there are too many
+    fn add_entry(&mut self, entry: QueryHistoryEntry) {
+        self.entries.push(entry);
+
+        if self.entries.len() > MAX_HISTORY_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// Remove all the entries from the history
+    pub fn clear(&mut self) {
+        self.entries.clear();
+
+        if let Err(error) = self.save() {
+            tracing::error!(?error, "failed to clear history file");
+        }
+    }
+}
+
+/// Initialize the global query history from the history file
+pub fn init_query_history(cx: &mut App) {
+    cx.set_global(QueryHistory::load());
+}
+
+/// Record an executed query within the global history, appending it to the
+/// history file in the background
+///
+/// Statements setting encryption keys aren't recorded as the key would be
+/// persisted in plain text
+pub fn record_query(cx: &mut App, entry: QueryHistoryEntry) {
+    if is_key_statement(&entry.query) {
+        return;
+    }
+
+    let persisted = entry.clone();
+    cx.background_executor()
+        .spawn(async move {
+            if let Err(error) = QueryHistory::append(&persisted) {
+                tracing::error!(?error, "failed to append history entry");
+            }
+        })
+        .detach();
+
+    cx.update_global::<QueryHistory, _>(|history, _cx| {
+        history.add_entry(entry);
+    });
+}
+
+/// Whether the `statement` sets an encryption key, either through a key
+/// pragma or an ATTACH with a KEY
+fn is_key_statement(statement: &str) -> bool {
+    let statement = skip_leading_comments(statement);
+    let words = |text: &str| {
+        text.split(|char: char| !char.is_alphanumeric() && char != '_')
+            .filter(|word| !word.is_empty())
+            .map(|word| word.to_ascii_uppercase())
+            .collect::<Vec<_>>()
+    };
+
+    let statement_words = words(statement);
+    match statement_words.first().map(String::as_str) {
+        // The pragma name is the last word before its value
+        Some("PRAGMA") => {
+            let end = statement.find(['=', '(', ';']).unwrap_or(statement.len());
+            words(&statement[..end])
+                .last()
+                .is_some_and(|name| KEY_PRAGMAS.contains(&name.as_str()))
+        }
+        Some("ATTACH") => statement_words.iter().any(|word| word == "KEY"),
+        _ => false,
+    }
+}
+
+/// Format a unix timestamp in seconds as a local "YYYY-MM-DD HH:MM" date time
+pub fn format_timestamp(timestamp: u64) -> String {
+    format_timestamp_in(timestamp, &Local)
+}
+
+/// Format a unix timestamp in seconds as a "YYYY-MM-DD HH:MM" date time
+/// within the `timezone`
+fn format_timestamp_in<Tz: TimeZone>(timestamp: u64, timezone: &Tz) -> String
+where
+    Tz::Offset: Display,
+{
+    match timezone.timestamp_opt(timestamp as i64, 0).single() {
+        Some(date_time) => date_time.format("%Y-%m-%d %H:%M").to_string(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, time::Duration};
+
+    use chrono::{FixedOffset, Utc};
+
+    use super::{
+        MAX_HISTORY_ENTRIES, QueryHistory, QueryHistoryEntry, format_timestamp_in, is_key_statement,
+    };
+
+    fn entry(query: &str) -> QueryHistoryEntry {
+        QueryHistoryEntry::new(
+            query.to_string(),
+            "/data/app.db".to_string(),
+            Duration::from_millis(5),
+            &Err(anyhow::anyhow!("failed")),
+        )
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp_in(0, &Utc), "1970-01-01 00:00");
+        assert_eq!(format_timestamp_in(951_782_400, &Utc), "2000-02-29 00:00");
+
+        let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            format_timestamp_in(1_792_326_180, &offset),
+            "2026-10-18 14:23"
+        );
+    }
+
+    #[test]
+    fn test_history_persistence() {
+        let path = std::env::temp_dir().join(format!(
+            "crabbyqlite-history-test-{}.jsonl",
+            std::process::id()
+        ));
+        _ = fs::remove_file(&path);
+
+        QueryHistory::append_to(&path, &entry("SELECT 1")).unwrap();
+        QueryHistory::append_to(&path, &entry("SELECT 2")).unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "invalid line\n").unwrap();
+
+        let history = QueryHistory::load_from(&path);
+        let queries: Vec<&str> = history
+            .entries
+            .iter()
+            .map(|entry| entry.query.as_str())
+            .collect();
+        assert_eq!(queries, ["SELECT 1", "SELECT 2"]);
+        assert_eq!(history.entries[0].database_path, "/data/app.db");
+        assert_eq!(history.entries[0].error.as_deref(), Some("failed"));
+
+        // Loading a file with too many entries keeps and rewrites the newest
+        for index in 0..MAX_HISTORY_ENTRIES {
+            QueryHistory::append_to(&path, &entry(&format!("SELECT {index}"))).unwrap();
+        }
+
+        let history = QueryHistory::load_from(&path);
+        assert_eq!(history.entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history.entries[0].query, "SELECT 0");
+        assert_eq!(
+            QueryHistory::load_from(&path).entries.len(),
+            MAX_HISTORY_ENTRIES
+        );
+
+        _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        let mut history = QueryHistory::default();
+
+        for index in 0..MAX_HISTORY_ENTRIES + 5 {
+            history.add_entry(entry(&format!("SELECT {index}")));
+        }
+
+        assert_eq!(history.entries.len(), MAX_HISTORY_ENTRIES);
+        assert_eq!(history.entries[0].query, "SELECT 5");
+    }
+
+    #[test]
+    fn test_key_statements() {
+        assert!(is_key_statement("PRAGMA key = 'secret'"));
+        assert!(is_key_statement("pragma main.rekey('secret');"));
+        assert!(is_key_statement(
+            "-- unlock\nPRAGMA hexkey = \"2DD29CA851E7B56E\""
+        ));
+        assert!(is_key_statement(
+            "ATTACH DATABASE 'other.db' AS other KEY 'secret'"
+        ));
+
+        assert!(!is_key_statement("PRAGMA table_info(key)"));
+        assert!(!is_key_statement("PRAGMA foreign_keys = ON"));
+        assert!(!is_key_statement("ATTACH 'other.db' AS other"));
+        assert!(!is_key_statement("SELECT key FROM settings"));
+    }
+}
@@ -0,0 +1,537 @@
+//! Reading of CSV files for importing into database tables, handles decoding
+//! the file contents, splitting the records and inferring the column types
+
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
+};
+
+use anyhow::Context;
+use encoding_rs::{Decoder, Encoding};
+use itertools::Itertools;
+
+use crate::database::{DatabaseValue, sqlite::quote_identifier};
+
+/// Encodings offered for decoding CSV files
+pub const CSV_ENCODINGS: &[&str] = &[
+    "UTF-8",
+    "UTF-16LE",
+    "UTF-16BE",
+    "windows-1252",
+    "ISO-8859-1",
+    "Shift_JIS",
+    "EUC-KR",
+    "GBK",
+];
+
+#[derive(Debug, Clone)]
+pub struct CsvImportOptions {
+    /// Character separating the fields of a record
+    pub delimiter: u8,
+    /// Character used to quote fields
+    pub quote: u8,
+    /// Whether the first record contains the column names
+    pub has_header: bool,
+    /// Encoding of the file contents
+    pub encoding: &'static Encoding,
+    /// Field value that should be treated as NULL
+    pub null_marker: Option<String>,
+}
+
+impl Default for CsvImportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            quote: b'"',
+            has_header: true,
+            encoding: encoding_rs::UTF_8,
+            null_marker: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CsvData {
+    /// Names of the columns, taken from the header record or generated
+    /// when the file has no header
+    pub columns: Vec<String>,
+    /// Records within the file
+    pub records: Vec<CsvRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvRecord {
+    /// Line within the file the record starts on
+    pub line: usize,
+    /// Raw field values
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvColumnType {
+    Integer,
+    Real,
+    Text,
+}
+
+impl CsvColumnType {
+    /// SQLite type name for the column type
+    pub fn sql_type(&self) -> &'static str {
+        match self {
+            CsvColumnType::Integer => "INTEGER",
+            CsvColumnType::Real => "REAL",
+            CsvColumnType::Text => "TEXT",
+        }
+    }
+}
+
+/// Size of the chunks read from the file before decoding
+const DECODE_CHUNK_SIZE: usize = 8 * 1024;
+
+/// [Read] implementation decoding the contents of the `reader` into UTF-8
+/// as they are read, so the file doesn't need to be held in memory
+struct DecodingReader<R: Read> {
+    reader: R,
+    decoder: Decoder,
+    /// Decoded bytes not yet read
+    output: Vec<u8>,
+    /// Position of the next unread byte within the output
+    position: usize,
+    finished: bool,
+}
+
+impl<R: Read> DecodingReader<R> {
+    fn new(reader: R, encoding: &'static Encoding) -> Self {
+        Self {
+            reader,
+            decoder: encoding.new_decoder_with_bom_removal(),
+            output: Vec::new(),
+            position: 0,
+            finished: false,
+        }
+    }
+}
+
+impl<R: Read> Read for DecodingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.position < self.output.len() {
+                let length = buf.len().min(self.output.len() - self.position);
+                buf[..length].copy_from_slice(&self.output[self.position..self.position + length]);
+                self.position += length;
+                return Ok(length);
+            }
+
+            if self.finished {
+                return Ok(0);
+            }
+
+            let mut input = [0; DECODE_CHUNK_SIZE];
+            let length = self.reader.read(&mut input)?;
+            let last = length == 0;
+
+            let capacity = self
+                .decoder
+                .max_utf8_buffer_length(length)
+                .ok_or_else(|| std::io::Error::other("csv chunk too large to decode"))?;
+
+            // The output is large enough for the decoder to consume the entire input
+            self.output.resize(capacity, 0);
+            let (_, _, written, _) =
+                self.decoder
+                    .decode_to_utf8(&input[..length], &mut self.output, last);
+            self.output.truncate(written);
+            self.position = 0;
+            self.finished = last;
+        }
+    }
+}
+
+/// Reader over the records of the CSV file at `path`
+type CsvFileReader = csv::Reader<DecodingReader<BufReader<File>>>;
+
+fn reader_builder(options: &CsvImportOptions) -> csv::ReaderBuilder {
+    let mut builder = csv::ReaderBuilder::new();
+    builder
+        .delimiter(options.delimiter)
+        .quote(options.quote)
+        .has_headers(options.has_header)
+        .flexible(true);
+    builder
+}
+
+fn open_csv_file(path: &Path, options: &CsvImportOptions) -> anyhow::Result<CsvFileReader> {
+    let file = File::open(path).context("failed to read csv file")?;
+    let reader = DecodingReader::new(BufReader::new(file), options.encoding);
+    Ok(reader_builder(options).from_reader(reader))
+}
+
+/// Read and decode the CSV file at `path` optionally only reading up to `limit` records
+pub fn read_csv_file(
+    path: &Path,
+    options: &CsvImportOptions,
+    limit: Option<usize>,
+) -> anyhow::Result<CsvData> {
+    read_csv(open_csv_file(path, options)?, options, limit)
+}
+
+/// Parse the CSV `text` optionally only reading up to `limit` records
+pub fn parse_csv(
+    text: &str,
+    options: &CsvImportOptions,
+    limit: Option<usize>,
+) -> anyhow::Result<CsvData> {
+    read_csv(
+        reader_builder(options).from_reader(text.as_bytes()),
+        options,
+        limit,
+    )
+}
+
+fn read_csv<R: Read>(
+    mut reader: csv::Reader<R>,
+    options: &CsvImportOptions,
+    limit: Option<usize>,
+) -> anyhow::Result<CsvData> {
+    let mut columns = read_header(&mut reader, options)?;
+    let mut records = Vec::new();
+
+    for record in reader.records() {
+        if limit.is_some_and(|limit| records.len() >= limit) {
+            break;
+        }
+
+        records.push(csv_record(record)?);
+    }
+
+    // Generate names for any columns missing from the header
+    let column_count = records
+        .iter()
+        .map(|record| record.values.len())
+        .max()
+        .unwrap_or_default();
+    generate_column_names(&mut columns, column_count);
+
+    Ok(CsvData { columns, records })
+}
+
+/// Read the column names from the header record when the file has one
+fn read_header<R: Read>(
+    reader: &mut csv::Reader<R>,
+    options: &CsvImportOptions,
+) -> anyhow::Result<Vec<String>> {
+    if !options.has_header {
+        return Ok(Vec::new());
+    }
+
+    Ok(reader
+        .headers()
+        .context("failed to read csv header")?
+        .iter()
+        .map(|value| value.to_string())
+        .collect())
+}
+
+fn csv_record(record: csv::Result<csv::StringRecord>) -> anyhow::Result<CsvRecord> {
+    let record = record.context("failed to read csv record")?;
+    let line = record
+        .position()
+        .map(|position| position.line() as usize)
+        .unwrap_or_default();
+
+    Ok(CsvRecord {
+        line,
+        values: record.iter().map(|value| value.to_string()).collect(),
+    })
+}
+
+/// Generate names for the columns beyond those named by the header
+fn generate_column_names(columns: &mut Vec<String>, column_count: usize) {
+    for index in columns.len()..column_count {
+        columns.push(format!("column{}", index + 1));
+    }
+}
+
+/// Columns, types and number of records of an entire CSV file
+#[derive(Debug, Clone)]
+pub struct CsvFileSummary {
+    pub columns: Vec<String>,
+    pub types: Vec<CsvColumnType>,
+    pub records: usize,
+}
+
+/// Read through the entire CSV file at `path` to find the columns, their
+/// types and the number of records without keeping the records in memory
+pub fn summarize_csv_file(
+    path: &Path,
+    options: &CsvImportOptions,
+) -> anyhow::Result<CsvFileSummary> {
+    let mut reader = open_csv_file(path, options)?;
+    let mut columns = read_header(&mut reader, options)?;
+    let mut inference = ColumnTypeInference::default();
+    let mut records = 0;
+
+    for record in reader.records() {
+        let record = csv_record(record)?;
+        inference.push(&record.values, options);
+        records += 1;
+    }
+
+    generate_column_names(&mut columns, inference.types.len());
+    let types = inference.finish(columns.len());
+
+    Ok(CsvFileSummary {
+        columns,
+        types,
+        records,
+    })
+}
+
+/// Iterator over the records of a CSV file, reads the file as records are
+/// requested so the entire file is never held in memory
+pub struct CsvFileRecords {
+    reader: CsvFileReader,
+}
+
+impl CsvFileRecords {
+    pub fn open(path: &Path, options: &CsvImportOptions) -> anyhow::Result<Self> {
+        let mut reader = open_csv_file(path, options)?;
+        read_header(&mut reader, options)?;
+        Ok(Self { reader })
+    }
+}
+
+impl Iterator for CsvFileRecords {
+    type Item = anyhow::Result<CsvRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut record = csv::StringRecord::new();
+
+        match self.reader.read_record(&mut record) {
+            Ok(true) => Some(csv_record(Ok(record))),
+            Ok(false) => None,
+            Err(error) => Some(Err(error).context("failed to read csv record")),
+        }
+    }
+}
+
+/// Check if the `value` should be treated as NULL
+fn is_null(value: &str, options: &CsvImportOptions) -> bool {
+    options
+        .null_marker
+        .as_deref()
+        .is_some_and(|marker| marker == value)
+}
+
+/// Infer the type of each column from the values within the records, columns
+/// only become numeric when every non-empty value is numeric
+pub fn infer_column_types(data: &CsvData, options: &CsvImportOptions) -> Vec<CsvColumnType> {
+    let mut inference = ColumnTypeInference::default();
+
+    for record in &data.records {
+        inference.push(&record.values, options);
+    }
+
+    inference.finish(data.columns.len())
+}
+
+/// Column types inferred incrementally from the values of each record
+#[derive(Default)]
+struct ColumnTypeInference {
+    /// Type of each column, [None] until a column has a non-empty value
+    types: Vec<Option<CsvColumnType>>,
+}
+
+impl ColumnTypeInference {
+    fn push(&mut self, values: &[String], options: &CsvImportOptions) {
+        if self.types.len() < values.len() {
+            self.types.resize(values.len(), None);
+        }
+
+        for (column_type, value) in self.types.iter_mut().zip(values) {
+            if value.is_empty() || is_null(value, options) {
+                continue;
+            }
+
+            *column_type = Some(match column_type {
+                Some(CsvColumnType::Text) => CsvColumnType::Text,
+                None | Some(CsvColumnType::Integer) if value.parse::<i64>().is_ok() => {
+                    CsvColumnType::Integer
+                }
+                _ if value.parse::<f64>().is_ok() => CsvColumnType::Real,
+                _ => CsvColumnType::Text,
+            });
+        }
+    }
+
+    /// Types of the `columns_count` columns, columns without any values are text
+    fn finish(self, columns_count: usize) -> Vec<CsvColumnType> {
+        (0..columns_count)
+            .map(|index| {
+                self.types
+                    .get(index)
+                    .copied()
+                    .flatten()
+                    .unwrap_or(CsvColumnType::Text)
+            })
+            .collect()
+    }
+}
+
+/// Convert a raw CSV field `value` into a database value of the provided `column_type`,
+/// values that don't match the type are kept as text
+pub fn convert_value(
+    value: Option<&str>,
+    column_type: CsvColumnType,
+    options: &CsvImportOptions,
+) -> DatabaseValue {
+    let value = match value {
+        Some(value) if !is_null(value, options) => value,
+        _ => return DatabaseValue::Null,
+    };
+
+    match column_type {
+        CsvColumnType::Integer if value.is_empty() => DatabaseValue::Null,
+        CsvColumnType::Real if value.is_empty() => DatabaseValue::Null,
+        CsvColumnType::Integer => match value.parse() {
+            Ok(value) => DatabaseValue::Integer(value),
+            Err(_) => DatabaseValue::Text(value.to_string()),
+        },
+        CsvColumnType::Real => match value.parse() {
+            Ok(value) => DatabaseValue::Real(value),
+            Err(_) => DatabaseValue::Text(value.to_string()),
+        },
+        CsvColumnType::Text => DatabaseValue::Text(value.to_string()),
+    }
+}
+
+/// Create the SQL for creating a new `table` to import into
+pub fn create_table_sql(table: &str, columns: &[String], types: &[CsvColumnType]) -> String {
+    format!(
+        "CREATE TABLE {table} ({columns})",
+        table = quote_identifier(table),
+        columns = columns
+            .iter()
+            .zip(types)
+            .map(|(column, column_type)| {
+                format!("{} {}", quote_identifier(column), column_type.sql_type())
+            })
+            .join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{
+        CsvColumnType, CsvFileRecords, CsvImportOptions, convert_value, create_table_sql,
+        infer_column_types, parse_csv, summarize_csv_file,
+    };
+    use crate::database::DatabaseValue;
+
+    #[test]
+    fn test_parse_and_infer() {
+        let options = CsvImportOptions {
+            null_marker: Some("\\N".to_string()),
+            ..Default::default()
+        };
+
+        let data = parse_csv("id,price,name\n1,2.5,a\n2,\\N,b\n3,4,\n", &options, None).unwrap();
+        assert_eq!(data.columns, vec!["id", "price", "name"]);
+        assert_eq!(data.records.len(), 3);
+        assert_eq!(data.records[0].line, 2);
+
+        let types = infer_column_types(&data, &options);
+        assert_eq!(
+            types,
+            vec![
+                CsvColumnType::Integer,
+                CsvColumnType::Real,
+                CsvColumnType::Text
+            ]
+        );
+
+        assert_eq!(
+            convert_value(Some("\\N"), CsvColumnType::Real, &options),
+            DatabaseValue::Null
+        );
+        assert_eq!(
+            convert_value(Some("4"), CsvColumnType::Real, &options),
+            DatabaseValue::Real(4.0)
+        );
+        assert_eq!(
+            convert_value(None, CsvColumnType::Text, &options),
+            DatabaseValue::Null
+        );
+
+        let data = parse_csv(
+            "1;x\n2;y;z\n",
+            &CsvImportOptions {
+                delimiter: b';',
+                has_header: false,
+                ..Default::default()
+            },
+            Some(1),
+        )
+        .unwrap();
+        assert_eq!(data.columns, vec!["column1", "column2"]);
+        assert_eq!(data.records.len(), 1);
+
+        assert_eq!(
+            create_table_sql(
+                "my \"table\"",
+                &data.columns,
+                &[CsvColumnType::Integer, CsvColumnType::Text]
+            ),
+            r#"CREATE TABLE "my ""table""" ("column1" INTEGER, "column2" TEXT)"#
+        );
+    }
+
+    #[test]
+    fn test_read_csv_file() {
+        let path =
+            std::env::temp_dir().join(format!("crabbyqlite-csv-test-{}.csv", std::process::id()));
+
+        // UTF-16 with a byte order mark, larger than a single decoded chunk
+        let mut text = String::from("id,name,price\n");
+        for index in 0..1000 {
+            text.push_str(&format!("{index},caf\u{e9} {index},{index}.5\n"));
+        }
+        text.push_str("1000,last,2,extra\n");
+
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        fs::write(&path, bytes).unwrap();
+
+        let options = CsvImportOptions {
+            encoding: encoding_rs::UTF_16LE,
+            ..Default::default()
+        };
+
+        let summary = summarize_csv_file(&path, &options).unwrap();
+        assert_eq!(summary.columns, vec!["id", "name", "price", "column4"]);
+        assert_eq!(
+            summary.types,
+            vec![
+                CsvColumnType::Integer,
+                CsvColumnType::Text,
+                CsvColumnType::Real,
+                CsvColumnType::Text
+            ]
+        );
+        assert_eq!(summary.records, 1001);
+
+        let records: Vec<_> = CsvFileRecords::open(&path, &options)
+            .unwrap()
+            .collect::<anyhow::Result<_>>()
+            .unwrap();
+        assert_eq!(records.len(), 1001);
+        assert_eq!(records[0].line, 2);
+        assert_eq!(records[999].values, vec!["999", "caf\u{e9} 999", "999.5"]);
+        assert_eq!(records[1000].values, vec!["1000", "last", "2", "extra"]);
+
+        _ = fs::remove_file(&path);
+    }
+}
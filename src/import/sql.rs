@@ -0,0 +1,419 @@
+//! Reading of SQL files as a stream of individual statements
+
+use std::io::BufRead;
+
+use crate::database::DatabaseScriptStatement;
+
+/// Lexer state of the splitter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitterState {
+    Normal,
+    /// Within a quoted string or identifier, closed by the contained char
+    Quoted(char),
+    LineComment,
+    BlockComment,
+}
+
+/// Incremental splitter that separates SQL text into complete statements,
+/// text can be pushed in arbitrary chunks (e.g. line by line) and only
+/// complete statements are produced
+///
+/// Semicolons within strings, quoted identifiers and comments are ignored
+/// along with those within the body of a `CREATE TRIGGER` statement
+#[derive(Debug)]
+pub struct SqlStatementSplitter {
+    state: SplitterState,
+    /// Text of the current incomplete statement
+    buffer: String,
+    /// Previous char within the current state
+    previous: Option<char>,
+    /// Word currently being read
+    word: String,
+    /// Leading words of the current statement
+    leading_words: Vec<String>,
+    /// Last complete token was the END keyword
+    last_word_end: bool,
+    /// Whether the statement contains anything other than whitespace and comments
+    has_content: bool,
+    /// Line number of the first content within the current statement (1 based)
+    start_line: usize,
+    /// Current line number (1 based)
+    line: usize,
+}
+
+/// Statement produced by the [SqlStatementSplitter]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitStatement {
+    /// Statement SQL text including the terminating semicolon
+    pub sql: String,
+    /// Line number the statement starts on (1 based)
+    pub line: usize,
+}
+
+/// Number of leading words to keep for detecting trigger statements
+/// (CREATE TEMPORARY TRIGGER)
+const LEADING_WORDS: usize = 3;
+
+impl Default for SqlStatementSplitter {
+    fn default() -> Self {
+        Self {
+            state: SplitterState::Normal,
+            buffer: String::new(),
+            previous: None,
+            word: String::new(),
+            leading_words: Vec::new(),
+            last_word_end: false,
+            has_content: false,
+            start_line: 1,
+            line: 1,
+        }
+    }
+}
+
+impl SqlStatementSplitter {
+    /// Push the `text` into the splitter, returning any statements
+    /// completed by the text
+    pub fn push(&mut self, text: &str) -> Vec<SplitStatement> {
+        let mut statements = Vec::new();
+
+        for char in text.chars() {
+            if let Some(statement) = self.push_char(char) {
+                statements.push(statement);
+            }
+        }
+
+        statements
+    }
+
+    /// Finish splitting, returns the remaining incomplete statement if
+    /// there is one
+    pub fn finish(&mut self) -> Option<SplitStatement> {
+        self.end_word();
+        self.take_statement()
+    }
+
+    fn push_char(&mut self, char: char) -> Option<SplitStatement> {
+        self.buffer.push(char);
+
+        let previous = self.previous.replace(char);
+        let mut statement = None;
+
+        match self.state {
+            SplitterState::Normal => match char {
+                '\'' | '"' | '`' | '[' => {
+                    self.end_word();
+                    self.mark_content();
+                    self.last_word_end = false;
+                    self.state = SplitterState::Quoted(if char == '[' { ']' } else { char });
+                    self.previous = None;
+                }
+                '-' if previous == Some('-') => {
+                    self.state = SplitterState::LineComment;
+                    self.previous = None;
+                }
+                '*' if previous == Some('/') => {
+                    self.state = SplitterState::BlockComment;
+                    self.previous = None;
+                }
+                // Wait for the next char to know if this starts a comment
+                '-' | '/' => self.end_word(),
+                ';' => {
+                    self.end_word();
+
+                    // Semicolons within the body of a trigger don't end the statement
+                    if !self.is_trigger() || self.last_word_end {
+                        statement = self.take_statement();
+                    }
+
+                    self.last_word_end = false;
+                }
+                char if char.is_alphanumeric() || char == '_' || char == '$' => {
+                    self.mark_pending_operator(previous);
+                    self.word.push(char);
+                }
+                char if char.is_whitespace() => {
+                    self.mark_pending_operator(previous);
+                    self.end_word();
+                }
+                _ => {
+                    self.mark_pending_operator(previous);
+                    self.end_word();
+                    self.mark_content();
+                    self.last_word_end = false;
+                }
+            },
+            SplitterState::Quoted(close) => {
+                if char == close {
+                    // Doubled quotes re-enter the quoted state on the next char
+                    self.state = SplitterState::Normal;
+                }
+            }
+            SplitterState::LineComment => {
+                if char == '\n' {
+                    self.state = SplitterState::Normal;
+                    self.previous = None;
+                }
+            }
+            SplitterState::BlockComment => {
+                if char == '/' && previous == Some('*') {
+                    self.state = SplitterState::Normal;
+                    self.previous = None;
+                }
+            }
+        }
+
+        if char == '\n' {
+            self.line += 1;
+        }
+
+        statement
+    }
+
+    /// A lone '-' or '/' followed by something other than a comment start
+    /// is an operator and counts as statement content
+    fn mark_pending_operator(&mut self, previous: Option<char>) {
+        if matches!(previous, Some('-' | '/')) {
+            self.mark_content();
+            self.last_word_end = false;
+        }
+    }
+
+    /// Mark the statement as having content, the statement line is the
+    /// line of the first content rather than any leading comments
+    fn mark_content(&mut self) {
+        if !self.has_content {
+            self.has_content = true;
+            self.start_line = self.line;
+        }
+    }
+
+    fn end_word(&mut self) {
+        if self.word.is_empty() {
+            return;
+        }
+
+        let word = std::mem::take(&mut self.word).to_uppercase();
+
+        self.mark_content();
+        self.last_word_end = word == "END";
+
+        if self.leading_words.len() < LEADING_WORDS {
+            self.leading_words.push(word);
+        }
+    }
+
+    /// Whether the current statement is a CREATE TRIGGER statement
+    fn is_trigger(&self) -> bool {
+        match self.leading_words.as_slice() {
+            [create, trigger, ..] if create == "CREATE" && trigger == "TRIGGER" => true,
+            [create, temp, trigger]
+                if create == "CREATE"
+                    && (temp == "TEMP" || temp == "TEMPORARY")
+                    && trigger == "TRIGGER" =>
+            {
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Take the current statement resetting the splitter for the next
+    fn take_statement(&mut self) -> Option<SplitStatement> {
+        let sql = std::mem::take(&mut self.buffer);
+        let has_content = self.has_content;
+        let line = self.start_line;
+
+        self.state = SplitterState::Normal;
+        self.previous = None;
+        self.word.clear();
+        self.leading_words.clear();
+        self.last_word_end = false;
+        self.has_content = false;
+
+        if !has_content {
+            return None;
+        }
+
+        Some(SplitStatement {
+            sql: sql.trim().to_string(),
+            line,
+        })
+    }
+}
+
+/// Whether the `statement` is a transaction control statement that starts
+/// or ends a transaction (BEGIN, COMMIT, END, ROLLBACK)
+///
+/// Savepoint statements (SAVEPOINT, RELEASE and ROLLBACK TO) aren't included
+/// as they nest within a transaction wrapping the statements
+pub fn is_transaction_statement(statement: &str) -> bool {
+    let statement = skip_leading_comments(statement);
+
+    // Only the keywords before any trailing semicolon or comment
+    let end = statement.find([';', '-', '/']).unwrap_or(statement.len());
+    let mut words = statement[..end]
+        .split(|char: char| !char.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_uppercase());
+
+    match words.next().as_deref() {
+        Some("BEGIN" | "COMMIT" | "END") => true,
+        // ROLLBACK [TRANSACTION] TO [SAVEPOINT] name only rolls back a savepoint
+        Some("ROLLBACK") => !words.take(2).any(|word| word == "TO"),
+        _ => false,
+    }
+}
+
+/// Remainder of the `statement` after its leading whitespace and comments
+pub fn skip_leading_comments(mut statement: &str) -> &str {
+    loop {
+        statement = statement.trim_start();
+
+        if let Some(comment) = statement.strip_prefix("--") {
+            statement = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = statement.strip_prefix("/*") {
+            statement = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            return statement;
+        }
+    }
+}
+
+/// Iterator over the statements within a SQL file `reader`, reads the file
+/// line by line so the entire file is never held in memory
+pub struct SqlFileStatements<R: BufRead> {
+    reader: R,
+    splitter: SqlStatementSplitter,
+    /// Statements that have been split but not yet returned
+    pending: std::collections::VecDeque<SplitStatement>,
+    /// Total number of bytes read from the reader
+    bytes_read: u64,
+    /// Buffer for the current line
+    line: String,
+    finished: bool,
+}
+
+impl<R: BufRead> SqlFileStatements<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            splitter: SqlStatementSplitter::default(),
+            pending: Default::default(),
+            bytes_read: 0,
+            line: String::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for SqlFileStatements<R> {
+    type Item = anyhow::Result<DatabaseScriptStatement>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(statement) = self.pending.pop_front() {
+                return Some(Ok(DatabaseScriptStatement {
+                    sql: statement.sql,
+                    line: statement.line,
+                    bytes_read: self.bytes_read,
+                }));
+            }
+
+            if self.finished {
+                return None;
+            }
+
+            self.line.clear();
+
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => {
+                    self.finished = true;
+                    self.pending.extend(self.splitter.finish());
+                }
+                Ok(length) => {
+                    self.bytes_read += length as u64;
+                    self.pending.extend(self.splitter.push(&self.line));
+                }
+                Err(error) => {
+                    self.finished = true;
+                    return Some(Err(error.into()));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SplitStatement, SqlStatementSplitter, is_transaction_statement};
+
+    fn split(chunks: &[&str]) -> Vec<SplitStatement> {
+        let mut splitter = SqlStatementSplitter::default();
+        let mut statements: Vec<SplitStatement> = chunks
+            .iter()
+            .flat_map(|chunk| splitter.push(chunk))
+            .collect();
+        statements.extend(splitter.finish());
+        statements
+    }
+
+    #[test]
+    fn test_statement_splitter() {
+        let statements = split(&[
+            "CREATE TABLE \"a;b\" (x TEXT); -- comment; here\n",
+            "INSERT INTO \"a;b\" VALUES('it''s; ok');\n",
+            "/* block; comment */\n",
+            "CREATE TRIGGER t AFTER INSERT ON x BEGIN\n",
+            "  UPDATE x SET y = 1; SELECT CASE WHEN y THEN 2 END FROM x;\n",
+            "END;\n",
+            "SELECT 4 - 2; SELECT 8 / 2",
+        ]);
+
+        let sql: Vec<(&str, usize)> = statements
+            .iter()
+            .map(|statement| (statement.sql.as_str(), statement.line))
+            .collect();
+
+        assert_eq!(
+            sql,
+            [
+                ("CREATE TABLE \"a;b\" (x TEXT);", 1),
+                (
+                    "-- comment; here\nINSERT INTO \"a;b\" VALUES('it''s; ok');",
+                    2
+                ),
+                (
+                    "/* block; comment */\nCREATE TRIGGER t AFTER INSERT ON x BEGIN\n  \
+                     UPDATE x SET y = 1; SELECT CASE WHEN y THEN 2 END FROM x;\nEND;",
+                    4
+                ),
+                ("SELECT 4 - 2;", 7),
+                ("SELECT 8 / 2", 7),
+            ]
+        );
+
+        // Trailing comments and whitespace aren't statements
+        assert!(split(&["SELECT 1;\n-- done\n\n"]).len() == 1);
+    }
+
+    #[test]
+    fn test_transaction_statement() {
+        assert!(is_transaction_statement("BEGIN TRANSACTION;"));
+        assert!(is_transaction_statement("commit;"));
+        assert!(!is_transaction_statement("CREATE TRIGGER t BEGIN END;"));
+
+        // Leading comments are kept within the split statements
+        assert!(is_transaction_statement("-- header\nBEGIN TRANSACTION;"));
+        assert!(is_transaction_statement("/* a */ -- b\n\n/* c */COMMIT;"));
+        assert!(!is_transaction_statement("-- BEGIN\nSELECT 1;"));
+
+        assert!(is_transaction_statement("ROLLBACK; -- due to errors"));
+        assert!(is_transaction_statement("rollback transaction;"));
+        assert!(!is_transaction_statement("ROLLBACK TO before_insert;"));
+        assert!(!is_transaction_statement(
+            "ROLLBACK TRANSACTION TO SAVEPOINT a;"
+        ));
+        assert!(!is_transaction_statement("SAVEPOINT before_insert;"));
+        assert!(!is_transaction_statement("RELEASE before_insert;"));
+    }
+}
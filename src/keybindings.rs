@@ -2,17 +2,32 @@ use gpui::{App, KeyBinding};
 
 use crate::ui::actions::new_database::NewDatabase;
 use crate::ui::actions::open_file::OpenFile;
+use crate::ui::actions::sql_file::{SaveSqlFile, SaveSqlFileAs};
+
+/// Key context of the query editor tabs
+pub const QUERY_TABS_CONTEXT: &str = "QueryTabs";
 
 pub fn init_keybindings(cx: &mut App) {
     #[cfg(target_os = "macos")]
     cx.bind_keys([
-        KeyBinding::new("cmd-o", OpenFile { read_only: false }, None),
+        KeyBinding::new(
+            "cmd-o",
+            OpenFile {
+                read_only: false,
+                new_window: false,
+            },
+            None,
+        ),
         KeyBinding::new("cmd-n", NewDatabase, None),
+        KeyBinding::new("cmd-s", SaveSqlFile, Some(QUERY_TABS_CONTEXT)),
+        KeyBinding::new("cmd-shift-s", SaveSqlFileAs, Some(QUERY_TABS_CONTEXT)),
     ]);
 
     #[cfg(not(target_os = "macos"))]
     cx.bind_keys([
         KeyBinding::new("ctrl-o", OpenFile::default(), None),
         KeyBinding::new("ctrl-n", NewDatabase, None),
+        KeyBinding::new("ctrl-s", SaveSqlFile, Some(QUERY_TABS_CONTEXT)),
+        KeyBinding::new("ctrl-shift-s", SaveSqlFileAs, Some(QUERY_TABS_CONTEXT)),
     ]);
 }
@@ -0,0 +1,322 @@
+//! Library of named queries saved by the user, persisted as JSON within the
+//! user data directory and shareable as a library file
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::dirs::data_dir;
+
+/// Name of the library file within the data directory
+const LIBRARY_FILE_NAME: &str = "library.json";
+
+/// Version of the library file format
+const LIBRARY_VERSION: u32 = 1;
+
+/// Databases a saved query is available for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "path")]
+pub enum SavedQueryScope {
+    /// Available for every database
+    Global,
+    /// Only available for the database at the full path
+    Database(String),
+}
+
+impl SavedQueryScope {
+    /// Whether the scope includes the database at `database_path`
+    pub fn includes(&self, database_path: Option<&str>) -> bool {
+        match self {
+            SavedQueryScope::Global => true,
+            SavedQueryScope::Database(path) => database_path == Some(path.as_str()),
+        }
+    }
+}
+
+/// Named parameter referenced within a saved query as `:name`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQueryParameter {
+    pub name: String,
+    /// SQL substituted for the parameter when the query is opened
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedQuery {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Folder the query is within, nested folders are separated by "/"
+    #[serde(default)]
+    pub folder: String,
+    pub query: String,
+    #[serde(default)]
+    pub parameters: Vec<SavedQueryParameter>,
+    pub scope: SavedQueryScope,
+}
+
+impl SavedQuery {
+    /// Text of the query with the parameters that have a default value
+    /// replaced by their default
+    pub fn text_with_defaults(&self) -> String {
+        self.parameters
+            .iter()
+            .fold(self.query.clone(), |text, parameter| {
+                match &parameter.default_value {
+                    Some(value) => replace_parameter(&text, &parameter.name, value),
+                    None => text,
+                }
+            })
+    }
+
+    /// Whether `other` is the same query within the library, used to
+    /// replace queries when importing
+    fn same_entry(&self, other: &SavedQuery) -> bool {
+        self.name == other.name && self.folder == other.folder && self.scope == other.scope
+    }
+}
+
+/// Replace the `:name` references of the parameter within the `text`,
+/// references within strings, quoted identifiers and comments are kept
+fn replace_parameter(text: &str, name: &str, value: &str) -> String {
+    let pattern = format!(":{name}");
+    let mut output = String::with_capacity(text.len());
+    let mut index = 0;
+
+    while let Some(char) = text[index..].chars().next() {
+        let rest = &text[index..];
+
+        // Length of the string, quoted identifier or comment starting at
+        // the index, unterminated ones run until the end of the text
+        let skip = match char {
+            '\'' | '"' | '`' | '[' => {
+                let close = if char == '[' { ']' } else { char };
+                rest[1..].find(close).map_or(rest.len(), |end| end + 2)
+            }
+            '-' if rest.starts_with("--") => rest.find('\n').unwrap_or(rest.len()),
+            '/' if rest.starts_with("/*") => rest[2..].find("*/").map_or(rest.len(), |end| end + 4),
+            ':' if rest.starts_with(&pattern) => {
+                let continues = rest[pattern.len()..]
+                    .chars()
+                    .next()
+                    .is_some_and(|char| char.is_alphanumeric() || char == '_');
+
+                match continues {
+                    // Reference to another parameter starting with the same name
+                    true => pattern.len(),
+                    false => {
+                        output.push_str(value);
+                        index += pattern.len();
+                        continue;
+                    }
+                }
+            }
+            char => char.len_utf8(),
+        };
+
+        output.push_str(&rest[..skip]);
+        index += skip;
+    }
+
+    output
+}
+
+/// Contents of a library file
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LibraryFile {
+    version: u32,
+    queries: Vec<SavedQuery>,
+}
+
+/// Saved queries sorted by folder then name
+#[derive(Default)]
+pub struct QueryLibrary {
+    pub queries: Vec<SavedQuery>,
+}
+
+impl Global for QueryLibrary {}
+
+impl QueryLibrary {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(LIBRARY_FILE_NAME))
+    }
+
+    /// Load the library from the library file, falling back to an empty
+    /// library when the file is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        match read_library_file(&path) {
+            Ok(queries) => {
+                let mut library = Self { queries };
+                library.sort();
+                library
+            }
+            Err(error) => {
+                tracing::error!(?error, "failed to load query library");
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the library to the library file
+    fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown data directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("failed to create data directory")?;
+        }
+
+        self.export(&path)
+    }
+
+    fn sort(&mut self) {
+        self.queries.sort_by(|a, b| {
+            a.folder
+                .cmp(&b.folder)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+    }
+
+    /// Add the `query` to the library, replacing a query with the same name
+    /// within the same folder and scope
+    fn add(&mut self, query: SavedQuery) {
+        match self
+            .queries
+            .iter_mut()
+            .find(|existing| existing.same_entry(&query))
+        {
+            Some(existing) => *existing = query,
+            None => self.queries.push(query),
+        }
+    }
+
+    /// Write the whole library to the file at `path`
+    pub fn export(&self, path: &Path) -> anyhow::Result<()> {
+        let file = LibraryFile {
+            version: LIBRARY_VERSION,
+            queries: self.queries.clone(),
+        };
+
+        let contents =
+            serde_json::to_string_pretty(&file).context("failed to serialize query library")?;
+        fs::write(path, contents).context("failed to write query library")?;
+
+        Ok(())
+    }
+}
+
+fn read_library_file(path: &Path) -> anyhow::Result<Vec<SavedQuery>> {
+    let contents = fs::read_to_string(path).context("failed to read query library")?;
+    let file: LibraryFile =
+        serde_json::from_str(&contents).context("failed to parse query library")?;
+
+    if file.version > LIBRARY_VERSION {
+        anyhow::bail!("unsupported query library version {}", file.version);
+    }
+
+    Ok(file.queries)
+}
+
+/// Initialize the global query library from the library file
+pub fn init_query_library(cx: &mut App) {
+    cx.set_global(QueryLibrary::load());
+}
+
+/// Update the global query library and persist it to the library file
+pub fn update_library(cx: &mut App, update: impl FnOnce(&mut QueryLibrary)) {
+    cx.update_global::<QueryLibrary, _>(|library, _cx| {
+        update(library);
+        library.sort();
+
+        if let Err(error) = library.save() {
+            tracing::error!(?error, "failed to save query library");
+        }
+    });
+}
+
+/// Save the `query` into the global library
+pub fn save_query(cx: &mut App, query: SavedQuery) {
+    update_library(cx, |library| library.add(query));
+}
+
+/// Import the queries from the library file at `path` into the global
+/// library, returning the number of queries imported
+pub fn import_library(cx: &mut App, path: &Path) -> anyhow::Result<usize> {
+    let queries = read_library_file(path)?;
+    let count = queries.len();
+
+    update_library(cx, |library| {
+        for query in queries {
+            library.add(query);
+        }
+    });
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SavedQuery, SavedQueryParameter, SavedQueryScope};
+
+    #[test]
+    fn test_text_with_defaults() {
+        let query = SavedQuery {
+            name: "Recent orders".to_string(),
+            description: String::new(),
+            folder: "orders".to_string(),
+            query: "SELECT * FROM orders WHERE id > :min AND id < :min_limit LIMIT :limit"
+                .to_string(),
+            parameters: vec![
+                SavedQueryParameter {
+                    name: "min".to_string(),
+                    default_value: Some("10".to_string()),
+                },
+                SavedQueryParameter {
+                    name: "limit".to_string(),
+                    default_value: None,
+                },
+            ],
+            scope: SavedQueryScope::Global,
+        };
+
+        assert_eq!(
+            query.text_with_defaults(),
+            "SELECT * FROM orders WHERE id > 10 AND id < :min_limit LIMIT :limit"
+        );
+    }
+
+    #[test]
+    fn test_text_with_defaults_skips_literals() {
+        let query = SavedQuery {
+            name: "Search".to_string(),
+            description: String::new(),
+            folder: String::new(),
+            query: "SELECT ':name', \"a:name\", [:name] FROM t -- :name\n\
+                    WHERE name = :name /* :name */ AND note = 'it''s :name'"
+                .to_string(),
+            parameters: vec![SavedQueryParameter {
+                name: "name".to_string(),
+                default_value: Some("'crab'".to_string()),
+            }],
+            scope: SavedQueryScope::Global,
+        };
+
+        assert_eq!(
+            query.text_with_defaults(),
+            "SELECT ':name', \"a:name\", [:name] FROM t -- :name\n\
+             WHERE name = 'crab' /* :name */ AND note = 'it''s :name'"
+        );
+    }
+}
@@ -1,17 +1,37 @@
+use std::ops::Range;
 use std::rc::Rc;
 
+use gpui::{App, Task};
 use gpui_component::highlighter::{LanguageConfig, LanguageRegistry};
-use gpui_component::input::CompletionProvider;
+use gpui_component::input::{CompletionProvider, HoverProvider};
+use lsp_types::{Diagnostic, SignatureHelp};
 
 use crate::database::sqlite::SqliteDatabase;
 use crate::lsp::sqlite::SqliteLsp;
 
-use crate::database::Database;
+use crate::database::{Database, DatabaseSchema};
 
 pub mod sqlite;
 
-pub trait SqlLsp: CompletionProvider + 'static {
+pub trait SqlLsp: CompletionProvider + HoverProvider + 'static {
     fn into_completion_provider(self: Rc<Self>) -> Rc<dyn CompletionProvider + 'static>;
+
+    fn into_hover_provider(self: Rc<Self>) -> Rc<dyn HoverProvider + 'static>;
+
+    /// Update the database schema used to provide completions and hovers
+    fn set_schema(&self, schema: DatabaseSchema);
+
+    /// Byte range of the statement within the `text` containing the `offset`
+    fn statement_range(&self, text: &str, offset: usize) -> Option<Range<usize>>;
+
+    /// Byte ranges of the statements within the `text` in order
+    fn statement_ranges(&self, text: &str) -> Vec<Range<usize>>;
+
+    /// Signature of the function call the `offset` is within the arguments of
+    fn signature_help(&self, text: &str, offset: usize) -> Option<SignatureHelp>;
+
+    /// Compute the syntax and statement errors within the `text`
+    fn diagnostics(&self, text: &str, cx: &mut App) -> Task<Vec<Diagnostic>>;
 }
 
 pub fn create_sql_lsp(database: Rc<dyn Database>) -> anyhow::Result<Rc<dyn SqlLsp>> {
@@ -1,15 +1,50 @@
-use crate::{database::sqlite::SqliteDatabase, lsp::SqlLsp};
+use crate::{
+    database::{
+        Database, DatabaseSchema, DatabaseTable, DatabaseTableColumn, DatabaseTableQuery,
+        sqlite::{SqliteDatabase, quote_identifier},
+    },
+    lsp::{
+        SqlLsp,
+        sqlite::context::{CursorContext, StatementScope, TokenKind, unquote_identifier},
+    },
+};
 use anyhow::Context;
-use gpui::{Task, Window};
-use gpui_component::input::{CompletionProvider, InputState};
-use lsp_types::{CompletionItem, CompletionItemKind, CompletionResponse};
+use gpui::{App, Task, Window};
+use gpui_component::input::{CompletionProvider, HoverProvider, InputState};
+use lsp_types::{
+    CompletionItem, CompletionResponse, Diagnostic, Documentation, Hover, HoverContents,
+    MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, SignatureHelp,
+    SignatureInformation,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::rc::Rc;
 use std::sync::Mutex;
 
-#[allow(unused)]
+mod completion;
+mod context;
+mod diagnostics;
+mod functions;
+mod hover;
+
 pub struct SqliteLsp {
     database: Rc<SqliteDatabase>,
     parser: Rc<Mutex<tree_sitter::Parser>>,
+    /// Current schema of the database used for completions
+    schema: RefCell<Rc<DatabaseSchema>>,
+    /// Row counts of the tables shown by hovers, replaced when the schema is
+    /// loaded so counting only happens once per table and schema load
+    row_counts: RefCell<RowCounts>,
+}
+
+type RowCounts = Rc<RefCell<HashMap<String, i64>>>;
+
+/// What is being hovered within the editor
+enum HoverTarget {
+    /// Table whose row count is loaded for the hover
+    Table(DatabaseTable),
+    Markdown(String),
 }
 
 impl SqliteLsp {
@@ -23,47 +58,381 @@ impl SqliteLsp {
         Ok(Rc::new(Self {
             database,
             parser: Rc::new(Mutex::new(parser)),
+            schema: Default::default(),
+            row_counts: Default::default(),
         }))
     }
+
+    /// Completions for the `prefix` being typed at `word_start` based on
+    /// what the statement expects at that position
+    fn context_completions(
+        &self,
+        text: &str,
+        word_start: usize,
+        prefix: &str,
+    ) -> Option<Vec<CompletionItem>> {
+        let (tree, prefix_tree) = {
+            let mut parser = self.parser.lock().ok()?;
+            // Parse the text up to the cursor separately so that the text
+            // after the cursor doesn't affect the valid keywords
+            (
+                parser.parse(text, None)?,
+                parser.parse(&text[..word_start], None)?,
+            )
+        };
+
+        let tokens = context::tokens(&tree, text);
+        let statement = context::statement_tokens(&tokens, word_start);
+        let before = statement
+            .iter()
+            .take_while(|token| token.start < word_start)
+            .count();
+
+        let cursor_context = context::cursor_context(&statement[..before]);
+
+        // Only complete once something has been typed, unless completing
+        // the columns after `qualifier.` or within a PRAGMA statement
+        let complete_empty = matches!(
+            cursor_context,
+            CursorContext::Qualified(_) | CursorContext::Pragma | CursorContext::PragmaValue(_)
+        );
+        if prefix.is_empty() && !complete_empty {
+            return None;
+        }
+
+        let schema = self.schema.borrow().clone();
+        let scope = StatementScope::new(statement);
+        let keywords = context::valid_keywords(&prefix_tree);
+
+        let mut items = Vec::new();
+
+        match cursor_context {
+            CursorContext::Table => {
+                items.extend(completion::cte_completions(&scope, prefix));
+                items.extend(completion::table_completions(&schema, prefix));
+                items.extend(completion::schema_completions(&schema, prefix));
+            }
+            CursorContext::Index => {
+                items.extend(completion::index_completions(&schema, prefix));
+            }
+            CursorContext::Pragma => {
+                items.extend(completion::pragma_completions(prefix));
+                return Some(items);
+            }
+            CursorContext::PragmaValue(name) => {
+                items.extend(completion::pragma_value_completions(&name, prefix));
+                return Some(items);
+            }
+            CursorContext::Expression => {
+                for cte in &scope.ctes {
+                    items.extend(completion::cte_column_completions(cte, prefix));
+                }
+
+                // Before any table is referenced (e.g. writing the SELECT
+                // before the FROM) columns of every table are suggested
+                items.extend(completion::column_completions(&schema, prefix, |table| {
+                    scope.tables.is_empty() || scope.contains_table(table)
+                }));
+                items.extend(completion::function_completions(&schema, prefix));
+            }
+            CursorContext::Qualified(qualifier) => {
+                if schema
+                    .schemas
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(&qualifier))
+                {
+                    items.extend(completion::schema_table_completions(
+                        &schema, &qualifier, prefix,
+                    ));
+                    return Some(items);
+                }
+
+                let table = scope.resolve(&qualifier).unwrap_or(&qualifier);
+
+                match scope
+                    .ctes
+                    .iter()
+                    .find(|cte| cte.name.eq_ignore_ascii_case(table))
+                {
+                    Some(cte) => items.extend(completion::cte_column_completions(cte, prefix)),
+                    None => items.extend(completion::column_completions(&schema, prefix, |name| {
+                        name.eq_ignore_ascii_case(table)
+                    })),
+                }
+
+                return Some(items);
+            }
+            CursorContext::Keyword => {}
+        }
+
+        items.extend(completion::keyword_completions(prefix, keywords.as_ref()));
+        Some(items)
+    }
+
+    /// Resolve what the token at the `offset` refers to
+    fn hover_target(&self, text: &str, offset: usize) -> Option<HoverTarget> {
+        let tree = self.parser.lock().ok()?.parse(text, None)?;
+        let tokens = context::tokens(&tree, text);
+        let statement = context::statement_tokens(&tokens, offset);
+        let index = context::token_at(statement, offset)?;
+        let token = statement[index];
+
+        let schema = self.schema.borrow().clone();
+
+        let is_call = statement
+            .get(index + 1)
+            .is_some_and(|token| token.is_punctuation("("));
+        if is_call && let Some(function) = functions::find_function(token.text) {
+            return Some(HoverTarget::Markdown(hover::function_hover(function)));
+        }
+
+        if token.kind != TokenKind::Identifier {
+            return None;
+        }
+
+        let name = unquote_identifier(token.text);
+        let scope = StatementScope::new(statement);
+
+        // Column qualified by a table or alias
+        let qualifier = index
+            .checked_sub(2)
+            .filter(|_| statement[index - 1].is_punctuation("."))
+            .map(|index| unquote_identifier(statement[index].text));
+
+        if let Some(qualifier) = qualifier
+            && !schema
+                .schemas
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(&qualifier))
+        {
+            let table = scope.resolve(&qualifier).unwrap_or(&qualifier);
+            return Self::column_hover_target(&schema, table, &name);
+        }
+
+        if let Some(table) = schema
+            .tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(&name))
+        {
+            return Some(HoverTarget::Table(table.clone()));
+        }
+
+        if let Some(view) = schema
+            .views()
+            .find(|view| view.name.eq_ignore_ascii_case(&name))
+        {
+            return Some(HoverTarget::Markdown(hover::view_hover(view)));
+        }
+
+        // Unqualified column of the tables in scope
+        schema
+            .tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .chain(schema.views().map(|view| view.name.as_str()))
+            .filter(|table| scope.tables.is_empty() || scope.contains_table(table))
+            .find_map(|table| Self::column_hover_target(&schema, table, &name))
+    }
+
+    fn column_hover_target(
+        schema: &DatabaseSchema,
+        table_name: &str,
+        column_name: &str,
+    ) -> Option<HoverTarget> {
+        let find_column = |columns: &[DatabaseTableColumn]| {
+            columns
+                .iter()
+                .find(|column| column.name.eq_ignore_ascii_case(column_name))
+                .cloned()
+        };
+
+        if let Some(table) = schema
+            .tables
+            .iter()
+            .find(|table| table.name.eq_ignore_ascii_case(table_name))
+        {
+            let column = find_column(&table.columns)?;
+            return Some(HoverTarget::Markdown(hover::column_hover(
+                &table.name,
+                &column,
+                &table.foreign_keys,
+            )));
+        }
+
+        let view = schema
+            .views()
+            .find(|view| view.name.eq_ignore_ascii_case(table_name))?;
+        let column = find_column(&view.columns)?;
+        Some(HoverTarget::Markdown(hover::column_hover(
+            &view.name,
+            &column,
+            &[],
+        )))
+    }
+}
+
+fn markdown_hover(value: String) -> Hover {
+    Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: None,
+    }
 }
 
 impl SqlLsp for SqliteLsp {
     fn into_completion_provider(self: Rc<Self>) -> Rc<dyn CompletionProvider + 'static> {
         self
     }
+
+    fn into_hover_provider(self: Rc<Self>) -> Rc<dyn HoverProvider + 'static> {
+        self
+    }
+
+    fn set_schema(&self, schema: DatabaseSchema) {
+        *self.schema.borrow_mut() = Rc::new(schema);
+        *self.row_counts.borrow_mut() = Default::default();
+    }
+
+    fn statement_range(&self, text: &str, offset: usize) -> Option<Range<usize>> {
+        let tree = self.parser.lock().ok()?.parse(text, None)?;
+        let tokens = context::tokens(&tree, text);
+        context::statement_range(&tokens, offset)
+    }
+
+    fn statement_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(tree) = self
+            .parser
+            .lock()
+            .ok()
+            .and_then(|mut parser| parser.parse(text, None))
+        else {
+            return Vec::new();
+        };
+
+        let tokens = context::tokens(&tree, text);
+        context::statement_spans(&tokens)
+            .into_iter()
+            .filter_map(|span| {
+                let statement = &tokens[span];
+                let (first, last) = (statement.first()?, statement.last()?);
+                Some(first.start..last.start + last.text.len())
+            })
+            .collect()
+    }
+
+    fn signature_help(&self, text: &str, offset: usize) -> Option<SignatureHelp> {
+        let tree = self.parser.lock().ok()?.parse(text, None)?;
+        let tokens = context::tokens(&tree, text);
+        let statement = context::statement_tokens(&tokens, offset);
+        let before = statement
+            .iter()
+            .take_while(|token| token.start < offset)
+            .count();
+
+        let (name, argument) = context::enclosing_call(&statement[..before])?;
+
+        let (signature, description) = match functions::find_function(name) {
+            Some(function) => (
+                function.signature.to_string(),
+                Some(function.description.to_string()),
+            ),
+            None => {
+                let schema = self.schema.borrow();
+                let arguments = schema
+                    .functions
+                    .iter()
+                    .filter(|function| function.name.eq_ignore_ascii_case(name))
+                    .map(|function| function.arguments)
+                    .max_by_key(|arguments| (*arguments == -1, *arguments))?;
+
+                (functions::generic_signature(name, arguments), None)
+            }
+        };
+
+        let parameters = functions::signature_parameters(&signature);
+
+        // Arguments past the last parameter belong to a variadic `...`
+        let active_parameter = match parameters.last() {
+            Some(last) if &signature[last.clone()] == "..." => {
+                Some(argument.min(parameters.len() - 1) as u32)
+            }
+            _ => (argument < parameters.len()).then_some(argument as u32),
+        };
+
+        let parameters = parameters
+            .into_iter()
+            .map(|range| ParameterInformation {
+                label: ParameterLabel::LabelOffsets([range.start as u32, range.end as u32]),
+                documentation: None,
+            })
+            .collect();
+
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label: signature,
+                documentation: description.map(Documentation::String),
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        })
+    }
+
+    fn diagnostics(&self, text: &str, cx: &mut App) -> Task<Vec<Diagnostic>> {
+        let tree = match self
+            .parser
+            .lock()
+            .ok()
+            .and_then(|mut parser| parser.parse(text, None))
+        {
+            Some(value) => value,
+            None => return Task::ready(Vec::new()),
+        };
+
+        let tokens = context::tokens(&tree, text);
+        let statements = diagnostics::diagnostics_statements(&tree, &tokens);
+
+        let prepare: Vec<String> = statements
+            .iter()
+            .filter(|statement| statement.prepare)
+            .map(|statement| text[statement.range.clone()].to_string())
+            .collect();
+
+        let database = self.database.clone();
+        let text = text.to_string();
+
+        cx.spawn(async move |_cx| {
+            let prepare_errors = match database.prepare_statements(prepare).await {
+                Ok(value) => value,
+                Err(error) => {
+                    tracing::error!(?error, "failed to prepare statements for diagnostics");
+                    Vec::new()
+                }
+            };
+
+            diagnostics::resolve_diagnostics(&text, statements, prepare_errors)
+        })
+    }
 }
 
 impl CompletionProvider for SqliteLsp {
     fn completions(
         &self,
-        _rope: &gpui_component::Rope,
-        _offset: usize,
-        trigger: lsp_types::CompletionContext,
+        rope: &gpui_component::Rope,
+        offset: usize,
+        _trigger: lsp_types::CompletionContext,
         _window: &mut Window,
         _cx: &mut gpui::Context<InputState>,
     ) -> gpui::Task<gpui::Result<CompletionResponse>> {
-        let trigger_character = trigger.trigger_character.unwrap_or_default();
-        if trigger_character.is_empty() {
-            return Task::ready(Ok(CompletionResponse::Array(vec![])));
-        }
-
-        let mut items: Vec<CompletionItem> = vec![];
+        let text = rope.to_string();
+        let (word_start, prefix) = completion::current_word(&text, offset);
 
-        for keyword in KEYWORDS {
-            // Exclude keywords that have already been completely typed
-            // out or are different from the current words
-            if !keyword.starts_with(&trigger_character) || trigger_character.eq(*keyword) {
-                continue;
-            }
-
-            items.push(CompletionItem {
-                label: keyword.to_string(),
-                kind: Some(CompletionItemKind::KEYWORD),
-                detail: Some(format!("SQLite keyword: {}", keyword)),
-                sort_text: Some(format!("0{}", keyword)),
-                ..Default::default()
-            });
-        }
+        let items = self
+            .context_completions(&text, word_start, prefix)
+            .unwrap_or_default();
 
         Task::ready(Ok(CompletionResponse::Array(items)))
     }
@@ -78,6 +447,48 @@ impl CompletionProvider for SqliteLsp {
     }
 }
 
+impl HoverProvider for SqliteLsp {
+    fn hover(
+        &self,
+        rope: &gpui_component::Rope,
+        offset: usize,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Task<gpui::Result<Option<Hover>>> {
+        let text = rope.to_string();
+
+        match self.hover_target(&text, offset) {
+            Some(HoverTarget::Table(table)) => {
+                let row_counts = self.row_counts.borrow().clone();
+                let row_count = row_counts.borrow().get(&table.name).copied();
+                if let Some(row_count) = row_count {
+                    let value = hover::table_hover(&table, Some(row_count));
+                    return Task::ready(Ok(Some(markdown_hover(value))));
+                }
+
+                let database = self.database.clone();
+
+                cx.spawn(async move |_cx| {
+                    let query = DatabaseTableQuery {
+                        table: quote_identifier(&table.name),
+                    };
+                    let row_count = database.query_table_rows_count(query).await.ok();
+
+                    if let Some(row_count) = row_count {
+                        row_counts
+                            .borrow_mut()
+                            .insert(table.name.clone(), row_count);
+                    }
+
+                    Ok(Some(markdown_hover(hover::table_hover(&table, row_count))))
+                })
+            }
+            Some(HoverTarget::Markdown(value)) => Task::ready(Ok(Some(markdown_hover(value)))),
+            None => Task::ready(Ok(None)),
+        }
+    }
+}
+
 static KEYWORDS: &[&str] = &[
     "ABORT",
     "ACTION",
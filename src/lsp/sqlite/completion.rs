@@ -0,0 +1,500 @@
+//! Building of completion items from the SQL keywords and database schema
+
+use std::collections::HashSet;
+
+use lsp_types::{CompletionItem, CompletionItemKind, Documentation, InsertTextFormat};
+
+use crate::{
+    database::{
+        DatabaseObjectKind, DatabaseSchema, PragmaType,
+        sqlite::{pragma::PRAGMA_DEFINITIONS, quote_identifier},
+    },
+    lsp::sqlite::{
+        KEYWORDS,
+        context::{ScopeCte, StatementScope},
+        functions::{FUNCTIONS, call_snippet, generic_signature, signature_parameters},
+    },
+};
+
+/// Sort prefixes to order the completion items by their kind
+const SORT_COLUMN: &str = "0";
+const SORT_TABLE: &str = "1";
+const SORT_SCHEMA: &str = "2";
+const SORT_FUNCTION: &str = "3";
+const SORT_KEYWORD: &str = "4";
+
+/// Find the identifier being typed before the `offset` within `text`,
+/// returns the byte offset the word starts at and the word itself
+pub fn current_word(text: &str, offset: usize) -> (usize, &str) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+
+    let before = &text[..offset];
+    let start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, char)| is_identifier_char(*char))
+        .last()
+        .map(|(index, _)| index)
+        .unwrap_or(offset);
+
+    (start, &text[start..offset])
+}
+
+pub fn is_identifier_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_' || char == '$'
+}
+
+/// Whether `value` starts with `prefix` ignoring ASCII case
+fn matches_prefix(value: &str, prefix: &str) -> bool {
+    value
+        .get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// Text to insert for an identifier, quoting identifiers that would not be
+/// valid when left bare
+fn identifier_insert_text(name: &str) -> Option<String> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|char| char.is_ascii_alphabetic() || char == '_');
+    let valid_rest = chars.all(|char| char.is_ascii_alphanumeric() || char == '_');
+    let is_keyword = KEYWORDS
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(name));
+
+    if valid_start && valid_rest && !is_keyword {
+        return None;
+    }
+
+    Some(quote_identifier(name))
+}
+
+fn identifier_item(
+    name: &str,
+    kind: CompletionItemKind,
+    detail: String,
+    sort: &str,
+) -> CompletionItem {
+    CompletionItem {
+        label: name.to_string(),
+        kind: Some(kind),
+        detail: Some(detail),
+        insert_text: identifier_insert_text(name),
+        sort_text: Some(format!("{sort}{name}")),
+        ..Default::default()
+    }
+}
+
+/// Completion items for the keywords starting with `prefix`, limited to the
+/// `valid` keywords when known
+pub fn keyword_completions(prefix: &str, valid: Option<&HashSet<String>>) -> Vec<CompletionItem> {
+    KEYWORDS
+        .iter()
+        .filter(|keyword| valid.is_none_or(|valid| valid.contains(**keyword)))
+        // Exclude keywords that have already been completely typed out
+        .filter(|keyword| matches_prefix(keyword, prefix) && !keyword.eq_ignore_ascii_case(prefix))
+        .map(|keyword| CompletionItem {
+            label: keyword.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            detail: Some(format!("SQLite keyword: {keyword}")),
+            sort_text: Some(format!("{SORT_KEYWORD}{keyword}")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Completion items for the tables and views starting with `prefix`
+pub fn table_completions(schema: &DatabaseSchema, prefix: &str) -> Vec<CompletionItem> {
+    let tables = schema
+        .tables
+        .iter()
+        .filter(|table| matches_prefix(&table.name, prefix))
+        .map(|table| {
+            identifier_item(
+                &table.name,
+                CompletionItemKind::STRUCT,
+                "table".to_string(),
+                SORT_TABLE,
+            )
+        });
+
+    let views = schema
+        .objects
+        .iter()
+        .filter(|object| {
+            object.kind == DatabaseObjectKind::View && matches_prefix(&object.name, prefix)
+        })
+        .map(|view| {
+            identifier_item(
+                &view.name,
+                CompletionItemKind::INTERFACE,
+                "view".to_string(),
+                SORT_TABLE,
+            )
+        });
+
+    tables.chain(views).collect()
+}
+
+/// Completion items for the columns starting with `prefix` within the tables
+/// and views accepted by the `filter`
+pub fn column_completions(
+    schema: &DatabaseSchema,
+    prefix: &str,
+    filter: impl Fn(&str) -> bool,
+) -> Vec<CompletionItem> {
+    let table_columns = schema
+        .tables
+        .iter()
+        .map(|table| (&table.name, &table.columns));
+    let view_columns = schema
+        .objects
+        .iter()
+        .filter(|object| object.kind == DatabaseObjectKind::View)
+        .map(|view| (&view.name, &view.columns));
+
+    table_columns
+        .chain(view_columns)
+        .filter(|(table, _)| filter(table))
+        .flat_map(|(table, columns)| {
+            columns
+                .iter()
+                .filter(|column| matches_prefix(&column.name, prefix))
+                .map(move |column| {
+                    let detail = match column.column_type.is_empty() {
+                        true => table.clone(),
+                        false => format!("{} · {table}", column.column_type),
+                    };
+
+                    identifier_item(&column.name, CompletionItemKind::FIELD, detail, SORT_COLUMN)
+                })
+        })
+        .collect()
+}
+
+/// Completion items for the indexes starting with `prefix`
+pub fn index_completions(schema: &DatabaseSchema, prefix: &str) -> Vec<CompletionItem> {
+    schema
+        .objects
+        .iter()
+        .filter(|object| {
+            object.kind == DatabaseObjectKind::Index && matches_prefix(&object.name, prefix)
+        })
+        .map(|index| {
+            identifier_item(
+                &index.name,
+                CompletionItemKind::REFERENCE,
+                format!("index on {}", index.table_name),
+                SORT_TABLE,
+            )
+        })
+        .collect()
+}
+
+/// Completion items for the schema names (main, temp and attached databases)
+/// starting with `prefix`
+pub fn schema_completions(schema: &DatabaseSchema, prefix: &str) -> Vec<CompletionItem> {
+    schema
+        .schemas
+        .iter()
+        .filter(|name| matches_prefix(name, prefix))
+        .map(|name| {
+            identifier_item(
+                name,
+                CompletionItemKind::MODULE,
+                "schema".to_string(),
+                SORT_SCHEMA,
+            )
+        })
+        .collect()
+}
+
+/// Completion items for the tables and views of the schema named
+/// `schema_name` starting with `prefix`
+pub fn schema_table_completions(
+    schema: &DatabaseSchema,
+    schema_name: &str,
+    prefix: &str,
+) -> Vec<CompletionItem> {
+    if schema_name.eq_ignore_ascii_case("main") {
+        return table_completions(schema, prefix);
+    }
+
+    schema
+        .schema_tables
+        .iter()
+        .filter(|table| {
+            table.schema.eq_ignore_ascii_case(schema_name) && matches_prefix(&table.name, prefix)
+        })
+        .map(|table| {
+            let (kind, detail) = match table.view {
+                true => (CompletionItemKind::INTERFACE, "view"),
+                false => (CompletionItemKind::STRUCT, "table"),
+            };
+
+            identifier_item(&table.name, kind, detail.to_string(), SORT_TABLE)
+        })
+        .collect()
+}
+
+/// Completion items for the CTEs of the statement starting with `prefix`
+pub fn cte_completions(scope: &StatementScope, prefix: &str) -> Vec<CompletionItem> {
+    scope
+        .ctes
+        .iter()
+        .filter(|cte| matches_prefix(&cte.name, prefix))
+        .map(|cte| {
+            identifier_item(
+                &cte.name,
+                CompletionItemKind::STRUCT,
+                "cte".to_string(),
+                SORT_TABLE,
+            )
+        })
+        .collect()
+}
+
+/// Completion items for the columns of the `cte` starting with `prefix`
+pub fn cte_column_completions(cte: &ScopeCte, prefix: &str) -> Vec<CompletionItem> {
+    cte.columns
+        .iter()
+        .filter(|column| matches_prefix(column, prefix))
+        .map(|column| {
+            identifier_item(
+                column,
+                CompletionItemKind::FIELD,
+                cte.name.clone(),
+                SORT_COLUMN,
+            )
+        })
+        .collect()
+}
+
+fn function_item(name: &str, signature: &str, description: Option<String>) -> CompletionItem {
+    let parameters: Vec<&str> = signature_parameters(signature)
+        .into_iter()
+        .map(|range| &signature[range])
+        .collect();
+
+    CompletionItem {
+        label: name.to_string(),
+        kind: Some(CompletionItemKind::FUNCTION),
+        detail: Some(signature.to_string()),
+        documentation: description.map(Documentation::String),
+        insert_text: Some(call_snippet(name, &parameters)),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        sort_text: Some(format!("{SORT_FUNCTION}{name}")),
+        ..Default::default()
+    }
+}
+
+/// Completion items for the built-in functions and those provided by the
+/// connection (e.g. from extensions) starting with `prefix`
+pub fn function_completions(schema: &DatabaseSchema, prefix: &str) -> Vec<CompletionItem> {
+    let mut names = HashSet::new();
+    let mut items = Vec::new();
+
+    for function in FUNCTIONS {
+        if matches_prefix(function.name, prefix) && names.insert(function.name.to_lowercase()) {
+            items.push(function_item(
+                function.name,
+                function.signature,
+                Some(function.description.to_string()),
+            ));
+        }
+    }
+
+    // Functions are listed once for each number of arguments they accept,
+    // the variadic or largest variant is used
+    for function in &schema.functions {
+        if !matches_prefix(&function.name, prefix) || names.contains(&function.name.to_lowercase())
+        {
+            continue;
+        }
+
+        let arguments = schema
+            .functions
+            .iter()
+            .filter(|other| other.name == function.name)
+            .map(|other| other.arguments)
+            .fold(0, |max, arguments| match (max, arguments) {
+                (-1, _) | (_, -1) => -1,
+                (max, arguments) => max.max(arguments),
+            });
+
+        names.insert(function.name.to_lowercase());
+        items.push(function_item(
+            &function.name,
+            &generic_signature(&function.name, arguments),
+            None,
+        ));
+    }
+
+    items
+}
+
+/// Completion items for the pragmas starting with `prefix`
+pub fn pragma_completions(prefix: &str) -> Vec<CompletionItem> {
+    PRAGMA_DEFINITIONS
+        .iter()
+        .filter(|definition| matches_prefix(definition.name, prefix))
+        .map(|definition| CompletionItem {
+            label: definition.name.to_string(),
+            kind: Some(CompletionItemKind::PROPERTY),
+            detail: Some(definition.url.to_string()),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Completion items for the allowed values of the pragma `name` starting
+/// with `prefix`
+pub fn pragma_value_completions(name: &str, prefix: &str) -> Vec<CompletionItem> {
+    let Some(definition) = PRAGMA_DEFINITIONS
+        .iter()
+        .find(|definition| definition.name.eq_ignore_ascii_case(name))
+    else {
+        return Vec::new();
+    };
+
+    let values: &[&str] = match &definition.ty {
+        PragmaType::Enum { values } => values,
+        PragmaType::Boolean => &["ON", "OFF"],
+        PragmaType::Integer | PragmaType::Text => &[],
+    };
+
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, value)| matches_prefix(value, prefix))
+        .map(|(index, value)| CompletionItem {
+            label: value.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some(definition.url.to_string()),
+            // Keep the order of the definition
+            sort_text: Some(format!("{index:04}")),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::CompletionItemKind;
+
+    use super::{
+        current_word, identifier_insert_text, keyword_completions, schema_table_completions,
+        table_completions,
+    };
+    use crate::database::{
+        DatabaseObject, DatabaseObjectKind, DatabaseSchema, DatabaseSchemaTable, DatabaseTable,
+    };
+
+    fn table(name: &str) -> DatabaseTable {
+        DatabaseTable {
+            name: name.to_string(),
+            sql: String::new(),
+            columns: Vec::new(),
+            foreign_keys: Vec::new(),
+        }
+    }
+
+    fn schema_table(schema: &str, name: &str, view: bool) -> DatabaseSchemaTable {
+        DatabaseSchemaTable {
+            schema: schema.to_string(),
+            name: name.to_string(),
+            view,
+        }
+    }
+
+    fn schema() -> DatabaseSchema {
+        DatabaseSchema {
+            tables: vec![table("users"), table("User Roles"), table("orders")],
+            objects: vec![DatabaseObject {
+                kind: DatabaseObjectKind::View,
+                name: "user_names".to_string(),
+                table_name: "user_names".to_string(),
+                sql: String::new(),
+                columns: Vec::new(),
+            }],
+            schemas: vec!["main".to_string(), "temp".to_string(), "other".to_string()],
+            schema_tables: vec![
+                schema_table("temp", "scratch", false),
+                schema_table("other", "users_archive", false),
+                schema_table("other", "user_view", true),
+            ],
+            functions: Vec::new(),
+        }
+    }
+
+    fn labels(items: &[lsp_types::CompletionItem]) -> Vec<&str> {
+        items.iter().map(|item| item.label.as_str()).collect()
+    }
+
+    #[test]
+    fn test_current_word() {
+        assert_eq!(current_word("SELECT us", 9), (7, "us"));
+        assert_eq!(current_word("SELECT ", 7), (7, ""));
+        assert_eq!(current_word("SELECT é", 8), (7, ""));
+        assert_eq!(current_word("SELECT ab", 100), (7, "ab"));
+    }
+
+    #[test]
+    fn test_identifier_quoting() {
+        assert_eq!(identifier_insert_text("users"), None);
+        assert_eq!(identifier_insert_text("_users2"), None);
+        assert_eq!(
+            identifier_insert_text("User Roles").as_deref(),
+            Some("\"User Roles\"")
+        );
+        assert_eq!(identifier_insert_text("2fa").as_deref(), Some("\"2fa\""));
+        assert_eq!(
+            identifier_insert_text("order").as_deref(),
+            Some("\"order\"")
+        );
+        assert_eq!(
+            identifier_insert_text("say \"hi\"").as_deref(),
+            Some("\"say \"\"hi\"\"\"")
+        );
+    }
+
+    #[test]
+    fn test_prefix_matching() {
+        let schema = schema();
+
+        let items = table_completions(&schema, "US");
+        assert_eq!(labels(&items), ["users", "User Roles", "user_names"]);
+        assert_eq!(items[1].insert_text.as_deref(), Some("\"User Roles\""));
+        assert_eq!(items[2].kind, Some(CompletionItemKind::INTERFACE));
+
+        assert_eq!(table_completions(&schema, "").len(), 4);
+        assert!(table_completions(&schema, "x").is_empty());
+
+        // Completely typed keywords are not suggested again
+        let items = keyword_completions("selec", None);
+        assert_eq!(labels(&items), ["SELECT"]);
+        assert!(keyword_completions("select", None).is_empty());
+    }
+
+    #[test]
+    fn test_schema_table_completions() {
+        let schema = schema();
+
+        let items = schema_table_completions(&schema, "MAIN", "ord");
+        assert_eq!(labels(&items), ["orders"]);
+
+        let items = schema_table_completions(&schema, "temp", "");
+        assert_eq!(labels(&items), ["scratch"]);
+
+        let items = schema_table_completions(&schema, "Other", "user");
+        assert_eq!(labels(&items), ["users_archive", "user_view"]);
+        assert_eq!(items[0].kind, Some(CompletionItemKind::STRUCT));
+        assert_eq!(items[1].kind, Some(CompletionItemKind::INTERFACE));
+
+        assert!(schema_table_completions(&schema, "missing", "").is_empty());
+    }
+}
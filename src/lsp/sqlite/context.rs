@@ -0,0 +1,744 @@
+//! Resolution of what is expected at the cursor from the parsed SQL, along
+//! with the tables and CTEs in scope of the statement
+
+use std::{collections::HashSet, ops::Range};
+
+use tree_sitter::{Node, Tree};
+
+/// Parse state tree-sitter starts parsing from
+const INITIAL_PARSE_STATE: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Keyword,
+    Identifier,
+    Punctuation,
+    /// Literals and operators
+    Other,
+}
+
+/// Leaf of the parsed syntax tree
+#[derive(Debug, Clone, Copy)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub start: usize,
+}
+
+impl Token<'_> {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        self.kind == TokenKind::Keyword && self.text.eq_ignore_ascii_case(keyword)
+    }
+
+    pub fn is_punctuation(&self, punctuation: &str) -> bool {
+        self.kind == TokenKind::Punctuation && self.text == punctuation
+    }
+
+    /// Uppercase text of the keyword, None for other tokens
+    fn keyword(&self) -> Option<String> {
+        (self.kind == TokenKind::Keyword).then(|| self.text.to_ascii_uppercase())
+    }
+}
+
+/// Collect the non-extra leaves of the `tree` parsed from `text` as tokens
+pub fn tokens<'a>(tree: &Tree, text: &'a str) -> Vec<Token<'a>> {
+    let mut tokens = Vec::new();
+    let mut cursor = tree.walk();
+
+    loop {
+        let node = cursor.node();
+
+        // Quoted identifiers have the quotes as children
+        if node.child_count() == 0 || node.kind() == "identifier" {
+            if let Some(token) = leaf_token(node, text) {
+                tokens.push(token);
+            }
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+
+        // Move to the next sibling, walking back up once the children
+        // of a node are exhausted
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return tokens;
+            }
+        }
+    }
+}
+
+fn leaf_token<'a>(node: Node, text: &'a str) -> Option<Token<'a>> {
+    if node.is_extra() || node.is_missing() {
+        return None;
+    }
+
+    let token_text = text.get(node.byte_range())?;
+    let first = token_text.chars().next()?;
+
+    // Words are only lexed as keywords where the grammar accepts the keyword
+    // (or within errors), others such as column names `key` or `data` are
+    // lexed as identifiers
+    let kind = if node.kind().starts_with("keyword_") {
+        TokenKind::Keyword
+    } else if node.kind() == "identifier"
+        || first.is_alphabetic()
+        || matches!(first, '_' | '"' | '`' | '[')
+    {
+        TokenKind::Identifier
+    } else if matches!(token_text, "," | "." | "(" | ")" | ";") {
+        TokenKind::Punctuation
+    } else {
+        TokenKind::Other
+    };
+
+    Some(Token {
+        kind,
+        text: token_text,
+        start: node.start_byte(),
+    })
+}
+
+/// Token index ranges of the statements, excluding the semicolons ending
+/// them, the range after the last semicolon is always included
+///
+/// Semicolons within the body of a `CREATE TRIGGER` statement don't end the
+/// statement, only one following `END` does
+pub fn statement_spans(tokens: &[Token]) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if !token.is_punctuation(";") {
+            continue;
+        }
+
+        let statement = &tokens[start..index];
+        if is_trigger(statement)
+            && !statement
+                .last()
+                .is_some_and(|token| token.is_keyword("END"))
+        {
+            continue;
+        }
+
+        spans.push(start..index);
+        start = index + 1;
+    }
+
+    spans.push(start..tokens.len());
+    spans
+}
+
+/// Whether the `statement` is a CREATE [TEMP] TRIGGER statement
+fn is_trigger(statement: &[Token]) -> bool {
+    match statement {
+        [create, trigger, ..] if create.is_keyword("CREATE") && trigger.is_keyword("TRIGGER") => {
+            true
+        }
+        [create, temp, trigger, ..] => {
+            create.is_keyword("CREATE")
+                && (temp.is_keyword("TEMP") || temp.is_keyword("TEMPORARY"))
+                && trigger.is_keyword("TRIGGER")
+        }
+        _ => false,
+    }
+}
+
+/// Index within `spans` of the statement containing the `offset`
+fn statement_index(tokens: &[Token], spans: &[Range<usize>], offset: usize) -> usize {
+    spans
+        .iter()
+        .position(|span| {
+            tokens
+                .get(span.end)
+                .is_none_or(|semicolon| semicolon.start >= offset)
+        })
+        .unwrap_or(spans.len() - 1)
+}
+
+/// Tokens of the statement containing the `offset`
+pub fn statement_tokens<'a, 'b>(tokens: &'b [Token<'a>], offset: usize) -> &'b [Token<'a>] {
+    let spans = statement_spans(tokens);
+    let index = statement_index(tokens, &spans, offset);
+    &tokens[spans[index].clone()]
+}
+
+/// Byte range of the statement containing the `offset`, the statement
+/// before when the `offset` is after the semicolon of the last statement
+pub fn statement_range(tokens: &[Token], offset: usize) -> Option<Range<usize>> {
+    let spans = statement_spans(tokens);
+    let index = statement_index(tokens, &spans, offset);
+
+    let mut statement = &tokens[spans[index].clone()];
+    if statement.is_empty() {
+        statement = &tokens[spans.get(index.checked_sub(1)?)?.clone()];
+    }
+
+    let (first, last) = (statement.first()?, statement.last()?);
+    Some(first.start..last.start + last.text.len())
+}
+
+/// Index of the token containing the `offset`
+pub fn token_at(tokens: &[Token], offset: usize) -> Option<usize> {
+    tokens
+        .iter()
+        .position(|token| token.start <= offset && offset < token.start + token.text.len())
+}
+
+/// Function call the cursor is within the parentheses of, returns the
+/// function name and the index of the argument at the cursor
+pub fn enclosing_call<'a>(before: &[Token<'a>]) -> Option<(&'a str, usize)> {
+    let mut depth = 0usize;
+    let mut argument = 0;
+
+    for (index, token) in before.iter().enumerate().rev() {
+        if token.is_punctuation(")") {
+            depth += 1;
+        } else if token.is_punctuation("(") {
+            if depth == 0 {
+                let name = before.get(index.checked_sub(1)?)?;
+                // Some function names (e.g. replace, like) are keywords
+                return matches!(name.kind, TokenKind::Identifier | TokenKind::Keyword)
+                    .then_some((name.text, argument));
+            }
+
+            depth -= 1;
+        } else if depth == 0 && token.is_punctuation(",") {
+            argument += 1;
+        }
+    }
+
+    None
+}
+
+/// Keywords the grammar accepts after the last token of the `tree`
+///
+/// Returns None when the valid keywords can't be determined (e.g. the
+/// tree ends in an error)
+pub fn valid_keywords(tree: &Tree) -> Option<HashSet<String>> {
+    let root = tree.root_node();
+    let state = match last_leaf(root) {
+        Some(leaf) => leaf.next_parse_state(),
+        None => INITIAL_PARSE_STATE,
+    };
+
+    if state == 0 {
+        return None;
+    }
+
+    let mut lookahead = root.language().lookahead_iterator(state)?;
+    let keywords: HashSet<String> = lookahead
+        .iter_names()
+        .filter_map(|name| name.strip_prefix("keyword_"))
+        .map(|name| name.to_ascii_uppercase())
+        .collect();
+
+    (!keywords.is_empty()).then_some(keywords)
+}
+
+/// Last non-extra leaf node of the tree
+fn last_leaf(root: Node) -> Option<Node> {
+    let mut node = root;
+
+    loop {
+        let mut cursor = node.walk();
+        let child = node
+            .children(&mut cursor)
+            .filter(|child| !child.is_extra() && !child.is_missing())
+            .last();
+
+        match child {
+            Some(child) => node = child,
+            None if node == root => return None,
+            None => return Some(node),
+        }
+    }
+}
+
+/// What is expected at the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CursorContext {
+    /// Name of a table (after FROM, JOIN, INTO, UPDATE)
+    Table,
+    /// Name of an index (after INDEX)
+    Index,
+    /// Expression using the columns of the in-scope tables (after SELECT,
+    /// WHERE, ON)
+    Expression,
+    /// Column of the table, alias or CTE (after `qualifier.`)
+    Qualified(String),
+    /// Name of a pragma (after PRAGMA)
+    Pragma,
+    /// Value of the named pragma (after `PRAGMA name =`)
+    PragmaValue(String),
+    /// Anything else, only keywords are valid
+    Keyword,
+}
+
+/// Determine the context from the tokens of the statement before the cursor
+pub fn cursor_context(before: &[Token]) -> CursorContext {
+    let Some(last) = before.last() else {
+        return CursorContext::Keyword;
+    };
+
+    if let Some(context) = pragma_context(before) {
+        return context;
+    }
+
+    match last.kind {
+        TokenKind::Keyword => keyword_context(&last.text.to_ascii_uppercase()),
+        TokenKind::Punctuation => match last.text {
+            "." => match before.len().checked_sub(2).map(|index| before[index]) {
+                Some(qualifier) if qualifier.kind == TokenKind::Identifier => {
+                    CursorContext::Qualified(unquote_identifier(qualifier.text))
+                }
+                _ => CursorContext::Keyword,
+            },
+            // Function arguments, subqueries and insert column lists
+            "(" => CursorContext::Expression,
+            "," => match clause_keyword(before).as_deref() {
+                Some("FROM") => CursorContext::Table,
+                Some(_) | None => CursorContext::Expression,
+            },
+            _ => CursorContext::Keyword,
+        },
+        // Operators expect an operand
+        TokenKind::Other if !is_literal(last.text) => CursorContext::Expression,
+        // Complete identifiers and literals are followed by keywords (AS, FROM)
+        _ => CursorContext::Keyword,
+    }
+}
+
+/// Context within a PRAGMA statement (`PRAGMA [schema.]name [= value]`)
+fn pragma_context(before: &[Token]) -> Option<CursorContext> {
+    let pragma = before.iter().position(|token| token.is_keyword("PRAGMA"))?;
+
+    let rest = match &before[pragma + 1..] {
+        [schema, dot, rest @ ..]
+            if schema.kind == TokenKind::Identifier && dot.is_punctuation(".") =>
+        {
+            rest
+        }
+        rest => rest,
+    };
+
+    let context = match rest {
+        [] => CursorContext::Pragma,
+        [name, assign]
+            if name.kind != TokenKind::Punctuation
+                && (assign.text == "=" || assign.is_punctuation("(")) =>
+        {
+            CursorContext::PragmaValue(unquote_identifier(name.text))
+        }
+        _ => CursorContext::Keyword,
+    };
+
+    Some(context)
+}
+
+fn keyword_context(keyword: &str) -> CursorContext {
+    match keyword {
+        "FROM" | "JOIN" | "INTO" | "UPDATE" | "TABLE" => CursorContext::Table,
+        "INDEX" | "INDEXED" => CursorContext::Index,
+        "SELECT" | "WHERE" | "ON" | "AND" | "OR" | "NOT" | "BY" | "SET" | "HAVING" | "WHEN"
+        | "THEN" | "ELSE" | "CASE" | "DISTINCT" | "ALL" | "IN" | "IS" | "LIKE" | "GLOB"
+        | "BETWEEN" | "RETURNING" | "USING" => CursorContext::Expression,
+        _ => CursorContext::Keyword,
+    }
+}
+
+fn is_literal(text: &str) -> bool {
+    text.starts_with(|char: char| char.is_ascii_digit() || char == '\'')
+}
+
+/// Nearest keyword before the end of `tokens` at the same parenthesis depth,
+/// ignoring the AS of aliases
+fn clause_keyword(tokens: &[Token]) -> Option<String> {
+    let mut depth = 0usize;
+
+    for token in tokens.iter().rev() {
+        if token.is_punctuation(")") {
+            depth += 1;
+        } else if token.is_punctuation("(") {
+            if depth == 0 {
+                return None;
+            }
+            depth -= 1;
+        } else if depth == 0 && token.kind == TokenKind::Keyword && !token.is_keyword("AS") {
+            return token.keyword();
+        }
+    }
+
+    None
+}
+
+/// Remove the quotes from a quoted identifier
+pub fn unquote_identifier(identifier: &str) -> String {
+    let mut chars = identifier.chars();
+    let (Some(first), Some(last)) = (chars.next(), chars.next_back()) else {
+        return identifier.to_string();
+    };
+
+    match (first, last) {
+        ('"', '"') => identifier[1..identifier.len() - 1].replace("\"\"", "\""),
+        ('`', '`') => identifier[1..identifier.len() - 1].replace("``", "`"),
+        ('[', ']') => identifier[1..identifier.len() - 1].to_string(),
+        _ => identifier.to_string(),
+    }
+}
+
+/// Table referenced by a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeTable {
+    pub name: String,
+    pub alias: Option<String>,
+}
+
+/// Common table expression defined by a statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeCte {
+    pub name: String,
+    pub columns: Vec<String>,
+}
+
+/// Tables and CTEs in scope of a statement
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementScope {
+    pub tables: Vec<ScopeTable>,
+    pub ctes: Vec<ScopeCte>,
+}
+
+impl StatementScope {
+    /// Collect the tables referenced and CTEs defined within the statement
+    pub fn new(tokens: &[Token]) -> Self {
+        let mut scope = Self::default();
+
+        for (index, token) in tokens.iter().enumerate() {
+            match token.keyword().as_deref() {
+                Some("WITH") => scope.read_ctes(&tokens[index + 1..]),
+                Some("FROM") => scope.read_tables(&tokens[index + 1..], true),
+                Some("JOIN" | "UPDATE" | "INTO") => scope.read_tables(&tokens[index + 1..], false),
+                _ => {}
+            }
+        }
+
+        scope
+    }
+
+    /// Name of the table or CTE the `qualifier` (alias or name) refers to
+    pub fn resolve(&self, qualifier: &str) -> Option<&str> {
+        let table = self.tables.iter().find(|table| {
+            table
+                .alias
+                .as_ref()
+                .is_some_and(|alias| alias.eq_ignore_ascii_case(qualifier))
+        });
+
+        if let Some(table) = table {
+            return Some(&table.name);
+        }
+
+        self.tables
+            .iter()
+            .map(|table| table.name.as_str())
+            .chain(self.ctes.iter().map(|cte| cte.name.as_str()))
+            .find(|name| name.eq_ignore_ascii_case(qualifier))
+    }
+
+    /// Whether the table `name` is referenced by the statement
+    pub fn contains_table(&self, name: &str) -> bool {
+        self.tables
+            .iter()
+            .any(|table| table.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Read the table references (`[schema.]table [AS] [alias]`) following
+    /// a keyword, `list` allows comma separated references
+    fn read_tables(&mut self, tokens: &[Token], list: bool) {
+        let mut index = 0;
+
+        while let Some(token) = tokens.get(index) {
+            if token.kind != TokenKind::Identifier {
+                return;
+            }
+
+            let mut name = token.text;
+            index += 1;
+
+            // Schema qualified table
+            if tokens
+                .get(index)
+                .is_some_and(|token| token.is_punctuation("."))
+                && let Some(table) = tokens.get(index + 1)
+                && table.kind == TokenKind::Identifier
+            {
+                name = table.text;
+                index += 2;
+            }
+
+            if tokens
+                .get(index)
+                .is_some_and(|token| token.is_keyword("AS"))
+            {
+                index += 1;
+            }
+
+            let alias = match tokens.get(index) {
+                Some(token) if token.kind == TokenKind::Identifier => {
+                    index += 1;
+                    Some(unquote_identifier(token.text))
+                }
+                _ => None,
+            };
+
+            self.tables.push(ScopeTable {
+                name: unquote_identifier(name),
+                alias,
+            });
+
+            if !list
+                || !tokens
+                    .get(index)
+                    .is_some_and(|token| token.is_punctuation(","))
+            {
+                return;
+            }
+
+            index += 1;
+        }
+    }
+
+    /// Read the CTE definitions (`name [(columns)] AS (select)`) following
+    /// a WITH keyword
+    fn read_ctes(&mut self, tokens: &[Token]) {
+        let mut index = 0;
+
+        if tokens
+            .first()
+            .is_some_and(|token| token.is_keyword("RECURSIVE"))
+        {
+            index += 1;
+        }
+
+        while let Some(token) = tokens.get(index) {
+            if token.kind != TokenKind::Identifier {
+                return;
+            }
+
+            let name = unquote_identifier(token.text);
+            let mut columns = Vec::new();
+            index += 1;
+
+            // Explicit column names
+            if tokens
+                .get(index)
+                .is_some_and(|token| token.is_punctuation("("))
+            {
+                index += 1;
+
+                while let Some(token) = tokens.get(index) {
+                    index += 1;
+
+                    match token.kind {
+                        TokenKind::Identifier => columns.push(unquote_identifier(token.text)),
+                        TokenKind::Punctuation if token.text == ")" => break,
+                        _ => {}
+                    }
+                }
+            }
+
+            // AS [NOT] [MATERIALIZED]
+            while tokens.get(index).is_some_and(|token| {
+                token.is_keyword("AS")
+                    || token.is_keyword("NOT")
+                    || token.is_keyword("MATERIALIZED")
+            }) {
+                index += 1;
+            }
+
+            if !tokens
+                .get(index)
+                .is_some_and(|token| token.is_punctuation("("))
+            {
+                self.ctes.push(ScopeCte { name, columns });
+                return;
+            }
+
+            let body_start = index + 1;
+            let body_end = matching_parenthesis(tokens, index).unwrap_or(tokens.len());
+
+            if columns.is_empty() {
+                columns = select_output_names(&tokens[body_start..body_end]);
+            }
+
+            self.ctes.push(ScopeCte { name, columns });
+
+            index = body_end + 1;
+            if !tokens
+                .get(index)
+                .is_some_and(|token| token.is_punctuation(","))
+            {
+                return;
+            }
+
+            index += 1;
+        }
+    }
+}
+
+/// Index of the parenthesis closing the one at `open`
+fn matching_parenthesis(tokens: &[Token], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punctuation("(") {
+            depth += 1;
+        } else if token.is_punctuation(")") {
+            depth -= 1;
+
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+    }
+
+    None
+}
+
+/// Names of the result columns of a SELECT statement, taken from the last
+/// identifier of each result column expression
+fn select_output_names(tokens: &[Token]) -> Vec<String> {
+    if !tokens
+        .first()
+        .is_some_and(|token| token.is_keyword("SELECT"))
+    {
+        return Vec::new();
+    }
+
+    let mut names = Vec::new();
+    let mut depth = 0usize;
+    let mut last: Option<&Token> = None;
+
+    for token in &tokens[1..] {
+        if token.is_punctuation("(") {
+            depth += 1;
+        } else if token.is_punctuation(")") {
+            depth = depth.saturating_sub(1);
+        } else if depth == 0 && (token.is_punctuation(",") || token.is_keyword("FROM")) {
+            names.extend(last.take().map(|token| unquote_identifier(token.text)));
+
+            if token.is_keyword("FROM") {
+                return names;
+            }
+
+            continue;
+        }
+
+        last = (depth == 0 && token.kind == TokenKind::Identifier).then_some(token);
+    }
+
+    names.extend(last.map(|token| unquote_identifier(token.text)));
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CursorContext, ScopeCte, ScopeTable, StatementScope, TokenKind, cursor_context,
+        statement_tokens, tokens,
+    };
+
+    fn parse(text: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_sequel::LANGUAGE.into())
+            .unwrap();
+        parser.parse(text, None).unwrap()
+    }
+
+    fn scope(text: &str) -> StatementScope {
+        let tree = parse(text);
+        let tokens = tokens(&tree, text);
+        StatementScope::new(statement_tokens(&tokens, text.len()))
+    }
+
+    fn table(name: &str, alias: Option<&str>) -> ScopeTable {
+        ScopeTable {
+            name: name.to_string(),
+            alias: alias.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_statement_scope() {
+        let scope = scope(
+            "SELECT 1;\n\
+            SELECT u.name, o.total FROM users AS u \
+            JOIN main.orders o ON o.user_id = u.id WHERE u.id = 1",
+        );
+
+        assert_eq!(
+            scope.tables,
+            [table("users", Some("u")), table("orders", Some("o"))]
+        );
+        assert!(scope.ctes.is_empty());
+        assert!(scope.contains_table("USERS"));
+        assert!(!scope.contains_table("u"));
+    }
+
+    #[test]
+    fn test_alias_resolution() {
+        let scope = scope("SELECT u.name FROM users u, \"Order Items\" AS items");
+
+        assert_eq!(scope.resolve("U"), Some("users"));
+        assert_eq!(scope.resolve("items"), Some("Order Items"));
+        assert_eq!(scope.resolve("users"), Some("users"));
+        assert_eq!(scope.resolve("orders"), None);
+    }
+
+    #[test]
+    fn test_keyword_named_identifiers() {
+        let text = "SELECT action.key FROM settings AS action";
+        let tree = parse(text);
+        let tokens = tokens(&tree, text);
+
+        let key = tokens.iter().find(|token| token.text == "key").unwrap();
+        assert_eq!(key.kind, TokenKind::Identifier);
+
+        let scope = StatementScope::new(&tokens);
+        assert_eq!(scope.tables, [table("settings", Some("action"))]);
+        assert_eq!(scope.resolve("action"), Some("settings"));
+
+        let dot = tokens.iter().position(|token| token.text == ".").unwrap();
+        assert_eq!(
+            cursor_context(&tokens[..=dot]),
+            CursorContext::Qualified("action".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cte_resolution() {
+        let scope = scope(
+            "WITH recent(id, amount) AS (SELECT id, total FROM orders), \
+            names AS (SELECT u.id, u.name AS label FROM users u) \
+            SELECT * FROM recent JOIN names ON names.id = recent.id",
+        );
+
+        assert_eq!(
+            scope.ctes,
+            [
+                ScopeCte {
+                    name: "recent".to_string(),
+                    columns: vec!["id".to_string(), "amount".to_string()],
+                },
+                ScopeCte {
+                    name: "names".to_string(),
+                    columns: vec!["id".to_string(), "label".to_string()],
+                },
+            ]
+        );
+        assert_eq!(scope.resolve("u"), Some("users"));
+        assert_eq!(scope.resolve("NAMES"), Some("names"));
+        assert!(scope.contains_table("orders"));
+    }
+}
@@ -0,0 +1,306 @@
+//! Syntax errors from the parsed SQL and errors from preparing the
+//! statements, reported as diagnostics
+
+use std::ops::Range;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity};
+use tree_sitter::Tree;
+
+use crate::{
+    lsp::sqlite::context::{Token, TokenKind, statement_spans, unquote_identifier},
+    utils::sql_format::text_position,
+};
+
+/// Maximum length of the text quoted within a syntax error
+const MAX_ERROR_TEXT: usize = 32;
+
+/// Statements that change the schema, statements after them can't be
+/// prepared as they may reference the objects they create
+const SCHEMA_KEYWORDS: &[&str] = &["CREATE", "DROP", "ALTER", "ATTACH", "DETACH"];
+
+/// Statements that are never prepared, only checked for syntax errors, as
+/// preparing them has effects on the connection (e.g. most pragmas and
+/// SQLCipher keys are applied while preparing)
+const SYNTAX_ONLY_KEYWORDS: &[&str] = &[
+    "PRAGMA",
+    "ATTACH",
+    "DETACH",
+    "VACUUM",
+    "BEGIN",
+    "COMMIT",
+    "END",
+    "ROLLBACK",
+    "SAVEPOINT",
+    "RELEASE",
+];
+
+/// Error at a byte range of the text
+#[derive(Debug, Clone)]
+pub struct TextError {
+    pub range: Range<usize>,
+    pub message: String,
+}
+
+/// Statement within the text to produce diagnostics for
+#[derive(Debug, Clone)]
+pub struct DiagnosticsStatement {
+    pub range: Range<usize>,
+    /// Syntax errors reported by the parser within the statement
+    pub syntax_errors: Vec<TextError>,
+    /// Whether the statement can be prepared to check for errors
+    pub prepare: bool,
+    /// Identifiers within the statement used to locate prepare errors
+    identifiers: Vec<(Range<usize>, String)>,
+}
+
+/// Split the `tokens` into statements, assigning each the syntax errors
+/// of the `tree` within it
+pub fn diagnostics_statements(tree: &Tree, tokens: &[Token]) -> Vec<DiagnosticsStatement> {
+    let mut syntax_errors = syntax_errors(tree);
+    let mut statements = Vec::new();
+    let mut schema_changed = false;
+
+    for span in statement_spans(tokens) {
+        let statement = &tokens[span];
+        let (Some(first), Some(last)) = (statement.first(), statement.last()) else {
+            continue;
+        };
+
+        let range = first.start..last.start + last.text.len();
+
+        let (statement_errors, remaining) = syntax_errors
+            .into_iter()
+            .partition(|error: &TextError| error.range.start < range.end);
+        syntax_errors = remaining;
+
+        // EXPLAIN still prepares the explained statement
+        let verb = statement
+            .iter()
+            .find(|token| {
+                !["EXPLAIN", "QUERY", "PLAN"]
+                    .iter()
+                    .any(|keyword| token.text.eq_ignore_ascii_case(keyword))
+            })
+            .unwrap_or(first);
+        let syntax_only = SYNTAX_ONLY_KEYWORDS
+            .iter()
+            .any(|keyword| verb.text.eq_ignore_ascii_case(keyword));
+
+        statements.push(DiagnosticsStatement {
+            range,
+            syntax_errors: statement_errors,
+            prepare: !schema_changed && !syntax_only,
+            identifiers: statement
+                .iter()
+                .filter(|token| token.kind == TokenKind::Identifier)
+                .map(|token| {
+                    let range = token.start..token.start + token.text.len();
+                    (range, unquote_identifier(token.text))
+                })
+                .collect(),
+        });
+
+        schema_changed |= SCHEMA_KEYWORDS
+            .iter()
+            .any(|keyword| first.text.eq_ignore_ascii_case(keyword));
+    }
+
+    // Errors after the last statement (e.g. an unterminated string)
+    if let Some(statement) = statements.last_mut() {
+        statement.syntax_errors.extend(syntax_errors);
+    }
+
+    statements
+}
+
+/// Collect the ERROR and MISSING nodes of the `tree` as syntax errors
+fn syntax_errors(tree: &Tree) -> Vec<TextError> {
+    let mut errors = Vec::new();
+    let mut cursor = tree.walk();
+
+    loop {
+        let node = cursor.node();
+
+        if node.is_error() {
+            errors.push(TextError {
+                range: node.byte_range(),
+                message: String::new(),
+            });
+        } else if node.is_missing() {
+            errors.push(TextError {
+                range: node.start_byte()..node.end_byte(),
+                message: format!("Syntax error: missing {}", node.kind()),
+            });
+        } else if cursor.goto_first_child() {
+            continue;
+        }
+
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return errors;
+            }
+        }
+    }
+}
+
+/// Combine the syntax errors and the results of preparing the statements
+/// (in order of the statements to prepare) into diagnostics
+///
+/// Syntax errors are only reported for statements SQLite also fails to
+/// prepare, as the parser doesn't cover all of the SQLite syntax
+pub fn resolve_diagnostics(
+    text: &str,
+    statements: Vec<DiagnosticsStatement>,
+    prepare_errors: Vec<Option<String>>,
+) -> Vec<Diagnostic> {
+    let mut prepare_errors = prepare_errors.into_iter();
+    let mut diagnostics = Vec::new();
+
+    for statement in statements {
+        let prepare_error = match statement.prepare {
+            true => match prepare_errors.next() {
+                Some(error) => error,
+                // Statement wasn't prepared, rely on the syntax errors
+                None => Some(String::new()),
+            },
+            false => Some(String::new()),
+        };
+
+        let Some(prepare_error) = prepare_error else {
+            continue;
+        };
+
+        if !statement.syntax_errors.is_empty() {
+            diagnostics.extend(statement.syntax_errors.iter().map(|error| {
+                let message = match error.message.is_empty() {
+                    true => syntax_error_message(text, &error.range, &prepare_error),
+                    false => error.message.clone(),
+                };
+
+                diagnostic(text, error.range.clone(), message)
+            }));
+        } else if !prepare_error.is_empty() {
+            let range = prepare_error_range(&statement, &prepare_error);
+            diagnostics.push(diagnostic(text, range, prepare_error));
+        }
+    }
+
+    diagnostics
+}
+
+fn syntax_error_message(text: &str, range: &Range<usize>, prepare_error: &str) -> String {
+    // SQLite's message is more descriptive when it is a syntax error
+    if prepare_error.contains("syntax error") || prepare_error.contains("incomplete input") {
+        return prepare_error.to_string();
+    }
+
+    let error_text: String = text
+        .get(range.clone())
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(MAX_ERROR_TEXT)
+        .collect();
+
+    format!("Syntax error near \"{error_text}\"")
+}
+
+/// Narrow the range of a prepare error to the unknown table or column it
+/// refers to, the whole statement otherwise
+fn prepare_error_range(statement: &DiagnosticsStatement, error: &str) -> Range<usize> {
+    let name = ["no such table: ", "no such column: ", "no such function: "]
+        .iter()
+        .find_map(|prefix| error.split_once(prefix))
+        .map(|(_, name)| name.rsplit('.').next().unwrap_or(name).trim());
+
+    name.and_then(|name| {
+        statement
+            .identifiers
+            .iter()
+            .find(|(_, identifier)| identifier.eq_ignore_ascii_case(name))
+            .map(|(range, _)| range.clone())
+    })
+    .unwrap_or_else(|| statement.range.clone())
+}
+
+fn diagnostic(text: &str, range: Range<usize>, message: String) -> Diagnostic {
+    Diagnostic {
+        range: lsp_types::Range {
+            start: text_position(text, range.start),
+            end: text_position(text, range.end),
+        },
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("sqlite".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diagnostics_statements;
+    use crate::{
+        database::{Database, DatabaseValue, sqlite::SqliteDatabase},
+        lsp::sqlite::context::{statement_range, tokens},
+    };
+
+    fn parse(text: &str) -> tree_sitter::Tree {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_sequel::LANGUAGE.into())
+            .unwrap();
+        parser.parse(text, None).unwrap()
+    }
+
+    #[test]
+    fn test_trigger_statements() {
+        let text = "CREATE TABLE t (a);\n\
+            CREATE TRIGGER tr AFTER INSERT ON t BEGIN\n  \
+            INSERT INTO t VALUES (1);\n  \
+            DELETE FROM t;\n\
+            END;\n\
+            SELECT a FROM t;";
+
+        let tree = parse(text);
+        let tokens = tokens(&tree, text);
+        let statements: Vec<&str> = diagnostics_statements(&tree, &tokens)
+            .into_iter()
+            .map(|statement| &text[statement.range])
+            .collect();
+
+        assert_eq!(statements.len(), 3);
+        assert_eq!(statements[0], "CREATE TABLE t (a)");
+        assert!(statements[1].starts_with("CREATE TRIGGER"));
+        assert!(statements[1].ends_with("END"));
+        assert_eq!(statements[2], "SELECT a FROM t");
+
+        // The whole trigger is the statement at a cursor within its body
+        let offset = text.find("DELETE").unwrap();
+        let range = statement_range(&tokens, offset).unwrap();
+        assert_eq!(&text[range], statements[1]);
+    }
+
+    #[tokio::test]
+    async fn test_pragma_not_prepared() {
+        let database = SqliteDatabase::memory().await.unwrap();
+        database.query("PRAGMA foreign_keys = ON").await.unwrap();
+
+        let text = "PRAGMA foreign_keys = OFF;\n\
+            EXPLAIN PRAGMA foreign_keys = OFF;\n\
+            BEGIN;\n\
+            SELECT 1;";
+        let tree = parse(text);
+        let tokens = tokens(&tree, text);
+        let prepare: Vec<String> = diagnostics_statements(&tree, &tokens)
+            .into_iter()
+            .filter(|statement| statement.prepare)
+            .map(|statement| text[statement.range].to_string())
+            .collect();
+
+        assert_eq!(prepare, ["SELECT 1"]);
+
+        database.prepare_statements(prepare).await.unwrap();
+        let rows = database.query("PRAGMA foreign_keys").await.unwrap().rows;
+        assert_eq!(rows[0].values, [DatabaseValue::Integer(1)]);
+    }
+}
@@ -0,0 +1,727 @@
+//! Catalogue of the built-in SQLite functions
+
+use std::ops::Range;
+
+/// Group of functions sharing a page within the SQLite documentation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionCategory {
+    Core,
+    Date,
+    Aggregate,
+    Window,
+    Math,
+    Json,
+    Fts5,
+}
+
+impl FunctionCategory {
+    fn docs_page(&self) -> &'static str {
+        match self {
+            FunctionCategory::Core => "https://www.sqlite.org/lang_corefunc.html",
+            FunctionCategory::Date => "https://www.sqlite.org/lang_datefunc.html",
+            FunctionCategory::Aggregate => "https://www.sqlite.org/lang_aggfunc.html",
+            FunctionCategory::Window => "https://www.sqlite.org/windowfunctions.html",
+            FunctionCategory::Math => "https://www.sqlite.org/lang_mathfunc.html",
+            FunctionCategory::Json => "https://www.sqlite.org/json1.html",
+            FunctionCategory::Fts5 => "https://www.sqlite.org/fts5.html",
+        }
+    }
+}
+
+/// Built-in SQLite function
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteFunction {
+    pub name: &'static str,
+    /// Signature of the function, e.g. `substr(X, Y, Z)`
+    pub signature: &'static str,
+    pub description: &'static str,
+    pub category: FunctionCategory,
+    /// Anchor of the function within the documentation page
+    anchor: &'static str,
+}
+
+impl SqliteFunction {
+    /// Link to the function within the SQLite documentation
+    pub fn docs_url(&self) -> String {
+        match self.anchor.is_empty() {
+            true => self.category.docs_page().to_string(),
+            false => format!("{}#{}", self.category.docs_page(), self.anchor),
+        }
+    }
+}
+
+/// Byte ranges of the parameters within the function `signature`, e.g. the
+/// ranges of `X` and `Y` within `instr(X, Y)`
+pub fn signature_parameters(signature: &str) -> Vec<Range<usize>> {
+    let (Some(open), Some(close)) = (signature.find('('), signature.rfind(')')) else {
+        return Vec::new();
+    };
+
+    let mut parameters = Vec::new();
+    let mut start = open + 1;
+
+    for (index, char) in signature[start..close].char_indices() {
+        if char == ',' {
+            parameters.push(start..open + 1 + index);
+            start = open + 1 + index + 1;
+        }
+    }
+    parameters.push(start..close);
+
+    parameters
+        .into_iter()
+        .map(|range| {
+            // Exclude the whitespace around the parameter names
+            let text = &signature[range.clone()];
+            let leading = text.len() - text.trim_start().len();
+            range.start + leading..range.start + leading + text.trim().len()
+        })
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// Snippet inserting a call to the function `name` with a placeholder for
+/// each of the `parameters`, variadic `...` parameters are left out
+pub fn call_snippet(name: &str, parameters: &[&str]) -> String {
+    let placeholders: Vec<String> = parameters
+        .iter()
+        .filter(|parameter| **parameter != "...")
+        .enumerate()
+        .map(|(index, parameter)| format!("${{{}:{parameter}}}", index + 1))
+        .collect();
+
+    format!("{name}({})$0", placeholders.join(", "))
+}
+
+/// Signature of a function only known by its number of `arguments`
+/// (e.g. a function from an extension), -1 accepts any number
+pub fn generic_signature(name: &str, arguments: i32) -> String {
+    let parameters: Vec<String> = match arguments {
+        ..0 => vec!["...".to_string()],
+        arguments => (1..=arguments).map(|index| format!("X{index}")).collect(),
+    };
+
+    format!("{name}({})", parameters.join(", "))
+}
+
+/// Find the built-in function with the `name`
+pub fn find_function(name: &str) -> Option<&'static SqliteFunction> {
+    FUNCTIONS
+        .iter()
+        .find(|function| function.name.eq_ignore_ascii_case(name))
+}
+
+const fn function(
+    category: FunctionCategory,
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+    anchor: &'static str,
+) -> SqliteFunction {
+    SqliteFunction {
+        name,
+        signature,
+        description,
+        category,
+        anchor,
+    }
+}
+
+const fn core(
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+) -> SqliteFunction {
+    function(FunctionCategory::Core, name, signature, description, name)
+}
+
+const fn date(
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+) -> SqliteFunction {
+    function(FunctionCategory::Date, name, signature, description, "")
+}
+
+const fn math(
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+) -> SqliteFunction {
+    function(FunctionCategory::Math, name, signature, description, name)
+}
+
+/// Function documented on a single page without individual anchors
+const fn page(
+    category: FunctionCategory,
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+) -> SqliteFunction {
+    function(category, name, signature, description, "")
+}
+
+const fn aggregate(
+    name: &'static str,
+    signature: &'static str,
+    description: &'static str,
+    anchor: &'static str,
+) -> SqliteFunction {
+    function(
+        FunctionCategory::Aggregate,
+        name,
+        signature,
+        description,
+        anchor,
+    )
+}
+
+pub static FUNCTIONS: &[SqliteFunction] = &[
+    // Core functions
+    core("abs", "abs(X)", "Absolute value of the numeric argument X"),
+    core(
+        "changes",
+        "changes()",
+        "Number of rows modified by the most recently completed INSERT, UPDATE or DELETE",
+    ),
+    core(
+        "char",
+        "char(X1, X2, ..., XN)",
+        "String composed of the characters with the unicode code points X1 to XN",
+    ),
+    core(
+        "coalesce",
+        "coalesce(X, Y, ...)",
+        "First non-NULL argument, or NULL if all arguments are NULL",
+    ),
+    core(
+        "concat",
+        "concat(X, ...)",
+        "Concatenation of the text of all non-NULL arguments",
+    ),
+    core(
+        "concat_ws",
+        "concat_ws(SEP, X, ...)",
+        "Concatenation of the non-NULL arguments separated by SEP",
+    ),
+    core(
+        "format",
+        "format(FORMAT, ...)",
+        "String formatted like printf from the FORMAT and the arguments",
+    ),
+    core(
+        "glob",
+        "glob(X, Y)",
+        "Whether Y matches the glob pattern X, equivalent to Y GLOB X",
+    ),
+    core(
+        "hex",
+        "hex(X)",
+        "Upper-case hexadecimal rendering of the content of X",
+    ),
+    core("ifnull", "ifnull(X, Y)", "First non-NULL of X and Y"),
+    core("iif", "iif(X, Y, Z)", "Y when X is true, Z otherwise"),
+    core(
+        "instr",
+        "instr(X, Y)",
+        "1 based position of the first occurrence of Y within X, 0 if not found",
+    ),
+    core(
+        "last_insert_rowid",
+        "last_insert_rowid()",
+        "Rowid of the most recent successful INSERT",
+    ),
+    core(
+        "length",
+        "length(X)",
+        "Number of characters in the text X or bytes in the blob X",
+    ),
+    core(
+        "like",
+        "like(X, Y, Z)",
+        "Whether Y matches the LIKE pattern X with the optional escape character Z",
+    ),
+    core(
+        "likelihood",
+        "likelihood(X, Y)",
+        "X unchanged, hints to the planner that X is true with probability Y",
+    ),
+    core(
+        "likely",
+        "likely(X)",
+        "X unchanged, hints to the planner that X is usually true",
+    ),
+    core(
+        "load_extension",
+        "load_extension(X, Y)",
+        "Load the SQLite extension from the shared library file X with the entry point Y",
+    ),
+    core(
+        "lower",
+        "lower(X)",
+        "Copy of the string X with ASCII characters converted to lower case",
+    ),
+    core(
+        "ltrim",
+        "ltrim(X, Y)",
+        "X with the characters in Y (spaces by default) removed from the start",
+    ),
+    core("max", "max(X, Y, ...)", "Argument with the maximum value"),
+    core("min", "min(X, Y, ...)", "Argument with the minimum value"),
+    core(
+        "nullif",
+        "nullif(X, Y)",
+        "NULL when X and Y are equal, X otherwise",
+    ),
+    core(
+        "octet_length",
+        "octet_length(X)",
+        "Number of bytes in the encoding of the text or blob X",
+    ),
+    core("printf", "printf(FORMAT, ...)", "Alias of format"),
+    core(
+        "quote",
+        "quote(X)",
+        "Text of the SQL literal suitable for inclusion in a statement for the value X",
+    ),
+    core(
+        "random",
+        "random()",
+        "Pseudo-random integer between -9223372036854775808 and +9223372036854775807",
+    ),
+    core(
+        "randomblob",
+        "randomblob(N)",
+        "Blob containing N pseudo-random bytes",
+    ),
+    core(
+        "replace",
+        "replace(X, Y, Z)",
+        "X with every occurrence of Y replaced by Z",
+    ),
+    core(
+        "round",
+        "round(X, Y)",
+        "X rounded to Y digits to the right of the decimal point",
+    ),
+    core(
+        "rtrim",
+        "rtrim(X, Y)",
+        "X with the characters in Y (spaces by default) removed from the end",
+    ),
+    core(
+        "sign",
+        "sign(X)",
+        "-1, 0 or +1 when X is negative, zero or positive",
+    ),
+    core("soundex", "soundex(X)", "Soundex encoding of the string X"),
+    core(
+        "sqlite_version",
+        "sqlite_version()",
+        "Version string of the SQLite library",
+    ),
+    core(
+        "substr",
+        "substr(X, Y, Z)",
+        "Substring of X starting at the Y-th character that is Z characters long",
+    ),
+    core("substring", "substring(X, Y, Z)", "Alias of substr"),
+    core(
+        "total_changes",
+        "total_changes()",
+        "Number of rows modified since the database connection was opened",
+    ),
+    core(
+        "trim",
+        "trim(X, Y)",
+        "X with the characters in Y (spaces by default) removed from both ends",
+    ),
+    core(
+        "typeof",
+        "typeof(X)",
+        "Datatype of X: null, integer, real, text or blob",
+    ),
+    core(
+        "unhex",
+        "unhex(X, Y)",
+        "Blob decoded from the hexadecimal string X, ignoring the characters in Y",
+    ),
+    core(
+        "unicode",
+        "unicode(X)",
+        "Unicode code point of the first character of X",
+    ),
+    core(
+        "unlikely",
+        "unlikely(X)",
+        "X unchanged, hints to the planner that X is usually false",
+    ),
+    core(
+        "upper",
+        "upper(X)",
+        "Copy of the string X with ASCII characters converted to upper case",
+    ),
+    core("zeroblob", "zeroblob(N)", "Blob of N 0x00 bytes"),
+    // Date and time functions
+    date(
+        "date",
+        "date(TIME, MODIFIER, ...)",
+        "Date as text in the format YYYY-MM-DD",
+    ),
+    date(
+        "time",
+        "time(TIME, MODIFIER, ...)",
+        "Time as text in the format HH:MM:SS",
+    ),
+    date(
+        "datetime",
+        "datetime(TIME, MODIFIER, ...)",
+        "Date and time as text in the format YYYY-MM-DD HH:MM:SS",
+    ),
+    date(
+        "julianday",
+        "julianday(TIME, MODIFIER, ...)",
+        "Number of days since noon in Greenwich on November 24, 4714 B.C.",
+    ),
+    date(
+        "unixepoch",
+        "unixepoch(TIME, MODIFIER, ...)",
+        "Number of seconds since 1970-01-01 00:00:00 UTC",
+    ),
+    date(
+        "strftime",
+        "strftime(FORMAT, TIME, MODIFIER, ...)",
+        "Date formatted according to the FORMAT string",
+    ),
+    date(
+        "timediff",
+        "timediff(TIME_A, TIME_B)",
+        "Amount of time that must be added to TIME_B to reach TIME_A",
+    ),
+    // Aggregate functions
+    aggregate(
+        "avg",
+        "avg(X)",
+        "Average value of all non-NULL X within a group",
+        "avg",
+    ),
+    aggregate(
+        "count",
+        "count(X)",
+        "Number of times X is not NULL within a group, count(*) counts the rows",
+        "count",
+    ),
+    aggregate(
+        "group_concat",
+        "group_concat(X, Y)",
+        "Concatenation of all non-NULL X separated by Y (a comma by default)",
+        "group_concat",
+    ),
+    aggregate(
+        "string_agg",
+        "string_agg(X, Y)",
+        "Alias of group_concat",
+        "group_concat",
+    ),
+    aggregate(
+        "sum",
+        "sum(X)",
+        "Sum of all non-NULL values within a group",
+        "sumunc",
+    ),
+    aggregate(
+        "total",
+        "total(X)",
+        "Sum of all non-NULL values within a group as a floating point value",
+        "sumunc",
+    ),
+    // Window functions
+    page(
+        FunctionCategory::Window,
+        "row_number",
+        "row_number()",
+        "Number of the row within the current partition, starting from 1",
+    ),
+    page(
+        FunctionCategory::Window,
+        "rank",
+        "rank()",
+        "Row number of the first peer in each group, with gaps",
+    ),
+    page(
+        FunctionCategory::Window,
+        "dense_rank",
+        "dense_rank()",
+        "Number of the current row's peer group within its partition, without gaps",
+    ),
+    page(
+        FunctionCategory::Window,
+        "percent_rank",
+        "percent_rank()",
+        "(rank - 1) / (partition rows - 1), between 0.0 and 1.0",
+    ),
+    page(
+        FunctionCategory::Window,
+        "cume_dist",
+        "cume_dist()",
+        "Cumulative distribution, row number of the last peer divided by the partition rows",
+    ),
+    page(
+        FunctionCategory::Window,
+        "ntile",
+        "ntile(N)",
+        "Divides the partition into N groups and returns the group of the row",
+    ),
+    page(
+        FunctionCategory::Window,
+        "lag",
+        "lag(EXPR, OFFSET, DEFAULT)",
+        "EXPR evaluated against the row OFFSET rows before the current row",
+    ),
+    page(
+        FunctionCategory::Window,
+        "lead",
+        "lead(EXPR, OFFSET, DEFAULT)",
+        "EXPR evaluated against the row OFFSET rows after the current row",
+    ),
+    page(
+        FunctionCategory::Window,
+        "first_value",
+        "first_value(EXPR)",
+        "EXPR evaluated against the first row of the window frame",
+    ),
+    page(
+        FunctionCategory::Window,
+        "last_value",
+        "last_value(EXPR)",
+        "EXPR evaluated against the last row of the window frame",
+    ),
+    page(
+        FunctionCategory::Window,
+        "nth_value",
+        "nth_value(EXPR, N)",
+        "EXPR evaluated against the row N of the window frame",
+    ),
+    // Math functions
+    math("acos", "acos(X)", "Arccosine of X in radians"),
+    math("acosh", "acosh(X)", "Hyperbolic arccosine of X"),
+    math("asin", "asin(X)", "Arcsine of X in radians"),
+    math("asinh", "asinh(X)", "Hyperbolic arcsine of X"),
+    math("atan", "atan(X)", "Arctangent of X in radians"),
+    math(
+        "atan2",
+        "atan2(Y, X)",
+        "Arctangent of Y/X in radians, using the signs to determine the quadrant",
+    ),
+    math("atanh", "atanh(X)", "Hyperbolic arctangent of X"),
+    math(
+        "ceil",
+        "ceil(X)",
+        "First representable integer value greater than or equal to X",
+    ),
+    math("ceiling", "ceiling(X)", "Alias of ceil"),
+    math("cos", "cos(X)", "Cosine of X in radians"),
+    math("cosh", "cosh(X)", "Hyperbolic cosine of X"),
+    math(
+        "degrees",
+        "degrees(X)",
+        "Converts X from radians into degrees",
+    ),
+    math("exp", "exp(X)", "e raised to the power X"),
+    math(
+        "floor",
+        "floor(X)",
+        "First representable integer value less than or equal to X",
+    ),
+    math("ln", "ln(X)", "Natural logarithm of X"),
+    math(
+        "log",
+        "log(B, X)",
+        "Logarithm of X in base B, base 10 when only X is given",
+    ),
+    math("log10", "log10(X)", "Base-10 logarithm of X"),
+    math("log2", "log2(X)", "Base-2 logarithm of X"),
+    math("mod", "mod(X, Y)", "Remainder after dividing X by Y"),
+    math("pi", "pi()", "Approximation of pi"),
+    math("pow", "pow(X, Y)", "X raised to the power Y"),
+    math("power", "power(X, Y)", "Alias of pow"),
+    math(
+        "radians",
+        "radians(X)",
+        "Converts X from degrees into radians",
+    ),
+    math("sin", "sin(X)", "Sine of X in radians"),
+    math("sinh", "sinh(X)", "Hyperbolic sine of X"),
+    math("sqrt", "sqrt(X)", "Square root of X"),
+    math("tan", "tan(X)", "Tangent of X in radians"),
+    math("tanh", "tanh(X)", "Hyperbolic tangent of X"),
+    math(
+        "trunc",
+        "trunc(X)",
+        "Integer part of X, rounding towards zero",
+    ),
+    // JSON functions
+    page(
+        FunctionCategory::Json,
+        "json",
+        "json(JSON)",
+        "Minified version of the JSON text",
+    ),
+    page(
+        FunctionCategory::Json,
+        "jsonb",
+        "jsonb(JSON)",
+        "Binary JSONB representation of the JSON",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_array",
+        "json_array(VALUE, ...)",
+        "JSON array of the arguments",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_array_length",
+        "json_array_length(JSON, PATH)",
+        "Number of elements in the JSON array at the PATH",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_error_position",
+        "json_error_position(JSON)",
+        "Character position of the first syntax error in the JSON, 0 when well-formed",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_extract",
+        "json_extract(JSON, PATH, ...)",
+        "Values extracted from the JSON at the PATHs",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_insert",
+        "json_insert(JSON, PATH, VALUE, ...)",
+        "JSON with the values inserted at the PATHs that don't exist",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_object",
+        "json_object(LABEL, VALUE, ...)",
+        "JSON object of the label and value pairs",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_patch",
+        "json_patch(TARGET, PATCH)",
+        "TARGET with the RFC-7396 merge PATCH applied",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_pretty",
+        "json_pretty(JSON, INDENT)",
+        "JSON formatted for readability",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_remove",
+        "json_remove(JSON, PATH, ...)",
+        "JSON with the elements at the PATHs removed",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_replace",
+        "json_replace(JSON, PATH, VALUE, ...)",
+        "JSON with the values at the existing PATHs replaced",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_set",
+        "json_set(JSON, PATH, VALUE, ...)",
+        "JSON with the values at the PATHs inserted or replaced",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_type",
+        "json_type(JSON, PATH)",
+        "Type of the JSON element at the PATH",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_valid",
+        "json_valid(JSON, FLAGS)",
+        "1 when the JSON is well-formed, 0 otherwise",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_quote",
+        "json_quote(VALUE)",
+        "JSON representation of the SQL value",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_group_array",
+        "json_group_array(VALUE)",
+        "Aggregate JSON array of the values within a group",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_group_object",
+        "json_group_object(LABEL, VALUE)",
+        "Aggregate JSON object of the label and value pairs within a group",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_each",
+        "json_each(JSON, PATH)",
+        "Table with one row for each element of the JSON array or object",
+    ),
+    page(
+        FunctionCategory::Json,
+        "json_tree",
+        "json_tree(JSON, PATH)",
+        "Table with one row for each element of the JSON, walking it recursively",
+    ),
+    // FTS5 auxiliary functions
+    page(
+        FunctionCategory::Fts5,
+        "bm25",
+        "bm25(TABLE, WEIGHT, ...)",
+        "Accuracy of the current full-text match, lower values are better matches",
+    ),
+    page(
+        FunctionCategory::Fts5,
+        "highlight",
+        "highlight(TABLE, COLUMN, OPEN, CLOSE)",
+        "Text of the COLUMN with the matched phrases surrounded by OPEN and CLOSE",
+    ),
+    page(
+        FunctionCategory::Fts5,
+        "snippet",
+        "snippet(TABLE, COLUMN, OPEN, CLOSE, ELLIPSIS, TOKENS)",
+        "Fragment of the COLUMN text containing the matched phrases",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{call_snippet, generic_signature, signature_parameters};
+
+    #[test]
+    fn test_signature_parameters() {
+        let signature = "substr(X, Y, Z)";
+        let parameters: Vec<&str> = signature_parameters(signature)
+            .into_iter()
+            .map(|range| &signature[range])
+            .collect();
+
+        assert_eq!(parameters, ["X", "Y", "Z"]);
+        assert!(signature_parameters("random()").is_empty());
+
+        assert_eq!(
+            call_snippet("coalesce", &["X", "Y", "..."]),
+            "coalesce(${1:X}, ${2:Y})$0"
+        );
+        assert_eq!(generic_signature("ext", 2), "ext(X1, X2)");
+        assert_eq!(generic_signature("ext", -1), "ext(...)");
+    }
+}
@@ -0,0 +1,75 @@
+//! Building of the markdown hover contents for the database schema and
+//! built-in functions
+
+use crate::{
+    database::{DatabaseForeignKey, DatabaseObject, DatabaseTable, DatabaseTableColumn},
+    lsp::sqlite::functions::SqliteFunction,
+};
+
+/// Hover for a table showing its CREATE statement and number of rows
+pub fn table_hover(table: &DatabaseTable, row_count: Option<i64>) -> String {
+    let mut value = format!("**{}** table\n\n```sql\n{}\n```", table.name, table.sql);
+
+    if let Some(row_count) = row_count {
+        value.push_str(&format!("\n\n{row_count} rows"));
+    }
+
+    value
+}
+
+/// Hover for a view showing its CREATE statement
+pub fn view_hover(view: &DatabaseObject) -> String {
+    format!("**{}** view\n\n```sql\n{}\n```", view.name, view.sql)
+}
+
+/// Hover for a column of the `table_name` table showing its type,
+/// constraints and the foreign key referencing another table
+pub fn column_hover(
+    table_name: &str,
+    column: &DatabaseTableColumn,
+    foreign_keys: &[DatabaseForeignKey],
+) -> String {
+    let mut value = format!("**{}** column of `{table_name}`", column.name);
+
+    if !column.column_type.is_empty() {
+        value.push_str(&format!("\n\n`{}`", column.column_type));
+    }
+
+    let mut constraints = Vec::new();
+    if column.primary_key {
+        constraints.push("PRIMARY KEY".to_string());
+    }
+    if column.not_null {
+        constraints.push("NOT NULL".to_string());
+    }
+    if let Some(default_value) = &column.default_value {
+        constraints.push(format!("DEFAULT {default_value}"));
+    }
+
+    if !constraints.is_empty() {
+        value.push_str(&format!("\n\n{}", constraints.join(", ")));
+    }
+
+    let foreign_key = foreign_keys
+        .iter()
+        .find(|foreign_key| foreign_key.from == column.name);
+
+    if let Some(foreign_key) = foreign_key {
+        match &foreign_key.to {
+            Some(to) => value.push_str(&format!("\n\nReferences `{}`(`{to}`)", foreign_key.table)),
+            None => value.push_str(&format!("\n\nReferences `{}`", foreign_key.table)),
+        }
+    }
+
+    value
+}
+
+/// Hover for a built-in function showing its signature and documentation link
+pub fn function_hover(function: &SqliteFunction) -> String {
+    format!(
+        "```sql\n{}\n```\n\n{}\n\n[SQLite documentation]({})",
+        function.signature,
+        function.description,
+        function.docs_url()
+    )
+}
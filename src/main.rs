@@ -4,24 +4,47 @@
 )]
 
 use gpui::*;
-use gpui_component::{Root, Theme, ThemeMode, TitleBar};
 use gpui_component_assets::Assets;
 
 use crate::{
     assets::{CombinedAssetSource, CustomAssets},
+    cli::{CliArgs, USAGE, init_startup_error, init_startup_sql_file},
+    database::sqlite::SqliteDatabaseOptions,
+    history::init_query_history,
     keybindings::init_keybindings,
+    library::init_query_library,
     lsp::init_sql_language,
-    state::AppState,
-    ui::{actions::register_actions, app::MainApp, menus::register_app_menus},
+    query_tabs::init_query_tabs,
+    recent::init_recent_databases,
+    settings::init_settings,
+    state::{AppState, WindowSessions},
+    theme::init_theme,
+    ui::{
+        actions::{
+            open_file::{load_database_file, open_file_urls},
+            register_actions,
+        },
+        app::open_main_window,
+        menus::register_app_menus,
+    },
     utils::gpui_tokio::init_tokio,
 };
 
 mod assets;
+mod cli;
 mod database;
+mod export;
+mod history;
+mod import;
 mod keybindings;
+mod library;
 mod logging;
 mod lsp;
+mod query_tabs;
+mod recent;
+mod settings;
 mod state;
+mod theme;
 mod ui;
 mod utils;
 
@@ -29,21 +52,43 @@ rust_i18n::i18n!("locales");
 
 fn init(cx: &mut App) {
     gpui_component::init(cx);
+    init_settings(cx);
+    init_query_history(cx);
+    init_query_library(cx);
+    init_query_tabs(cx);
     init_theme(cx);
     init_keybindings(cx);
     init_tokio(cx);
 }
 
-fn init_theme(cx: &mut App) {
-    // Set the theme to dark
-    Theme::change(ThemeMode::Dark, None, cx);
-
-    // Move the notifications to the bottom right
-    let theme = Theme::global_mut(cx);
-    theme.notification.placement = Anchor::BottomRight;
-}
-
 fn main() {
+    // Errors are also shown within the main window as the console isn't
+    // visible for the windows subsystem
+    let mut startup_error = None;
+
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("error: {error:#}\n\n{USAGE}");
+            startup_error = Some(format!("{error:#}\n\n{USAGE}"));
+            CliArgs::default()
+        }
+    };
+
+    if args.help {
+        println!("{USAGE}");
+        return;
+    }
+
+    let key = match args.read_key() {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("error: {error:#}");
+            startup_error = Some(format!("{error:#}"));
+            None
+        }
+    };
+
     logging::init_logging();
 
     init_sql_language();
@@ -55,10 +100,18 @@ fn main() {
             custom_assets: CustomAssets,
         });
 
+    // Files opened with the application by the operating system (e.g. from
+    // Finder on macOS) are received outside of the app context
+    let (open_urls_tx, mut open_urls_rx) = tokio::sync::mpsc::unbounded_channel();
+    app.on_open_urls(move |urls| {
+        _ = open_urls_tx.send(urls);
+    });
+
     app.run(move |cx| {
         init(cx);
 
         // Setup global state
+        init_recent_databases(cx);
         let app_state = AppState::new(cx);
         cx.set_global(app_state);
 
@@ -69,23 +122,29 @@ fn main() {
         register_actions(cx);
         register_app_menus(cx);
 
-        let bounds = Bounds::centered(None, size(px(800.0), px(600.0)), cx);
-
         cx.spawn(async move |cx| {
-            cx.open_window(
-                WindowOptions {
-                    titlebar: Some(TitleBar::title_bar_options()),
-                    window_bounds: Some(WindowBounds::Windowed(bounds)),
-                    ..Default::default()
-                },
-                |window, cx| {
-                    let view = MainApp::new(window, cx);
-                    cx.new(|cx| Root::new(view, window, cx))
-                },
-            )?;
-
-            Ok::<_, anyhow::Error>(())
+            while let Some(urls) = open_urls_rx.recv().await {
+                cx.update(|cx| open_file_urls(cx, urls));
+            }
         })
         .detach();
+
+        let sessions = WindowSessions::new(cx);
+        let has_error = startup_error.is_some();
+        init_startup_error(cx, startup_error);
+
+        // Open the database from the command line before the window renders
+        if !has_error && let Some(path) = args.database {
+            init_startup_sql_file(cx, args.sql);
+
+            let database = WindowSessions::open_session(&sessions, cx);
+            let options = SqliteDatabaseOptions {
+                readonly: args.readonly,
+                key,
+            };
+            load_database_file(cx, database, path, options);
+        }
+
+        open_main_window(sessions, cx);
     });
 }
@@ -0,0 +1,111 @@
+//! Query editor tabs of each database, persisted as JSON within the user
+//! data directory so they are restored when the database is reopened
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Context;
+use gpui::{App, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::dirs::data_dir;
+
+/// Name of the query tabs file within the data directory
+const QUERY_TABS_FILE_NAME: &str = "tabs.json";
+
+/// Contents of a single query editor tab
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueryTabContent {
+    pub title: String,
+    pub text: String,
+    /// Path of the SQL file the tab is associated with
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Query editor tabs of a database
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseQueryTabs {
+    pub tabs: Vec<QueryTabContent>,
+    /// Index of the focused tab
+    pub active: usize,
+}
+
+/// Query editor tabs by the full path of their database
+#[derive(Default, Serialize, Deserialize)]
+pub struct SavedQueryTabs {
+    pub databases: HashMap<String, DatabaseQueryTabs>,
+}
+
+impl Global for SavedQueryTabs {}
+
+impl SavedQueryTabs {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(QUERY_TABS_FILE_NAME))
+    }
+
+    /// Load the query tabs from the query tabs file, falling back to no
+    /// tabs when the file is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(error) => {
+                tracing::error!(?error, "failed to read query tabs file");
+                return Self::default();
+            }
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(?error, "failed to parse query tabs file");
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the query tabs to the query tabs file
+    fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown data directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("failed to create data directory")?;
+        }
+
+        let contents =
+            serde_json::to_string_pretty(self).context("failed to serialize query tabs")?;
+        fs::write(&path, contents).context("failed to write query tabs file")?;
+
+        Ok(())
+    }
+
+    /// Get the saved tabs of the database at `database_path`
+    pub fn get(&self, database_path: &str) -> Option<&DatabaseQueryTabs> {
+        self.databases.get(database_path)
+    }
+}
+
+/// Initialize the global query tabs from the query tabs file
+pub fn init_query_tabs(cx: &mut App) {
+    cx.set_global(SavedQueryTabs::load());
+}
+
+/// Store the `tabs` of the database at `database_path` persisting them to
+/// the query tabs file when they changed
+pub fn save_query_tabs(cx: &mut App, database_path: String, tabs: DatabaseQueryTabs) {
+    cx.update_global::<SavedQueryTabs, _>(|saved, _cx| {
+        if saved.databases.get(&database_path) == Some(&tabs) {
+            return;
+        }
+
+        saved.databases.insert(database_path, tabs);
+
+        if let Err(error) = saved.save() {
+            tracing::error!(?error, "failed to save query tabs");
+        }
+    });
+}
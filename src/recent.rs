@@ -0,0 +1,288 @@
+//! Recently opened databases, persisted as JSON within the user data
+//! directory
+//!
+//! Only the location and how the database was opened are stored, the key of
+//! encrypted databases is never persisted
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use gpui::{App, AppContext, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    state::{async_resource::AsyncResource, database::connection::DatabaseConnectionResource},
+    utils::dirs::data_dir,
+};
+
+/// Name of the recent databases file within the data directory
+const RECENT_FILE_NAME: &str = "recent.json";
+
+/// Maximum number of unpinned recent databases kept
+const MAX_RECENT_DATABASES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentDatabase {
+    /// Absolute path to the database file
+    pub path: String,
+    /// Unix timestamp in seconds of when the database was last opened
+    pub last_opened: u64,
+    /// Whether the database was opened as readonly
+    pub readonly: bool,
+    /// Whether the database is encrypted and requires a key to open
+    pub encrypted: bool,
+    /// Whether the database is kept at the top of the list
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl RecentDatabase {
+    /// Whether the database file no longer exists
+    pub fn is_missing(&self) -> bool {
+        !Path::new(&self.path).exists()
+    }
+
+    /// Name of the database file
+    pub fn file_name(&self) -> String {
+        Path::new(&self.path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.path.clone())
+    }
+}
+
+/// Recently opened databases, pinned databases first then most recent first
+#[derive(Default, Serialize, Deserialize)]
+pub struct RecentDatabases {
+    pub databases: Vec<RecentDatabase>,
+}
+
+impl Global for RecentDatabases {}
+
+impl RecentDatabases {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join(RECENT_FILE_NAME))
+    }
+
+    /// Load the recent databases from the recent file, falling back to an
+    /// empty list when the file is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(error) => {
+                tracing::error!(?error, "failed to read recent databases file");
+                return Self::default();
+            }
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(?error, "failed to parse recent databases file");
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the recent databases to the recent file
+    fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown data directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("failed to create data directory")?;
+        }
+
+        let contents =
+            serde_json::to_string_pretty(self).context("failed to serialize recent databases")?;
+        fs::write(&path, contents).context("failed to write recent databases file")?;
+
+        Ok(())
+    }
+
+    /// Find the recent database at `path`
+    pub fn find(&self, path: &str) -> Option<&RecentDatabase> {
+        self.databases.iter().find(|database| database.path == path)
+    }
+
+    /// Record the database at `path` being opened at the unix timestamp
+    /// `last_opened`, moving it to the top of the unpinned databases
+    fn opened(&mut self, path: String, readonly: bool, encrypted: bool, last_opened: u64) {
+        let pinned = self.find(&path).is_some_and(|database| database.pinned);
+        self.databases.retain(|database| database.path != path);
+
+        // Inserted first so it stays above databases opened within the same second
+        self.databases.insert(
+            0,
+            RecentDatabase {
+                path,
+                last_opened,
+                readonly,
+                encrypted,
+                pinned,
+            },
+        );
+
+        self.sort();
+
+        // Drop the oldest unpinned databases beyond the limit
+        let mut unpinned = 0;
+        self.databases.retain(|database| {
+            if database.pinned {
+                return true;
+            }
+
+            unpinned += 1;
+            unpinned <= MAX_RECENT_DATABASES
+        });
+    }
+
+    fn sort(&mut self) {
+        self.databases.sort_by(|a, b| {
+            b.pinned
+                .cmp(&a.pinned)
+                .then_with(|| b.last_opened.cmp(&a.last_opened))
+        });
+    }
+
+    /// Toggle whether the database at `path` is pinned
+    pub fn toggle_pinned(&mut self, path: &str) {
+        if let Some(database) = self
+            .databases
+            .iter_mut()
+            .find(|database| database.path == path)
+        {
+            database.pinned = !database.pinned;
+        }
+
+        self.sort();
+    }
+
+    /// Remove the database at `path` from the list
+    pub fn remove(&mut self, path: &str) {
+        self.databases.retain(|database| database.path != path);
+    }
+
+    /// Remove the databases whose files no longer exist
+    pub fn remove_missing(&mut self) {
+        self.databases.retain(|database| !database.is_missing());
+    }
+}
+
+/// Current unix timestamp in seconds
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Initialize the global recent databases from the recent file and record
+/// databases as they are opened within any session
+///
+/// Must be called before any database session is created
+pub fn init_recent_databases(cx: &mut App) {
+    cx.set_global(RecentDatabases::load());
+
+    cx.observe_new::<DatabaseConnectionResource>(|resource, _window, cx| {
+        cx.observe(&resource.database, |_, database, cx| {
+            let AsyncResource::Loaded(database) = database.read(cx) else {
+                return;
+            };
+
+            let options = database.options();
+
+            // In memory databases can't be reopened
+            let Some(full_path) = options.full_path else {
+                return;
+            };
+
+            update_recent_databases(cx, |recent| {
+                recent.opened(full_path, options.readonly, options.encrypted, now());
+            });
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Update the global recent databases and persist them to the recent file
+pub fn update_recent_databases(cx: &mut App, update: impl FnOnce(&mut RecentDatabases)) {
+    cx.update_global::<RecentDatabases, _>(|recent, _cx| {
+        update(recent);
+
+        if let Err(error) = recent.save() {
+            tracing::error!(?error, "failed to save recent databases");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_RECENT_DATABASES, RecentDatabases};
+
+    fn paths(recent: &RecentDatabases) -> Vec<&str> {
+        recent
+            .databases
+            .iter()
+            .map(|database| database.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_opened_orders_most_recent_first() {
+        let mut recent = RecentDatabases::default();
+        recent.opened("/a.db".to_string(), false, false, 1);
+        recent.opened("/b.db".to_string(), true, false, 2);
+        recent.opened("/c.db".to_string(), false, false, 2);
+        assert_eq!(paths(&recent), ["/c.db", "/b.db", "/a.db"]);
+
+        // Reopening moves the database to the top with the latest options
+        recent.opened("/a.db".to_string(), false, true, 3);
+        assert_eq!(paths(&recent), ["/a.db", "/c.db", "/b.db"]);
+        assert!(recent.databases[0].encrypted);
+    }
+
+    #[test]
+    fn test_opened_keeps_pinned_first() {
+        let mut recent = RecentDatabases::default();
+        recent.opened("/a.db".to_string(), false, false, 1);
+        recent.opened("/b.db".to_string(), false, false, 2);
+        recent.toggle_pinned("/a.db");
+        assert_eq!(paths(&recent), ["/a.db", "/b.db"]);
+
+        // Reopening a pinned database keeps it pinned
+        recent.opened("/c.db".to_string(), false, false, 3);
+        recent.opened("/a.db".to_string(), false, false, 4);
+        assert_eq!(paths(&recent), ["/a.db", "/c.db", "/b.db"]);
+        assert!(recent.databases[0].pinned);
+    }
+
+    #[test]
+    fn test_opened_limits_unpinned() {
+        let mut recent = RecentDatabases::default();
+        recent.opened("/pinned.db".to_string(), false, false, 0);
+        recent.toggle_pinned("/pinned.db");
+
+        for index in 0..MAX_RECENT_DATABASES + 2 {
+            recent.opened(format!("/{index}.db"), false, false, index as u64 + 1);
+        }
+
+        assert_eq!(recent.databases.len(), MAX_RECENT_DATABASES + 1);
+        assert_eq!(recent.databases[0].path, "/pinned.db");
+        assert_eq!(
+            recent.databases[1].path,
+            format!("/{}.db", MAX_RECENT_DATABASES + 1)
+        );
+        assert!(recent.find("/0.db").is_none());
+        assert!(recent.find("/1.db").is_none());
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sqlformat::{FormatOptions, Indent};
+
+/// Case to convert keywords to when formatting
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordCase {
+    /// Leave keywords as they were written
+    #[default]
+    Preserve,
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatterSettings {
+    /// Number of spaces to indent by
+    pub indent_width: u8,
+    /// Indent using tabs rather than spaces
+    pub use_tabs: bool,
+    pub keyword_case: KeywordCase,
+    /// Number of line breaks between statements
+    pub lines_between_statements: u8,
+    /// Maximum width of blocks and argument lists kept on a single line
+    pub inline_width: usize,
+}
+
+impl Default for FormatterSettings {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            use_tabs: false,
+            keyword_case: KeywordCase::Preserve,
+            lines_between_statements: 1,
+            inline_width: 50,
+        }
+    }
+}
+
+impl FormatterSettings {
+    /// Options for the formatter from the settings
+    pub fn format_options(&self) -> FormatOptions<'static> {
+        FormatOptions {
+            indent: match self.use_tabs {
+                true => Indent::Tabs,
+                false => Indent::Spaces(self.indent_width),
+            },
+            uppercase: match self.keyword_case {
+                KeywordCase::Preserve => None,
+                KeywordCase::Upper => Some(true),
+                KeywordCase::Lower => Some(false),
+            },
+            lines_between_queries: self.lines_between_statements,
+            max_inline_block: self.inline_width,
+            max_inline_arguments: Some(self.inline_width),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// Color scheme of the interface
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeModeSetting {
+    Light,
+    #[default]
+    Dark,
+    /// Follow the appearance of the operating system
+    System,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppearanceSettings {
+    pub theme_mode: ThemeModeSetting,
+    /// Name of the theme used in light mode, the default theme is used
+    /// when unset or not found
+    pub light_theme: Option<String>,
+    /// Name of the theme used in dark mode, the default theme is used
+    /// when unset or not found
+    pub dark_theme: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TableSettings {
+    /// Number of rows shown per page when browsing a table
+    pub page_size: u32,
+}
+
+impl TableSettings {
+    /// Largest number of rows that can be shown per page
+    pub const MAX_PAGE_SIZE: u32 = 10_000;
+}
+
+impl Default for TableSettings {
+    fn default() -> Self {
+        Self { page_size: 5 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowSettings {
+    /// Initial width of new windows
+    pub width: f32,
+    /// Initial height of new windows
+    pub height: f32,
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+}
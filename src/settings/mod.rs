@@ -0,0 +1,131 @@
+//! Application settings persisted as JSON within the user config directory
+
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use gpui::{App, AppContext, Entity, EventEmitter, Global};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    settings::{
+        formatter::FormatterSettings,
+        interface::{AppearanceSettings, TableSettings, WindowSettings},
+    },
+    utils::dirs::config_dir,
+};
+
+pub mod formatter;
+pub mod interface;
+
+/// Name of the settings file within the config directory
+const SETTINGS_FILE_NAME: &str = "settings.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub appearance: AppearanceSettings,
+    /// Settings for browsing the rows of tables
+    pub table: TableSettings,
+    /// Settings for new windows
+    pub window: WindowSettings,
+    /// Settings for formatting SQL within the editor
+    pub formatter: FormatterSettings,
+}
+
+impl Global for AppSettings {}
+
+/// Event emitted after the settings are updated
+pub struct SettingsChangedEvent {
+    /// Settings before the update
+    pub previous: AppSettings,
+}
+
+/// Entity emitting [SettingsChangedEvent] for views to subscribe to
+pub struct SettingsNotifier;
+
+impl EventEmitter<SettingsChangedEvent> for SettingsNotifier {}
+
+struct GlobalSettingsNotifier(Entity<SettingsNotifier>);
+
+impl Global for GlobalSettingsNotifier {}
+
+/// Get the entity emitting an event whenever the settings change
+pub fn settings_notifier(cx: &App) -> Entity<SettingsNotifier> {
+    cx.global::<GlobalSettingsNotifier>().0.clone()
+}
+
+impl AppSettings {
+    fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join(SETTINGS_FILE_NAME))
+    }
+
+    /// Load the settings from the settings file, falling back to the
+    /// defaults when the file is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        let contents = match fs::read_to_string(&path) {
+            Ok(value) => value,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(error) => {
+                tracing::error!(?error, "failed to read settings file");
+                return Self::default();
+            }
+        };
+
+        match serde_json::from_str(&contents) {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(?error, "failed to parse settings file");
+                Self::default()
+            }
+        }
+    }
+
+    /// Save the settings to the settings file
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Self::path().context("unknown config directory")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("failed to create config directory")?;
+        }
+
+        let contents =
+            serde_json::to_string_pretty(self).context("failed to serialize settings")?;
+        fs::write(&path, contents).context("failed to write settings file")?;
+
+        Ok(())
+    }
+}
+
+/// Initialize the global settings from the settings file
+pub fn init_settings(cx: &mut App) {
+    cx.set_global(AppSettings::load());
+
+    let notifier = cx.new(|_| SettingsNotifier);
+    cx.set_global(GlobalSettingsNotifier(notifier));
+}
+
+/// Update the global settings, persisting them to the settings file and
+/// notifying the subscribers when they changed
+pub fn update_settings(cx: &mut App, update: impl FnOnce(&mut AppSettings)) {
+    let previous = cx.global::<AppSettings>().clone();
+
+    cx.update_global::<AppSettings, _>(|settings, _cx| {
+        update(settings);
+    });
+
+    if cx.global::<AppSettings>() == &previous {
+        return;
+    }
+
+    if let Err(error) = cx.global::<AppSettings>().save() {
+        tracing::error!(?error, "failed to save settings");
+    }
+
+    settings_notifier(cx).update(cx, |_, cx| {
+        cx.emit(SettingsChangedEvent { previous });
+    });
+}
@@ -1,11 +1,12 @@
 use gpui::Entity;
 
 use crate::{
-    database::{AnySharedDatabase, DatabaseTable},
+    database::{AnySharedDatabase, DatabaseSchema, DatabaseTable},
     state::{async_resource::AsyncResource, database::connection::DatabaseConnectionResource},
 };
 
 pub mod connection;
+pub mod schema;
 pub mod tables;
 
 pub trait DatabaseResourceExt {
@@ -21,4 +22,7 @@ pub trait DatabaseResourceExt {
 
     /// Get a [Entity] of the derived tables async resource
     fn database_tables(&self) -> Entity<AsyncResource<Vec<DatabaseTable>>>;
+
+    /// Get a [Entity] of the derived schema async resource
+    fn database_schema(&self) -> Entity<AsyncResource<DatabaseSchema>>;
 }
@@ -0,0 +1,109 @@
+use anyhow::Context;
+use gpui::{App, AppContext, Entity, Subscription};
+
+use crate::{
+    database::{DatabaseSchema, DatabaseTable},
+    state::{
+        async_resource::{AsyncResource, AsyncResourceEntityExt},
+        database::connection::DatabaseConnectionResource,
+    },
+};
+
+pub struct DatabaseSchemaResource {
+    /// Async resource for the schema of the currently loaded database
+    schema: Entity<AsyncResource<DatabaseSchema>>,
+
+    /// Subscriptions attached to this derived resource
+    /// (Database tables entity)
+    _subscriptions: (Subscription,),
+}
+
+impl DatabaseSchemaResource {
+    pub fn derive(
+        cx: &mut App,
+        database: Entity<DatabaseConnectionResource>,
+        tables: Entity<AsyncResource<Vec<DatabaseTable>>>,
+    ) -> Entity<DatabaseSchemaResource> {
+        cx.new(move |cx| {
+            let schema = AsyncResource::new(cx);
+
+            // Tables are re-fetched when the database changes or queries are
+            // executed, the rest of the schema is re-fetched alongside them
+            let tables_subscription = cx.observe(
+                &tables,
+                move |view: &mut DatabaseSchemaResource, tables, cx| {
+                    Self::load_database_schema(&database, &tables, &view.schema, cx);
+                },
+            );
+
+            DatabaseSchemaResource {
+                schema,
+                _subscriptions: (tables_subscription,),
+            }
+        })
+    }
+
+    pub fn database_schema(this: &Entity<Self>, cx: &App) -> Entity<AsyncResource<DatabaseSchema>> {
+        this.read(cx).schema.clone()
+    }
+
+    /// Loads the remaining schema details for the loaded `tables`
+    fn load_database_schema<T: 'static>(
+        database: &Entity<DatabaseConnectionResource>,
+        tables: &Entity<AsyncResource<Vec<DatabaseTable>>>,
+        async_schema: &Entity<AsyncResource<DatabaseSchema>>,
+        cx: &mut gpui::Context<T>,
+    ) {
+        let tables = match tables.read(cx) {
+            AsyncResource::Loaded(value) => value.clone(),
+            // Keep the previous schema while the tables are loading
+            AsyncResource::Loading(_) => return,
+            _ => {
+                async_schema.set_idle(cx);
+                return;
+            }
+        };
+
+        let database = match DatabaseConnectionResource::database_connection(database, cx) {
+            Some(value) => value,
+            None => {
+                async_schema.set_idle(cx);
+                return;
+            }
+        };
+
+        async_schema.load(cx, || async move {
+            tracing::debug!("loading database schema");
+
+            let objects = database
+                .database_objects()
+                .await
+                .context("failed to load database objects")?;
+            let schemas = database
+                .database_schemas()
+                .await
+                .context("failed to load database schemas")?;
+            let schema_tables = database
+                .database_schema_tables()
+                .await
+                .context("failed to load tables of the attached databases")?;
+
+            // Listing functions isn't supported by every SQLite build
+            let functions = match database.database_functions().await {
+                Ok(value) => value,
+                Err(error) => {
+                    tracing::warn!(?error, "failed to load database functions");
+                    Vec::new()
+                }
+            };
+
+            Ok(DatabaseSchema {
+                tables,
+                objects,
+                schemas,
+                schema_tables,
+                functions,
+            })
+        });
+    }
+}
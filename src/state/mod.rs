@@ -1,12 +1,14 @@
-use gpui::{App, Context, Entity, Global};
+use std::{cell::Cell, rc::Rc};
+
+use gpui::{AnyWindowHandle, App, AppContext, Context, Entity, Global, SharedString};
 
 use crate::{
-    database::AnySharedDatabase,
+    database::{AnySharedDatabase, DatabaseSchema, DatabaseTable},
     state::{
-        async_resource::AsyncResource,
+        async_resource::{AsyncResource, AsyncResourceEntityExt},
         database::{
             DatabaseResourceExt, connection::DatabaseConnectionResource,
-            tables::DatabaseTablesResource,
+            schema::DatabaseSchemaResource, tables::DatabaseTablesResource,
         },
     },
 };
@@ -14,61 +16,276 @@ use crate::{
 pub mod async_resource;
 pub mod database;
 
-pub struct AppState {
-    pub database: Entity<DatabaseConnectionResource>,
+/// Database open within a window along with the resources derived from it
+#[derive(Clone)]
+pub struct DatabaseSession {
+    /// Identifier of the session, unique within its window
+    pub id: usize,
+    pub connection: Entity<DatabaseConnectionResource>,
     pub tables: Entity<DatabaseTablesResource>,
+    pub schema: Entity<DatabaseSchemaResource>,
+    /// Whether the query editor of the session has unsaved changes, kept
+    /// up to date by the query editor
+    pub unsaved_changes: Rc<Cell<bool>>,
+}
+
+impl DatabaseSession {
+    fn new(id: usize, cx: &mut App) -> Self {
+        let connection = DatabaseConnectionResource::new(cx);
+        let tables = DatabaseTablesResource::derive(cx, connection.clone());
+        let tables_resource = DatabaseTablesResource::database_tables(&tables, cx);
+        let schema = DatabaseSchemaResource::derive(cx, connection.clone(), tables_resource);
+        Self {
+            id,
+            connection,
+            tables,
+            schema,
+            unsaved_changes: Rc::default(),
+        }
+    }
+
+    /// Get the database entity of the session
+    pub fn database(&self, cx: &App) -> Entity<AsyncResource<AnySharedDatabase>> {
+        DatabaseConnectionResource::database(&self.connection, cx)
+    }
+
+    /// Get a handle to the database connection of the session if one is
+    /// available
+    pub fn database_connection(&self, cx: &App) -> Option<AnySharedDatabase> {
+        DatabaseConnectionResource::database_connection(&self.connection, cx)
+    }
+
+    /// Get a [Entity] of the derived tables async resource
+    pub fn database_tables(&self, cx: &App) -> Entity<AsyncResource<Vec<DatabaseTable>>> {
+        DatabaseTablesResource::database_tables(&self.tables, cx)
+    }
+
+    /// Get a [Entity] of the derived schema async resource
+    pub fn database_schema(&self, cx: &App) -> Entity<AsyncResource<DatabaseSchema>> {
+        DatabaseSchemaResource::database_schema(&self.schema, cx)
+    }
+
+    /// Whether the session has no database open or being opened
+    pub fn is_idle(&self, cx: &App) -> bool {
+        matches!(self.database(cx).read(cx), AsyncResource::Idle)
+    }
+
+    /// Title of the session, the file name of the open database
+    pub fn title(&self, cx: &App) -> Option<SharedString> {
+        let database = self.database_connection(cx)?;
+        let path = database.options().path;
+
+        let name = std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(path);
+
+        Some(name.into())
+    }
+}
+
+/// Database sessions open within a single window
+pub struct WindowSessions {
+    /// Sessions of the open databases, there is always at least one
+    /// session which is idle when no database is open
+    pub sessions: Vec<DatabaseSession>,
+    /// Index of the focused session
+    pub active: usize,
+    /// Identifier given to the next created session
+    next_session_id: usize,
+}
+
+impl WindowSessions {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        let session = DatabaseSession::new(0, cx);
+
+        cx.new(|_| Self {
+            sessions: vec![session],
+            active: 0,
+            next_session_id: 1,
+        })
+    }
+
+    /// The focused session
+    pub fn active_session(this: &Entity<Self>, cx: &App) -> DatabaseSession {
+        let this = this.read(cx);
+        this.sessions[this.active].clone()
+    }
+
+    /// Whether any of the sessions has unsaved changes
+    pub fn has_unsaved_changes(this: &Entity<Self>, cx: &App) -> bool {
+        this.read(cx)
+            .sessions
+            .iter()
+            .any(|session| session.unsaved_changes.get())
+    }
+
+    /// Get the database resource to open a database into, the focused
+    /// session is reused when it has no database otherwise a new session
+    /// is created and focused
+    pub fn open_session(
+        this: &Entity<Self>,
+        cx: &mut App,
+    ) -> Entity<AsyncResource<AnySharedDatabase>> {
+        let active = Self::active_session(this, cx);
+        if active.is_idle(cx) {
+            return active.database(cx);
+        }
+
+        let id = this.read(cx).next_session_id;
+        let session = DatabaseSession::new(id, cx);
+        let database = session.database(cx);
+
+        this.update(cx, |this, cx| {
+            this.next_session_id += 1;
+            this.sessions.push(session);
+            this.active = this.sessions.len() - 1;
+            cx.notify();
+        });
+
+        database
+    }
+
+    /// Focus the session with the provided `id`
+    pub fn activate_session(this: &Entity<Self>, id: usize, cx: &mut App) {
+        this.update(cx, |this, cx| {
+            if let Some(index) = this.sessions.iter().position(|session| session.id == id) {
+                this.active = index;
+                cx.notify();
+            }
+        });
+    }
+
+    /// Close the database of the session with the provided `id`, the
+    /// session is removed unless it is the only session
+    pub fn close_session(this: &Entity<Self>, id: usize, cx: &mut App) {
+        let Some(session) = this
+            .read(cx)
+            .sessions
+            .iter()
+            .find(|session| session.id == id)
+            .cloned()
+        else {
+            return;
+        };
+
+        session.database(cx).set_idle(cx);
+
+        this.update(cx, |this, cx| {
+            if this.sessions.len() == 1 {
+                return;
+            }
+
+            let Some(index) = this.sessions.iter().position(|session| session.id == id) else {
+                return;
+            };
+
+            this.sessions.remove(index);
+
+            // Keep the focus on the same session or the one before the closed one
+            if this.active > index || this.active == this.sessions.len() {
+                this.active -= 1;
+            }
+
+            cx.notify();
+        });
+    }
+}
+
+pub struct AppState {
+    /// Sessions of each of the open windows
+    pub windows: Vec<(AnyWindowHandle, Entity<WindowSessions>)>,
+    /// Idle session used when no window is open
+    detached: DatabaseSession,
 }
 
 impl Global for AppState {}
 
 impl AppState {
     pub fn new(cx: &mut App) -> Self {
-        let database = DatabaseConnectionResource::new(cx);
-        let tables = DatabaseTablesResource::derive(cx, database.clone());
-        Self { database, tables }
+        Self {
+            windows: Vec::new(),
+            detached: DatabaseSession::new(0, cx),
+        }
+    }
+}
+
+/// Register the `sessions` of a newly opened `window`
+pub fn register_window(cx: &mut App, window: AnyWindowHandle, sessions: Entity<WindowSessions>) {
+    cx.update_global::<AppState, _>(|state, _cx| {
+        state.windows.push((window, sessions));
+    });
+}
+
+/// Remove the sessions of the closed `window`
+pub fn unregister_window(cx: &mut App, window: AnyWindowHandle) {
+    cx.update_global::<AppState, _>(|state, _cx| {
+        state.windows.retain(|(handle, _)| *handle != window);
+    });
+}
+
+/// Get the sessions of the focused window, falling back to the most
+/// recently opened window when no window is focused
+pub fn focused_window_sessions(cx: &App) -> Option<Entity<WindowSessions>> {
+    let state = cx.global::<AppState>();
+    let active_window = cx.active_window();
+
+    state
+        .windows
+        .iter()
+        .find(|(handle, _)| Some(*handle) == active_window)
+        .or_else(|| state.windows.last())
+        .map(|(_, sessions)| sessions.clone())
+}
+
+/// Get the focused session of the focused window
+fn focused_session(cx: &App) -> DatabaseSession {
+    match focused_window_sessions(cx) {
+        Some(sessions) => WindowSessions::active_session(&sessions, cx),
+        None => cx.global::<AppState>().detached.clone(),
     }
 }
 
 impl DatabaseResourceExt for App {
     fn database(&self) -> Entity<AsyncResource<AnySharedDatabase>> {
-        let app_state = self.global::<AppState>();
-        DatabaseConnectionResource::database(&app_state.database, self)
+        focused_session(self).database(self)
     }
 
     fn database_connection(&self) -> Option<AnySharedDatabase> {
-        let app_state = self.global::<AppState>();
-        DatabaseConnectionResource::database_connection(&app_state.database, self)
+        focused_session(self).database_connection(self)
     }
 
     fn database_connection_resource(&self) -> Entity<DatabaseConnectionResource> {
-        let app_state = self.global::<AppState>();
-        app_state.database.clone()
+        focused_session(self).connection
     }
 
-    fn database_tables(&self) -> Entity<AsyncResource<Vec<crate::database::DatabaseTable>>> {
-        let app_state = self.global::<AppState>();
-        DatabaseTablesResource::database_tables(&app_state.tables, self)
+    fn database_tables(&self) -> Entity<AsyncResource<Vec<DatabaseTable>>> {
+        focused_session(self).database_tables(self)
+    }
+
+    fn database_schema(&self) -> Entity<AsyncResource<DatabaseSchema>> {
+        focused_session(self).database_schema(self)
     }
 }
 
 impl<'a, T> DatabaseResourceExt for Context<'a, T> {
     fn database(&self) -> Entity<AsyncResource<AnySharedDatabase>> {
-        let app_state = self.global::<AppState>();
-        DatabaseConnectionResource::database(&app_state.database, self)
+        focused_session(self).database(self)
     }
 
     fn database_connection(&self) -> Option<AnySharedDatabase> {
-        let app_state = self.global::<AppState>();
-        DatabaseConnectionResource::database_connection(&app_state.database, self)
+        focused_session(self).database_connection(self)
     }
 
     fn database_connection_resource(&self) -> Entity<DatabaseConnectionResource> {
-        let app_state = self.global::<AppState>();
-        app_state.database.clone()
+        focused_session(self).connection
+    }
+
+    fn database_tables(&self) -> Entity<AsyncResource<Vec<DatabaseTable>>> {
+        focused_session(self).database_tables(self)
     }
 
-    fn database_tables(&self) -> Entity<AsyncResource<Vec<crate::database::DatabaseTable>>> {
-        let app_state = self.global::<AppState>();
-        DatabaseTablesResource::database_tables(&app_state.tables, self)
+    fn database_schema(&self) -> Entity<AsyncResource<DatabaseSchema>> {
+        focused_session(self).database_schema(self)
     }
 }
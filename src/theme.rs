@@ -0,0 +1,110 @@
+//! Theme of the application, applied from the appearance settings along
+//! with custom theme files loaded from the themes directory
+
+use std::path::PathBuf;
+
+use gpui::{Anchor, App, SharedString, Window, WindowAppearance};
+use gpui_component::{Theme, ThemeMode, ThemeRegistry};
+
+use crate::{
+    settings::{AppSettings, SettingsChangedEvent, interface::ThemeModeSetting, settings_notifier},
+    utils::dirs::config_dir,
+};
+
+/// Name of the directory within the config directory custom theme files
+/// are loaded from
+const THEMES_DIR_NAME: &str = "themes";
+
+/// Directory custom theme files are loaded from
+pub fn themes_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join(THEMES_DIR_NAME))
+}
+
+/// Apply the theme from the settings, watching the themes directory to
+/// reapply the theme whenever a theme file changes
+pub fn init_theme(cx: &mut App) {
+    apply_theme(None, cx);
+
+    if let Some(themes_dir) = themes_dir() {
+        if let Err(error) = std::fs::create_dir_all(&themes_dir) {
+            tracing::error!(?error, "failed to create themes directory");
+        }
+
+        if let Err(error) = ThemeRegistry::watch_dir(themes_dir, cx, |cx| {
+            apply_theme(None, cx);
+            cx.refresh_windows();
+        }) {
+            tracing::error!(?error, "failed to watch themes directory");
+        }
+    }
+
+    // Apply changes to the appearance settings live
+    cx.subscribe(
+        &settings_notifier(cx),
+        |_, event: &SettingsChangedEvent, cx| {
+            if event.previous.appearance != cx.global::<AppSettings>().appearance {
+                apply_theme(None, cx);
+                cx.refresh_windows();
+            }
+        },
+    )
+    .detach();
+}
+
+/// Names of the available themes for the `mode`
+pub fn theme_names(mode: ThemeMode, cx: &App) -> Vec<SharedString> {
+    ThemeRegistry::global(cx)
+        .sorted_themes()
+        .into_iter()
+        .filter(|theme| theme.mode == mode)
+        .map(|theme| theme.name.clone())
+        .collect()
+}
+
+/// Apply the theme from the settings, the `window` is used to determine
+/// the system appearance when available
+pub fn apply_theme(window: Option<&Window>, cx: &mut App) {
+    let appearance = cx.global::<AppSettings>().appearance.clone();
+
+    let mode = match appearance.theme_mode {
+        ThemeModeSetting::Light => ThemeMode::Light,
+        ThemeModeSetting::Dark => ThemeMode::Dark,
+        ThemeModeSetting::System => {
+            let system = match window {
+                Some(window) => window.appearance(),
+                None => cx.window_appearance(),
+            };
+
+            match system {
+                WindowAppearance::Light | WindowAppearance::VibrantLight => ThemeMode::Light,
+                WindowAppearance::Dark | WindowAppearance::VibrantDark => ThemeMode::Dark,
+            }
+        }
+    };
+
+    let registry = ThemeRegistry::global(cx);
+    let find_theme = |name: &Option<String>| {
+        name.as_ref()
+            .and_then(|name| registry.themes().get(name.as_str()).cloned())
+    };
+
+    let light_theme = find_theme(&appearance.light_theme)
+        .unwrap_or_else(|| registry.default_light_theme().clone());
+    let dark_theme =
+        find_theme(&appearance.dark_theme).unwrap_or_else(|| registry.default_dark_theme().clone());
+
+    let theme = Theme::global_mut(cx);
+    theme.light_theme = light_theme;
+    theme.dark_theme = dark_theme;
+
+    Theme::change(mode, None, cx);
+
+    // Move the notifications to the bottom right
+    let theme = Theme::global_mut(cx);
+    theme.notification.placement = Anchor::BottomRight;
+}
+
+/// Whether the theme follows the appearance of the operating system
+pub fn follows_system_appearance(cx: &App) -> bool {
+    cx.global::<AppSettings>().appearance.theme_mode == ThemeModeSetting::System
+}
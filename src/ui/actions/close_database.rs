@@ -1,12 +1,26 @@
 use gpui::{App, actions};
 
-use crate::state::{async_resource::AsyncResourceEntityExt, database::DatabaseResourceExt};
+use crate::{
+    state::{WindowSessions, focused_window_sessions},
+    ui::app::request_close_session,
+};
 
 actions!(file, [CloseDatabase]);
 
+/// Close the database of the focused session within the focused window
 pub fn close_database(_: &CloseDatabase, cx: &mut App) {
-    let database = cx.database();
-    database.set_idle(cx);
+    let Some(sessions) = focused_window_sessions(cx) else {
+        return;
+    };
 
-    tracing::debug!("closed database");
+    let Some(window) = cx.active_window() else {
+        return;
+    };
+
+    let id = WindowSessions::active_session(&sessions, cx).id;
+    _ = window.update(cx, |_view, window, cx| {
+        request_close_session(&sessions, id, window, cx);
+    });
+
+    tracing::debug!("requested closing database");
 }
@@ -0,0 +1,19 @@
+use gpui::{App, actions};
+
+use crate::{state::database::DatabaseResourceExt, ui::views::sql_dump::open_sql_dump_dialog};
+
+actions!(file, [ExportSqlDump]);
+
+pub fn export_sql_dump(_: &ExportSqlDump, cx: &mut App) {
+    // Exporting requires an active database to export from
+    if cx.database_connection().is_none() {
+        return;
+    }
+
+    let window = cx.active_window().expect("expected a active window");
+    cx.defer(move |cx| {
+        _ = window.update(cx, move |_view, window, cx| {
+            open_sql_dump_dialog(window, cx);
+        });
+    });
+}
@@ -0,0 +1,30 @@
+use gpui::{Action, App, SharedString};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    state::database::DatabaseResourceExt,
+    ui::views::export::{ExportSource, open_export_dialog},
+};
+
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = file)]
+pub struct ExportTable {
+    pub table: SharedString,
+}
+
+pub fn export_table(export: &ExportTable, cx: &mut App) {
+    // Exporting requires an active database to export from
+    if cx.database_connection().is_none() {
+        return;
+    }
+
+    let source = ExportSource::Table(export.table.to_string());
+
+    let window = cx.active_window().expect("expected a active window");
+    cx.defer(move |cx| {
+        _ = window.update(cx, move |_view, window, cx| {
+            open_export_dialog(source, window, cx);
+        });
+    });
+}
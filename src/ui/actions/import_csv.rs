@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use gpui::{App, ParentElement, PathPromptOptions, actions, px};
+use gpui_component::WindowExt;
+
+use crate::{
+    state::database::DatabaseResourceExt,
+    ui::{components::atoms::i18n::translated::ts, views::import_csv::CsvImportView},
+    utils::async_utils::resolve_async_callback_cx,
+};
+
+actions!(file, [ImportCsv]);
+
+pub fn import_csv(_: &ImportCsv, cx: &mut App) {
+    // Importing requires an active database to import into
+    if cx.database_connection().is_none() {
+        return;
+    }
+
+    let prompt_recv = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        multiple: false,
+        directories: false,
+        prompt: Some("CSV files (*.csv, *.tsv, *.txt)".into()),
+    });
+
+    resolve_async_callback_cx(cx, prompt_recv, move |cx, prompt_result| {
+        let paths = match prompt_result {
+            Ok(Ok(Some(value))) => value,
+
+            // Error occurred
+            Ok(Err(error)) => {
+                tracing::error!(?error, "failed to pick csv file");
+                return;
+            }
+
+            // Cancelled picking the file or picked nothing
+            Err(_) | Ok(Ok(None)) => return,
+        };
+
+        let path = match paths.first() {
+            Some(value) => value,
+            // Picked nothing
+            None => return,
+        };
+
+        let path = path.to_path_buf();
+
+        on_csv_path_picked(cx, path);
+    });
+}
+
+/// Handle the CSV file `path` being picked, opens the import wizard dialog
+fn on_csv_path_picked(cx: &mut App, path: PathBuf) {
+    let window = cx.active_window().expect("expected a active window");
+    _ = window.update(cx, move |_view, window, cx| {
+        let view = CsvImportView::new(path, window, cx);
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title(ts("import-csv"))
+                .width(px(900.))
+                .child(view.clone())
+        });
+    });
+}
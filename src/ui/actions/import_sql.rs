@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use gpui::{App, ParentElement, PathPromptOptions, actions, px};
+use gpui_component::WindowExt;
+
+use crate::{
+    state::database::DatabaseResourceExt,
+    ui::{components::atoms::i18n::translated::ts, views::import_sql::SqlImportView},
+    utils::async_utils::resolve_async_callback_cx,
+};
+
+actions!(file, [ImportSql]);
+
+pub fn import_sql(_: &ImportSql, cx: &mut App) {
+    // Importing requires an active database to execute against
+    if cx.database_connection().is_none() {
+        return;
+    }
+
+    let prompt_recv = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        multiple: false,
+        directories: false,
+        prompt: Some("SQL files (*.sql)".into()),
+    });
+
+    resolve_async_callback_cx(cx, prompt_recv, move |cx, prompt_result| {
+        let paths = match prompt_result {
+            Ok(Ok(Some(value))) => value,
+
+            // Error occurred
+            Ok(Err(error)) => {
+                tracing::error!(?error, "failed to pick sql file");
+                return;
+            }
+
+            // Cancelled picking the file or picked nothing
+            Err(_) | Ok(Ok(None)) => return,
+        };
+
+        let path = match paths.first() {
+            Some(value) => value,
+            // Picked nothing
+            None => return,
+        };
+
+        let path = path.to_path_buf();
+
+        on_sql_path_picked(cx, path);
+    });
+}
+
+/// Handle the SQL file `path` being picked, opens the import dialog
+fn on_sql_path_picked(cx: &mut App, path: PathBuf) {
+    let window = cx.active_window().expect("expected a active window");
+    _ = window.update(cx, move |_view, window, cx| {
+        let view = SqlImportView::new(path, cx);
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title(ts("import-sql"))
+                .width(px(600.))
+                .child(view.clone())
+        });
+    });
+}
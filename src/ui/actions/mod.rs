@@ -2,11 +2,17 @@ use gpui::App;
 
 pub mod close_database;
 pub mod copy_text;
+pub mod export_sql_dump;
+pub mod export_table;
+pub mod import_csv;
+pub mod import_sql;
 pub mod new_database;
 pub mod new_memory_database;
 pub mod open_encrypted_database;
 pub mod open_file;
+pub mod open_recent_database;
 pub mod quit;
+pub mod sql_file;
 
 pub fn register_actions(cx: &mut App) {
     cx.on_action(quit::quit);
@@ -15,5 +21,11 @@ pub fn register_actions(cx: &mut App) {
     cx.on_action(open_file::open_file);
     cx.on_action(close_database::close_database);
     cx.on_action(open_encrypted_database::open_encrypted_database);
+    cx.on_action(open_recent_database::open_recent_database);
+    cx.on_action(open_recent_database::remove_missing_recent_databases);
     cx.on_action(copy_text::copy_text);
+    cx.on_action(import_csv::import_csv);
+    cx.on_action(import_sql::import_sql);
+    cx.on_action(export_table::export_table);
+    cx.on_action(export_sql_dump::export_sql_dump);
 }
@@ -7,7 +7,7 @@ use tokio::fs::File;
 use crate::database::AnySharedDatabase;
 use crate::database::sqlite::{SqliteDatabase, SqliteDatabaseOptions};
 use crate::state::async_resource::AsyncResourceEntityExt;
-use crate::state::database::DatabaseResourceExt;
+use crate::ui::app::open_session;
 use crate::utils::async_utils::resolve_async_callback_cx;
 
 actions!(file, [NewDatabase]);
@@ -43,7 +43,7 @@ pub fn new_database(_: &NewDatabase, cx: &mut App) {
 
 /// Handle the file `path` of the database being picked
 fn on_database_path_picked(cx: &mut App, path: PathBuf) {
-    let database = cx.database();
+    let database = open_session(cx);
 
     database.maybe_load(cx, async move || {
         if let Err(error) = File::create(&path).await {
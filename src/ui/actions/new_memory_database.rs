@@ -5,13 +5,14 @@ use gpui::{App, actions};
 
 use crate::{
     database::{AnySharedDatabase, sqlite::SqliteDatabase},
-    state::{async_resource::AsyncResourceEntityExt, database::DatabaseResourceExt},
+    state::async_resource::AsyncResourceEntityExt,
+    ui::app::open_session,
 };
 
 actions!(file, [NewMemoryDatabase]);
 
 pub fn new_memory_database(_: &NewMemoryDatabase, cx: &mut App) {
-    let database = cx.database();
+    let database = open_session(cx);
 
     database.load(cx, async move || {
         let database = SqliteDatabase::memory()
@@ -5,7 +5,8 @@ use crate::{
         AnySharedDatabase,
         sqlite::{SqliteDatabase, SqliteDatabaseOptions},
     },
-    state::{async_resource::AsyncResourceEntityExt, database::DatabaseResourceExt},
+    state::async_resource::AsyncResourceEntityExt,
+    ui::app::open_session,
     utils::async_utils::resolve_async_callback_cx,
 };
 use anyhow::Context;
@@ -70,7 +71,7 @@ fn on_encrypted_database_path_picked(cx: &mut App, path: PathBuf, readonly: bool
 
 /// Open a dialog to prompt for the password for the database at `path`
 /// optionally opening as `readonly`
-fn on_open_password_dialog(window: &mut Window, cx: &mut App, path: PathBuf, readonly: bool) {
+pub fn on_open_password_dialog(window: &mut Window, cx: &mut App, path: PathBuf, readonly: bool) {
     let input = cx.new(|cx| {
         InputState::new(window, cx)
             .masked(true)
@@ -116,7 +117,7 @@ fn on_open_password_dialog(window: &mut Window, cx: &mut App, path: PathBuf, rea
 /// Handle the password (`key`) being provided for the database at `path` attempts
 /// to connect to and use the database optionally opening as `readonly`
 fn on_database_password(cx: &mut App, path: PathBuf, readonly: bool, key: String) {
-    let database = cx.database();
+    let database = open_session(cx);
 
     database.maybe_load(cx, async move || {
         tracing::debug!(?path, "picked file for opening");
@@ -3,22 +3,33 @@ use crate::{
         AnySharedDatabase,
         sqlite::{SqliteDatabase, SqliteDatabaseOptions},
     },
-    state::{async_resource::AsyncResourceEntityExt, database::DatabaseResourceExt},
+    state::async_resource::{AsyncResource, AsyncResourceEntityExt},
+    ui::app::{open_session, open_session_in_new_window},
     utils::async_utils::resolve_async_callback_cx,
 };
 use anyhow::Context;
-use gpui::{Action, App, PathPromptOptions};
+use gpui::{Action, App, Entity, PathPromptOptions};
 use schemars::JsonSchema;
 use serde::Deserialize;
 use std::{path::PathBuf, rc::Rc};
+use url::Url;
 
 #[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
 #[action(namespace = file)]
 pub struct OpenFile {
     pub read_only: bool,
+    /// Open the database within a new window
+    #[serde(default)]
+    pub new_window: bool,
 }
 
-pub fn open_file(OpenFile { read_only }: &OpenFile, cx: &mut App) {
+pub fn open_file(
+    OpenFile {
+        read_only,
+        new_window,
+    }: &OpenFile,
+    cx: &mut App,
+) {
     let prompt_recv = cx.prompt_for_paths(PathPromptOptions {
         files: true,
         multiple: false,
@@ -27,6 +38,7 @@ pub fn open_file(OpenFile { read_only }: &OpenFile, cx: &mut App) {
     });
 
     let readonly = *read_only;
+    let new_window = *new_window;
 
     resolve_async_callback_cx(cx, prompt_recv, move |cx, prompt_result| {
         let paths = match prompt_result {
@@ -50,21 +62,54 @@ pub fn open_file(OpenFile { read_only }: &OpenFile, cx: &mut App) {
 
         let path = path.to_path_buf();
 
-        on_database_path_picked(cx, path, readonly);
+        match new_window {
+            true => {
+                let database = open_session_in_new_window(cx);
+                load_database_file(cx, database, path, readonly_options(readonly));
+            }
+            false => on_database_path_picked(cx, path, readonly),
+        }
     });
 }
 
 /// Handle the file `path` of the database being picked
-fn on_database_path_picked(cx: &mut App, path: PathBuf, readonly: bool) {
-    let database = cx.database();
+pub fn on_database_path_picked(cx: &mut App, path: PathBuf, readonly: bool) {
+    let database = open_session(cx);
+    load_database_file(cx, database, path, readonly_options(readonly));
+}
+
+/// Open the database files of the `urls` the application was asked to open
+/// by the operating system, URLs that aren't files are ignored
+pub fn open_file_urls(cx: &mut App, urls: Vec<String>) {
+    for url in urls {
+        match Url::parse(&url)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+        {
+            Some(path) => on_database_path_picked(cx, path, false),
+            None => tracing::warn!(?url, "ignoring non-file url to open"),
+        }
+    }
+}
+
+/// Options for opening an unencrypted database optionally as `readonly`
+fn readonly_options(readonly: bool) -> SqliteDatabaseOptions {
+    SqliteDatabaseOptions {
+        readonly,
+        ..Default::default()
+    }
+}
+
+/// Load the database file at `path` into the `database` resource
+pub fn load_database_file(
+    cx: &mut App,
+    database: Entity<AsyncResource<AnySharedDatabase>>,
+    path: PathBuf,
+    options: SqliteDatabaseOptions,
+) {
     database.maybe_load(cx, async move || {
         tracing::debug!(?path, "picked file for opening");
 
-        let options = SqliteDatabaseOptions {
-            readonly,
-            ..Default::default()
-        };
-
         let database = SqliteDatabase::from_path(&path, options)
             .await
             .context("failed to connect to database")?;
@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use gpui::{Action, App, actions};
+use gpui_component::{
+    WindowExt,
+    notification::{Notification, NotificationType},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{
+    recent::{RecentDatabases, update_recent_databases},
+    ui::{
+        actions::{
+            open_encrypted_database::on_open_password_dialog, open_file::on_database_path_picked,
+        },
+        components::atoms::i18n::translated::ts,
+    },
+};
+
+#[derive(PartialEq, Clone, Default, Debug, Deserialize, JsonSchema, Action)]
+#[action(namespace = file)]
+pub struct OpenRecentDatabase {
+    pub path: String,
+}
+
+actions!(file, [RemoveMissingRecentDatabases]);
+
+/// Reopen a recent database the same way it was last opened, prompting for
+/// the key of encrypted databases
+pub fn open_recent_database(OpenRecentDatabase { path }: &OpenRecentDatabase, cx: &mut App) {
+    let Some(recent) = cx.global::<RecentDatabases>().find(path).cloned() else {
+        return;
+    };
+
+    if recent.is_missing() {
+        if let Some(window) = cx.active_window() {
+            _ = window.update(cx, |_view, window, cx| {
+                window.push_notification(
+                    Notification::new()
+                        .message(format!(
+                            "{}: {}",
+                            ts("recent-database-missing"),
+                            recent.path
+                        ))
+                        .with_type(NotificationType::Error),
+                    cx,
+                );
+            });
+        }
+
+        return;
+    }
+
+    let path = PathBuf::from(recent.path);
+
+    if !recent.encrypted {
+        on_database_path_picked(cx, path, recent.readonly);
+        return;
+    }
+
+    // The key is prompted for within the focused window
+    if let Some(window) = cx.active_window() {
+        _ = window.update(cx, move |_view, window, cx| {
+            on_open_password_dialog(window, cx, path, recent.readonly);
+        });
+    }
+}
+
+pub fn remove_missing_recent_databases(_: &RemoveMissingRecentDatabases, cx: &mut App) {
+    update_recent_databases(cx, |recent| recent.remove_missing());
+}
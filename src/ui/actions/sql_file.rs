@@ -0,0 +1,6 @@
+//! Actions for the SQL files of the query editor, handled by the focused
+//! query editor tabs rather than globally
+
+use gpui::actions;
+
+actions!(editor, [OpenSqlFile, SaveSqlFile, SaveSqlFileAs]);
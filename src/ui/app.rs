@@ -1,48 +1,297 @@
 use crate::{
-    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    cli::take_startup_error,
+    database::AnySharedDatabase,
+    recent::RecentDatabases,
+    settings::AppSettings,
+    state::{
+        WindowSessions, async_resource::AsyncResource, focused_window_sessions, register_window,
+        unregister_window,
+    },
+    theme::{apply_theme, follows_system_appearance},
     ui::{
-        components::organisms::titlebar::AppTitleBar,
-        views::{database::DatabaseView, welcome::WelcomeView},
+        components::{atoms::i18n::translated::ts, organisms::titlebar::AppTitleBar},
+        views::{
+            database::DatabaseView, unsaved_changes::open_unsaved_changes_dialog,
+            welcome::WelcomeView,
+        },
     },
 };
 use gpui::{
-    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, Styled, Window, div,
+    App, AppContext, Bounds, Context, Entity, InteractiveElement, IntoElement, MouseButton,
+    ParentElement, PromptLevel, Render, Styled, Window, WindowBounds, WindowOptions, div,
+    prelude::FluentBuilder, px, size,
+};
+use gpui_component::{
+    IconName, Root, Sizable, StyledExt, TitleBar,
+    button::{Button, ButtonVariants},
+    tab::{Tab, TabBar},
 };
-use gpui_component::{Root, StyledExt};
 
 pub struct MainApp {
+    /// Database sessions open within the window
+    sessions: Entity<WindowSessions>,
     app_title_bar: Entity<AppTitleBar>,
-    database_view: Entity<DatabaseView>,
+    /// Views for each of the database sessions by session id
+    database_views: Vec<(usize, Entity<DatabaseView>)>,
+}
+
+/// Open a new main window for the database `sessions`
+pub fn open_main_window(sessions: Entity<WindowSessions>, cx: &mut App) {
+    let window_settings = cx.global::<AppSettings>().window.clone();
+    let bounds = Bounds::centered(
+        None,
+        size(px(window_settings.width), px(window_settings.height)),
+        cx,
+    );
+
+    cx.spawn(async move |cx| {
+        cx.open_window(
+            WindowOptions {
+                titlebar: Some(TitleBar::title_bar_options()),
+                window_bounds: Some(WindowBounds::Windowed(bounds)),
+                ..Default::default()
+            },
+            |window, cx| {
+                register_window(cx, window.window_handle(), sessions.clone());
+
+                let view = MainApp::new(sessions, window, cx);
+
+                if let Some(error) = take_startup_error(cx) {
+                    show_startup_error(error, window, cx);
+                }
+
+                cx.new(|cx| Root::new(view, window, cx))
+            },
+        )?;
+
+        Ok::<_, anyhow::Error>(())
+    })
+    .detach();
+}
+
+/// Show the `error` from the command line within the `window`, quitting once
+/// dismissed as it would have when launched from a terminal
+fn show_startup_error(error: String, window: &mut Window, cx: &mut App) {
+    let ok = ts("ok");
+    let answer = window.prompt(
+        PromptLevel::Critical,
+        &ts("startup-error"),
+        Some(&error),
+        &[ok.as_ref()],
+        cx,
+    );
+
+    cx.spawn(async move |cx| {
+        _ = answer.await;
+        cx.update(|cx| cx.quit());
+    })
+    .detach();
+}
+
+/// Get the database resource to open a database into within the focused
+/// window, opening a new window when there are no windows open
+pub fn open_session(cx: &mut App) -> Entity<AsyncResource<AnySharedDatabase>> {
+    match focused_window_sessions(cx) {
+        Some(sessions) => WindowSessions::open_session(&sessions, cx),
+        None => open_session_in_new_window(cx),
+    }
+}
+
+/// Get the database resource to open a database into within a new window
+pub fn open_session_in_new_window(cx: &mut App) -> Entity<AsyncResource<AnySharedDatabase>> {
+    let sessions = WindowSessions::new(cx);
+    let database = WindowSessions::open_session(&sessions, cx);
+    open_main_window(sessions, cx);
+    database
+}
+
+/// Close the session with the provided `id`, asking for confirmation when
+/// its query editor has unsaved changes
+pub fn request_close_session(
+    sessions: &Entity<WindowSessions>,
+    id: usize,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let Some(session) = sessions
+        .read(cx)
+        .sessions
+        .iter()
+        .find(|session| session.id == id)
+        .cloned()
+    else {
+        return;
+    };
+
+    if !session.unsaved_changes.get() {
+        WindowSessions::close_session(sessions, id, cx);
+        return;
+    }
+
+    let title = session.title(cx).unwrap_or_default();
+    let message = format!("{} {title}", ts("unsaved-changes-session"));
+    let sessions = sessions.clone();
+
+    open_unsaved_changes_dialog(window, cx, message, move |_window, cx| {
+        session.unsaved_changes.set(false);
+        WindowSessions::close_session(&sessions, id, cx);
+    });
 }
 
 impl MainApp {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<MainApp> {
-        cx.new(|cx| MainApp {
-            app_title_bar: AppTitleBar::new(window, cx),
-            database_view: DatabaseView::new(window, cx),
+    pub fn new(
+        sessions: Entity<WindowSessions>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<MainApp> {
+        cx.new(|cx| {
+            // Update the recent databases on the welcome screen
+            cx.observe_global::<RecentDatabases>(|_, cx| cx.notify())
+                .detach();
+
+            // Update the session tabs and drop the views of closed sessions
+            cx.observe(&sessions, |this: &mut MainApp, sessions, cx| {
+                let sessions = &sessions.read(cx).sessions;
+                this.database_views
+                    .retain(|(id, _)| sessions.iter().any(|session| session.id == *id));
+                cx.notify();
+            })
+            .detach();
+
+            // Follow changes to the system appearance
+            cx.observe_window_appearance(window, |_, window, cx| {
+                if follows_system_appearance(cx) {
+                    apply_theme(Some(window), cx);
+                    window.refresh();
+                }
+            })
+            .detach();
+
+            // Confirm unsaved query changes are discarded before the window closes
+            window.on_window_should_close(cx, {
+                let sessions = sessions.clone();
+
+                move |window, cx| {
+                    if !WindowSessions::has_unsaved_changes(&sessions, cx) {
+                        return true;
+                    }
+
+                    let sessions = sessions.clone();
+                    let message = ts("unsaved-changes-window").to_string();
+                    open_unsaved_changes_dialog(window, cx, message, move |window, cx| {
+                        for session in &sessions.read(cx).sessions {
+                            session.unsaved_changes.set(false);
+                        }
+
+                        window.remove_window();
+                    });
+
+                    false
+                }
+            });
+
+            // Drop the sessions of the window once it is closed
+            let handle = window.window_handle();
+            cx.on_release(move |_, cx| unregister_window(cx, handle))
+                .detach();
+
+            MainApp {
+                app_title_bar: AppTitleBar::new(sessions.clone(), window, cx),
+                sessions,
+                database_views: Vec::new(),
+            }
         })
     }
+
+    /// Get the view for the focused session, creating it if the session
+    /// doesn't have one yet
+    fn active_database_view(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<DatabaseView> {
+        let session = WindowSessions::active_session(&self.sessions, cx);
+
+        if let Some((_, view)) = self.database_views.iter().find(|(id, _)| *id == session.id) {
+            return view.clone();
+        }
+
+        let id = session.id;
+        let view = DatabaseView::new(session, window, cx);
+        self.database_views.push((id, view.clone()));
+        view
+    }
+
+    fn on_change_session(&mut self, index: &usize, _window: &mut Window, cx: &mut Context<Self>) {
+        let id = self
+            .sessions
+            .read(cx)
+            .sessions
+            .get(*index)
+            .map(|session| session.id);
+
+        if let Some(id) = id {
+            WindowSessions::activate_session(&self.sessions, id, cx);
+        }
+    }
+
+    /// Render the tabs for switching between the open sessions
+    fn render_session_tabs(&self, cx: &Context<Self>) -> impl IntoElement {
+        let state = self.sessions.read(cx);
+
+        let tabs: Vec<Tab> = state
+            .sessions
+            .iter()
+            .map(|session| {
+                let id = session.id;
+                let sessions = self.sessions.clone();
+                let title = session
+                    .title(cx)
+                    .unwrap_or_else(|| match session.is_idle(cx) {
+                        true => ts("not-connected"),
+                        false => ts("loading-database"),
+                    });
+
+                Tab::new().label(title).suffix(
+                    Button::new(("close-session", id))
+                        .icon(IconName::Close)
+                        .ghost()
+                        .xsmall()
+                        .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                            cx.stop_propagation();
+                            request_close_session(&sessions, id, window, cx);
+                        }),
+                )
+            })
+            .collect();
+
+        TabBar::new("sessions")
+            .selected_index(state.active)
+            .on_click(cx.listener(Self::on_change_session))
+            .children(tabs)
+    }
 }
 
 impl Render for MainApp {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let dialog_layer = Root::render_dialog_layer(window, cx);
         let notification_layer = Root::render_notification_layer(window, cx);
-        let database = cx.database();
+        let session = WindowSessions::active_session(&self.sessions, cx);
+        let multiple_sessions = self.sessions.read(cx).sessions.len() > 1;
+
+        let content = match session.database(cx).read(cx) {
+            AsyncResource::Idle => WelcomeView.into_any_element(),
+            _ => self.active_database_view(window, cx).into_any_element(),
+        };
 
         div()
             .v_flex()
             .size_full()
             .child(self.app_title_bar.clone())
-            .child(
-                div()
-                    .flex_auto()
-                    .overflow_hidden()
-                    .child(match database.read(cx) {
-                        AsyncResource::Idle => WelcomeView.into_any_element(),
-                        _ => self.database_view.clone().into_any_element(),
-                    }),
-            )
+            .when(multiple_sessions, |this| {
+                this.child(self.render_session_tabs(cx))
+            })
+            .child(div().flex_auto().overflow_hidden().child(content))
             .children(notification_layer)
             .children(dialog_layer)
     }
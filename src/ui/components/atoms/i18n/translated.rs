@@ -16,6 +16,18 @@ pub fn ts(key: &'static str) -> SharedString {
     }
 }
 
+/// Translate the `key`, replacing the `%{name}` placeholders of the
+/// translation with the values of the `args`
+pub fn ts_args(key: &'static str, args: &[(&str, String)]) -> SharedString {
+    let mut translated = ts(key).to_string();
+
+    for (name, value) in args {
+        translated = translated.replace(&format!("%{{{name}}}"), value);
+    }
+
+    SharedString::from(translated)
+}
+
 impl IntoElement for Translated {
     type Element = SharedString;
 
@@ -1,6 +1,7 @@
 use gpui::{
     AnyView, App, AppContext, Context, Entity, InteractiveElement, IntoElement, MouseButton,
-    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window, div, px, rems,
+    ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Window,
+    div, px, rems,
 };
 use gpui_component::{
     ActiveTheme, IconName, Sizable, StyledExt,
@@ -10,12 +11,17 @@ use gpui_component::{
 };
 
 use crate::{
-    state::database::DatabaseResourceExt,
+    state::WindowSessions,
     ui::{actions::close_database::CloseDatabase, components::atoms::i18n::translated::ts},
 };
 
 pub struct DatabaseStatusLabel {
     database_options: Option<SharedDatabaseOptions>,
+
+    /// Database sessions of the window the label is within
+    sessions: Entity<WindowSessions>,
+    /// Subscription to the database of the focused session
+    database_subscription: Option<Subscription>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,26 +37,50 @@ pub struct SharedDatabaseOptions {
 }
 
 impl DatabaseStatusLabel {
-    pub fn new(_window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(
+        sessions: Entity<WindowSessions>,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
         cx.new(|cx| {
-            let database = cx.database();
-
-            cx.observe(&database, |this: &mut DatabaseStatusLabel, _, cx| {
-                this.update_database_options(cx);
+            // Follow the database of the focused session
+            cx.observe(&sessions, |this: &mut DatabaseStatusLabel, _, cx| {
+                this.observe_active_database(cx);
             })
             .detach();
 
-            DatabaseStatusLabel {
+            let mut this = DatabaseStatusLabel {
                 database_options: None,
-            }
+                sessions,
+                database_subscription: None,
+            };
+
+            this.observe_active_database(cx);
+
+            this
         })
     }
 
+    fn observe_active_database(&mut self, cx: &mut Context<'_, Self>) {
+        let database = WindowSessions::active_session(&self.sessions, cx).database(cx);
+
+        self.database_subscription = Some(cx.observe(
+            &database,
+            |this: &mut DatabaseStatusLabel, _, cx| {
+                this.update_database_options(cx);
+            },
+        ));
+
+        self.update_database_options(cx);
+    }
+
     fn update_database_options(&mut self, cx: &mut Context<'_, Self>) {
-        let database = match cx.database_connection() {
+        let session = WindowSessions::active_session(&self.sessions, cx);
+        let database = match session.database_connection(cx) {
             Some(value) => value,
             _ => {
                 self.database_options = None;
+                cx.notify();
                 return;
             }
         };
@@ -61,6 +91,7 @@ impl DatabaseStatusLabel {
             encrypted: options.encrypted,
             readonly: options.readonly,
         });
+        cx.notify();
     }
 }
 
@@ -0,0 +1,65 @@
+//! Helpers shared between the form views for labelled fields and selects
+//! over a fixed set of translated options
+
+use gpui::{
+    App, AppContext, Entity, IntoElement, ParentElement, SharedString, Styled, Window, div,
+};
+use gpui_component::{
+    IndexPath, StyledExt,
+    select::{SearchableVec, SelectState},
+};
+
+use crate::ui::components::atoms::i18n::translated::ts;
+
+/// State of a select over string items
+pub type StringSelectState = SelectState<SearchableVec<String>>;
+
+/// Helper to render a form `element` with a `label` above it
+pub fn labelled(label: SharedString, element: impl IntoElement) -> impl IntoElement {
+    div()
+        .v_flex()
+        .gap_1()
+        .child(div().text_xs().child(label))
+        .child(element)
+}
+
+/// Create the state for a select over the translated labels of `options`
+/// with the option matching `selected` initially selected
+pub fn options_select<T: PartialEq>(
+    options: &[(&'static str, T)],
+    selected: &T,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<StringSelectState> {
+    let labels: Vec<String> = options
+        .iter()
+        .map(|(label, _)| ts(label).to_string())
+        .collect();
+
+    let selected = options
+        .iter()
+        .position(|(_, value)| value == selected)
+        .unwrap_or_default();
+
+    cx.new(|cx| {
+        SelectState::new(
+            SearchableVec::new(labels),
+            Some(IndexPath::new(selected)),
+            window,
+            cx,
+        )
+    })
+}
+
+/// Get the option value for the currently selected item of `state`
+pub fn selected_option<T: Copy>(
+    options: &[(&'static str, T)],
+    state: &Entity<StringSelectState>,
+    cx: &App,
+) -> Option<T> {
+    state
+        .read(cx)
+        .selected_index(cx)
+        .and_then(|index| options.get(index.row))
+        .map(|(_, value)| *value)
+}
@@ -1,2 +1,3 @@
 pub mod database_status_label;
+pub mod form;
 pub mod welcome;
@@ -1,6 +1,6 @@
 use gpui::{
     App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
-    Task, Window, div,
+    Subscription, Task, Window, div,
 };
 use gpui_component::{
     StyledExt,
@@ -10,12 +10,15 @@ use gpui_component::{
 
 use crate::{
     database::{AnySharedDatabase, DatabaseQueryResult, DatabaseRow, DatabaseTableQuery},
-    state::database::DatabaseResourceExt,
+    settings::{AppSettings, SettingsChangedEvent, settings_notifier},
+    state::async_resource::AsyncResource,
     ui::components::organisms::pagination::Pagination,
 };
 
 /// Component for browsing the contents of a table
 pub struct DatabaseTableBrowser {
+    /// Database the table belongs to
+    database: Entity<AsyncResource<AnySharedDatabase>>,
     /// The table being browsed
     table: String,
     /// Pagination of the dataset
@@ -26,6 +29,8 @@ pub struct DatabaseTableBrowser {
 
     /// State for the table data loading
     load_state: TableLoadState,
+
+    _settings_subscription: Subscription,
 }
 
 enum TableLoadState {
@@ -44,11 +49,11 @@ struct TablePaginationData {
     count: Option<i64>,
 }
 
-impl Default for TablePaginationData {
-    fn default() -> Self {
+impl TablePaginationData {
+    fn new(page_size: i64) -> Self {
         Self {
             page: 0,
-            page_size: 5,
+            page_size,
             count: None,
         }
     }
@@ -82,22 +87,47 @@ impl TableDelegate for BrowseTableDelegate {
     ) -> impl IntoElement {
         let row = &self.data[row_ix];
         let value = row.values.get(col_ix);
-        value.cloned().unwrap_or_default()
+        value
+            .map(|value| SharedString::from(value.to_string()))
+            .unwrap_or_default()
     }
 }
 
 impl DatabaseTableBrowser {
-    pub fn new(table: String, window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(
+        database: Entity<AsyncResource<AnySharedDatabase>>,
+        table: String,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
         cx.new(|cx| {
             let table_delegate = BrowseTableDelegate::default();
             let table_state = cx.new(|cx| TableState::new(table_delegate, window, cx));
-            let pagination = TablePaginationData::default();
+            let page_size = cx.global::<AppSettings>().table.page_size;
+            let pagination = TablePaginationData::new(page_size as i64);
+
+            // Reload from the first page when the page size setting changes
+            let settings_subscription = cx.subscribe_in(
+                &settings_notifier(cx),
+                window,
+                |this: &mut Self, _, event: &SettingsChangedEvent, window, cx| {
+                    let page_size = cx.global::<AppSettings>().table.page_size;
+                    if event.previous.table.page_size == page_size {
+                        return;
+                    }
+
+                    this.pagination = TablePaginationData::new(page_size as i64);
+                    this.load_table_page(window, cx);
+                },
+            );
 
             let mut this = Self {
+                database,
                 table,
                 pagination,
                 table_state,
                 load_state: TableLoadState::Idle,
+                _settings_subscription: settings_subscription,
             };
 
             this.load_table_page(window, cx);
@@ -138,9 +168,9 @@ impl DatabaseTableBrowser {
 
     /// Load the current table
     fn load_table_page(&mut self, window: &mut Window, cx: &mut Context<'_, Self>) {
-        let database = match cx.database_connection() {
-            Some(value) => value,
-            None => {
+        let database = match self.database.read(cx) {
+            AsyncResource::Loaded(value) => value.clone(),
+            _ => {
                 self.pagination.count = None;
                 self.load_state = TableLoadState::Idle;
                 return;
@@ -1,9 +1,13 @@
-use gpui::{App, AppContext, Entity, ParentElement, Render, Styled, Window, div};
+use gpui::{App, AppContext, Entity, ParentElement, Render, SharedString, Styled, Window, div};
 use gpui_component::{
-    ActiveTheme, IndexPath, StyledExt, h_flex,
+    ActiveTheme, IndexPath, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
     select::{SearchableVec, Select, SelectState},
 };
 
+use crate::ui::{actions::export_table::ExportTable, components::atoms::i18n::translated::ts};
+
 pub type TableSelectState = SelectState<SearchableVec<String>>;
 
 /// Toolbar for the database browser options
@@ -58,19 +62,43 @@ impl Render for DatabaseBrowseDataViewToolbar {
     fn render(
         &mut self,
         _window: &mut Window,
-        _cx: &mut gpui::Context<Self>,
+        cx: &mut gpui::Context<Self>,
     ) -> impl gpui::IntoElement {
-        div().h_flex().child(
-            div().w_auto().child(
-                Select::new(&self.table_select_state)
-                    .empty(|_window, app| {
-                        h_flex()
-                            .justify_center()
-                            .text_color(app.theme().muted_foreground)
-                            .child("No options available")
-                    })
-                    .min_w_80(),
-            ),
-        )
+        let selected_table = self.table_select_state.read(cx).selected_value().cloned();
+
+        div()
+            .h_flex()
+            .gap_2()
+            .child(
+                div().w_auto().child(
+                    Select::new(&self.table_select_state)
+                        .empty(|_window, app| {
+                            h_flex()
+                                .justify_center()
+                                .text_color(app.theme().muted_foreground)
+                                .child("No options available")
+                        })
+                        .min_w_80(),
+                ),
+            )
+            .child(
+                Button::new("export-table")
+                    .ghost()
+                    .small()
+                    .label(ts("export"))
+                    .disabled(selected_table.is_none())
+                    .on_click(move |_event, window, cx| {
+                        let Some(table) = selected_table.clone() else {
+                            return;
+                        };
+
+                        window.dispatch_action(
+                            Box::new(ExportTable {
+                                table: SharedString::from(table),
+                            }),
+                            cx,
+                        );
+                    }),
+            )
     }
 }
@@ -15,24 +15,28 @@ use gpui_component::{
     tooltip::Tooltip,
     tree::{TreeItem, TreeState, tree},
 };
-use sqlformat::FormatOptions;
 use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     database::DatabaseTable,
-    state::database::DatabaseResourceExt,
+    settings::AppSettings,
+    state::DatabaseSession,
     ui::{
-        actions::copy_text::CopyText,
+        actions::{copy_text::CopyText, export_table::ExportTable},
         components::{
             atoms::{i18n::translated::ts, icons::CustomIconName},
             organisms::sql_editor::SqlEditor,
         },
     },
+    utils::sql_format::format_sql,
 };
 
 /// Tree view of the database tables and their schema revealed by expanding
 /// each tree item
 pub struct DatabaseTablesTreeView {
+    /// Session of the database the tables are within
+    session: DatabaseSession,
+
     /// Tree state
     tree_state: Entity<TreeState>,
 
@@ -57,8 +61,9 @@ struct TableColumnData {
 }
 
 impl DatabaseTablesTreeView {
-    pub fn new(cx: &mut App) -> Entity<Self> {
+    pub fn new(session: DatabaseSession, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| Self {
+            session,
             tables_data: Default::default(),
             columns_data: Default::default(),
             tree_state: cx.new(|cx| TreeState::new(cx)),
@@ -117,6 +122,7 @@ impl Render for DatabaseTablesTreeView {
     ) -> impl gpui::IntoElement {
         let tables_data = self.tables_data.clone();
         let columns_data = self.columns_data.clone();
+        let session = self.session.clone();
 
         tree(
             &self.tree_state,
@@ -132,7 +138,7 @@ impl Render for DatabaseTablesTreeView {
                     return ListItem::new(ix)
                         .selected(selected)
                         .pl(px(16.) * entry.depth() + px(12.)) // Indent based on depth
-                        .child(TableTreeItem::new(entry_data));
+                        .child(TableTreeItem::new(entry_data, session.clone()));
                 }
 
                 let column = columns_data
@@ -154,25 +160,31 @@ impl Render for DatabaseTablesTreeView {
 struct TableTreeItem {
     /// Details about the represented table
     table_data: TableData,
+    /// Session of the database the table is within
+    session: DatabaseSession,
 }
 
 impl TableTreeItem {
-    fn new(table_data: TableData) -> Self {
-        Self { table_data }
+    fn new(table_data: TableData, session: DatabaseSession) -> Self {
+        Self {
+            table_data,
+            session,
+        }
     }
 
     /// Helper to create the hover tooltip factory function for table tree items
     /// (Shows the formatted creation SQL)
-    fn tooltip(sql: SharedString) -> impl Fn(&mut Window, &mut App) -> AnyView + 'static {
+    fn tooltip(
+        sql: SharedString,
+        session: DatabaseSession,
+    ) -> impl Fn(&mut Window, &mut App) -> AnyView + 'static {
         move |window, cx| {
             let sql = sql.clone();
+            let session = session.clone();
             Tooltip::element(move |window, cx| {
-                let database = cx.database();
-
-                let options = FormatOptions::default();
-                let formatted = sqlformat::format(&sql, &sqlformat::QueryParams::None, &options);
+                let formatted = format_sql(&sql, &cx.global::<AppSettings>().formatter);
 
-                let editor = SqlEditor::new(window, cx, formatted.into(), true, false, database);
+                let editor = SqlEditor::new(window, cx, formatted.into(), true, false, &session);
 
                 div()
                     //
@@ -188,6 +200,7 @@ impl TableTreeItem {
     /// Helper to create the factory function for producing the context menu
     /// revealed when right clicking a table tree item
     fn context_menu(
+        table_name: SharedString,
         sql: SharedString,
     ) -> impl Fn(PopupMenu, &mut Window, &mut Context<PopupMenu>) -> PopupMenu + 'static {
         move |menu, _window, _cx| {
@@ -201,6 +214,13 @@ impl TableTreeItem {
                         text: sql.clone(),
                     }),
                 )
+                .separator()
+                .menu(
+                    ts("export-table"),
+                    Box::new(ExportTable {
+                        table: table_name.clone(),
+                    }),
+                )
         }
     }
 }
@@ -224,9 +244,9 @@ impl RenderOnce for TableTreeItem {
                     .id(ElementId::Name(
                         format!("schema-tooltip-{table_name}").into(),
                     ))
-                    .tooltip(Self::tooltip(sql.clone())),
+                    .tooltip(Self::tooltip(sql.clone(), self.session)),
             )
-            .context_menu(Self::context_menu(sql.clone()))
+            .context_menu(Self::context_menu(table_name, sql))
     }
 }
 
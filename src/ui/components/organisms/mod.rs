@@ -1,4 +1,6 @@
 pub mod database;
 pub mod pagination;
+pub mod query_history_panel;
+pub mod saved_queries_panel;
 pub mod sql_editor;
 pub mod titlebar;
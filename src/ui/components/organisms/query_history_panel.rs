@@ -0,0 +1,227 @@
+//! [QueryHistoryPanel] listing the previously executed queries
+
+use gpui::{
+    App, AppContext, Context, Entity, EventEmitter, IntoElement, ParentElement, Render, Styled,
+    Subscription, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement,
+    switch::Switch,
+};
+
+use crate::{
+    history::{QueryHistory, QueryHistoryEntry, format_timestamp},
+    state::DatabaseSession,
+    ui::components::atoms::i18n::translated::ts,
+};
+
+/// Maximum number of entries rendered within the panel
+const MAX_VISIBLE_ENTRIES: usize = 200;
+
+/// Maximum number of characters of the query shown for an entry
+const MAX_PREVIEW_LENGTH: usize = 200;
+
+/// Event emitted when a history entry is chosen
+pub enum QueryHistoryEvent {
+    /// Insert the query into the editor
+    Insert(String),
+    /// Execute the query again
+    Run(String),
+}
+
+pub struct QueryHistoryPanel {
+    /// Session of the database the panel is shown for
+    session: DatabaseSession,
+    search_input: Entity<InputState>,
+    /// Only show the queries executed against the current database
+    current_database_only: bool,
+
+    _subscriptions: (Subscription, Subscription),
+}
+
+impl EventEmitter<QueryHistoryEvent> for QueryHistoryPanel {}
+
+impl QueryHistoryPanel {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let search_input =
+                cx.new(|cx| InputState::new(window, cx).placeholder(ts("history-search")));
+
+            let search_subscription = cx.subscribe(&search_input, |_, _, event, cx| {
+                if matches!(event, InputEvent::Change { .. }) {
+                    cx.notify();
+                }
+            });
+
+            let history_subscription = cx.observe_global::<QueryHistory>(|_, cx| cx.notify());
+
+            Self {
+                session,
+                search_input,
+                current_database_only: true,
+                _subscriptions: (search_subscription, history_subscription),
+            }
+        })
+    }
+
+    /// Entries matching the search and database filters, newest first
+    fn visible_entries<'a>(&self, cx: &'a App) -> Vec<(usize, &'a QueryHistoryEntry)> {
+        let search = self.search_input.read(cx).value().to_lowercase();
+        let database_path = match self.current_database_only {
+            true => self
+                .session
+                .database_connection(cx)
+                .map(|database| database.options().location()),
+            false => None,
+        };
+
+        cx.global::<QueryHistory>()
+            .entries
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| {
+                database_path
+                    .as_ref()
+                    .is_none_or(|path| &entry.database_path == path)
+            })
+            .filter(|(_, entry)| search.is_empty() || entry.query.to_lowercase().contains(&search))
+            .take(MAX_VISIBLE_ENTRIES)
+            .collect()
+    }
+
+    fn render_entry(
+        index: usize,
+        entry: &QueryHistoryEntry,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let mut preview: String = entry
+            .query
+            .trim()
+            .chars()
+            .take(MAX_PREVIEW_LENGTH)
+            .collect();
+        if preview.len() < entry.query.trim().len() {
+            preview.push('…');
+        }
+
+        let mut details = vec![
+            format_timestamp(entry.executed_at),
+            format!("{:.1} ms", entry.duration_ms),
+        ];
+        if let Some(row_count) = entry.row_count {
+            details.push(format!("{row_count} {}", ts("rows")));
+        }
+
+        let insert_query = entry.query.clone();
+        let run_query = entry.query.clone();
+
+        div()
+            .v_flex()
+            .gap_1()
+            .p_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(div().text_xs().font_family("monospace").child(preview))
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(details.join(" · ")),
+            )
+            .when_some(entry.error.clone(), |this, error| {
+                this.child(div().text_xs().text_color(cx.theme().danger).child(error))
+            })
+            .child(
+                div()
+                    .h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("history-insert", index))
+                            .label(ts("history-insert"))
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                cx.emit(QueryHistoryEvent::Insert(insert_query.clone()));
+                            })),
+                    )
+                    .child(
+                        Button::new(("history-run", index))
+                            .label(ts("history-run"))
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                cx.emit(QueryHistoryEvent::Run(run_query.clone()));
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for QueryHistoryPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries: Vec<(usize, QueryHistoryEntry)> = self
+            .visible_entries(cx)
+            .into_iter()
+            .map(|(index, entry)| (index, entry.clone()))
+            .collect();
+
+        div()
+            .v_flex()
+            .size_full()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .v_flex()
+                    .gap_2()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(Input::new(&self.search_input).small())
+                    .child(
+                        Switch::new("history-current-database")
+                            .label(ts("history-current-database"))
+                            .checked(self.current_database_only)
+                            .small()
+                            .on_click(cx.listener(|this, checked, _window, cx| {
+                                this.current_database_only = *checked;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scrollbar()
+                    .when(entries.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_2()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(ts("history-empty")),
+                        )
+                    })
+                    .children(
+                        entries
+                            .iter()
+                            .map(|(index, entry)| Self::render_entry(*index, entry, cx)),
+                    ),
+            )
+            .child(
+                div().p_2().h_flex().justify_end().child(
+                    Button::new("history-clear")
+                        .label(ts("history-clear"))
+                        .small()
+                        .on_click(cx.listener(|_, _, _, cx| {
+                            cx.update_global::<QueryHistory, _>(|history, _cx| history.clear());
+                        })),
+                ),
+            )
+    }
+}
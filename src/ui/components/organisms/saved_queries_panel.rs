@@ -0,0 +1,323 @@
+//! [SavedQueriesPanel] listing the queries saved within the query library
+
+use std::path::PathBuf;
+
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, EventEmitter, IntoElement, ParentElement,
+    PathPromptOptions, Render, Styled, Subscription, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    notification::{Notification, NotificationType},
+    scroll::ScrollableElement,
+};
+
+use crate::{
+    library::{QueryLibrary, SavedQuery, import_library, update_library},
+    state::DatabaseSession,
+    ui::components::atoms::i18n::translated::ts,
+};
+
+/// Name suggested when exporting the library
+const EXPORT_FILE_NAME: &str = "queries.json";
+
+/// Event emitted by the saved queries panel
+pub enum SavedQueriesEvent {
+    /// Open the query into the editor
+    Open(String),
+    /// Save the query within the editor into the library
+    SaveCurrent,
+}
+
+pub struct SavedQueriesPanel {
+    /// Session of the database the panel is shown for
+    session: DatabaseSession,
+    search_input: Entity<InputState>,
+
+    _subscriptions: (Subscription, Subscription),
+}
+
+impl EventEmitter<SavedQueriesEvent> for SavedQueriesPanel {}
+
+impl SavedQueriesPanel {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let search_input =
+                cx.new(|cx| InputState::new(window, cx).placeholder(ts("library-search")));
+
+            let search_subscription = cx.subscribe(&search_input, |_, _, event, cx| {
+                if matches!(event, InputEvent::Change { .. }) {
+                    cx.notify();
+                }
+            });
+
+            let library_subscription = cx.observe_global::<QueryLibrary>(|_, cx| cx.notify());
+
+            Self {
+                session,
+                search_input,
+                _subscriptions: (search_subscription, library_subscription),
+            }
+        })
+    }
+
+    /// Queries available for the current database matching the search,
+    /// along with their index within the library
+    fn visible_queries(&self, cx: &App) -> Vec<(usize, SavedQuery)> {
+        let search = self.search_input.read(cx).value().to_lowercase();
+        let database_path = self
+            .session
+            .database_connection(cx)
+            .map(|database| database.options().location());
+
+        cx.global::<QueryLibrary>()
+            .queries
+            .iter()
+            .enumerate()
+            .filter(|(_, query)| query.scope.includes(database_path.as_deref()))
+            .filter(|(_, query)| {
+                search.is_empty()
+                    || query.name.to_lowercase().contains(&search)
+                    || query.description.to_lowercase().contains(&search)
+                    || query.folder.to_lowercase().contains(&search)
+            })
+            .map(|(index, query)| (index, query.clone()))
+            .collect()
+    }
+
+    fn on_save_current(
+        &mut self,
+        _event: &ClickEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.emit(SavedQueriesEvent::SaveCurrent);
+    }
+
+    fn on_import(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let prompt_recv = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            multiple: false,
+            directories: false,
+            prompt: Some("Query library (*.json)".into()),
+        });
+
+        cx.spawn_in(window, async move |_this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(paths))) => match paths.into_iter().next() {
+                    Some(value) => value,
+                    None => return,
+                },
+                // Cancelled picking the file or picked nothing
+                _ => return,
+            };
+
+            _ = cx.update(|window, cx| {
+                let notification = match import_library(cx, &path) {
+                    Ok(count) => Notification::new()
+                        .message(format!(
+                            "{} {count} {}",
+                            ts("library-imported"),
+                            ts("library-queries")
+                        ))
+                        .with_type(NotificationType::Success),
+                    Err(error) => {
+                        tracing::error!(?error, "failed to import query library");
+                        Notification::new()
+                            .message(format!("{error:#}"))
+                            .with_type(NotificationType::Error)
+                    }
+                };
+
+                window.push_notification(notification, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn on_export(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let directory = directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or(PathBuf::from("."));
+        let prompt_recv = cx.prompt_for_new_path(&directory, Some(EXPORT_FILE_NAME));
+
+        cx.spawn_in(window, async move |_this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(value))) => value,
+                // Cancelled picking the file or picked nothing
+                _ => return,
+            };
+
+            _ = cx.update(|window, cx| {
+                let notification = match cx.global::<QueryLibrary>().export(&path) {
+                    Ok(()) => Notification::new()
+                        .message(ts("library-exported"))
+                        .with_type(NotificationType::Success),
+                    Err(error) => {
+                        tracing::error!(?error, "failed to export query library");
+                        Notification::new()
+                            .message(format!("{error:#}"))
+                            .with_type(NotificationType::Error)
+                    }
+                };
+
+                window.push_notification(notification, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn render_query(index: usize, query: &SavedQuery, cx: &mut Context<Self>) -> impl IntoElement {
+        let parameters = query
+            .parameters
+            .iter()
+            .map(|parameter| format!(":{}", parameter.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let text = query.text_with_defaults();
+
+        div()
+            .v_flex()
+            .gap_1()
+            .p_2()
+            .pl_4()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(div().text_sm().child(query.name.clone()))
+            .when(!query.description.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(query.description.clone()),
+                )
+            })
+            .when(!parameters.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(cx.theme().muted_foreground)
+                        .child(parameters),
+                )
+            })
+            .child(
+                div()
+                    .h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(("library-open", index))
+                            .label(ts("library-open"))
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                cx.emit(SavedQueriesEvent::Open(text.clone()));
+                            })),
+                    )
+                    .child(
+                        Button::new(("library-delete", index))
+                            .label(ts("library-delete"))
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |_, _, _, cx| {
+                                update_library(cx, |library| {
+                                    if index < library.queries.len() {
+                                        library.queries.remove(index);
+                                    }
+                                });
+                            })),
+                    ),
+            )
+    }
+}
+
+impl Render for SavedQueriesPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let queries = self.visible_queries(cx);
+
+        // Queries are sorted by folder so each folder is a single run
+        let mut items = Vec::new();
+        let mut current_folder: Option<&str> = None;
+
+        for (index, query) in &queries {
+            if current_folder != Some(query.folder.as_str()) {
+                current_folder = Some(query.folder.as_str());
+
+                let folder = match query.folder.is_empty() {
+                    true => ts("library-no-folder").to_string(),
+                    false => query.folder.clone(),
+                };
+
+                items.push(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .text_xs()
+                        .font_semibold()
+                        .bg(cx.theme().muted)
+                        .child(folder)
+                        .into_any_element(),
+                );
+            }
+
+            items.push(Self::render_query(*index, query, cx).into_any_element());
+        }
+
+        div()
+            .v_flex()
+            .size_full()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .v_flex()
+                    .gap_2()
+                    .p_2()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(
+                        div()
+                            .h_flex()
+                            .gap_1()
+                            .child(
+                                Button::new("library-save")
+                                    .label(ts("library-save"))
+                                    .small()
+                                    .on_click(cx.listener(Self::on_save_current)),
+                            )
+                            .child(
+                                Button::new("library-import")
+                                    .label(ts("import"))
+                                    .small()
+                                    .on_click(cx.listener(Self::on_import)),
+                            )
+                            .child(
+                                Button::new("library-export")
+                                    .label(ts("export"))
+                                    .small()
+                                    .on_click(cx.listener(Self::on_export)),
+                            ),
+                    )
+                    .child(Input::new(&self.search_input).small()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scrollbar()
+                    .when(items.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_2()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(ts("library-empty")),
+                        )
+                    })
+                    .children(items),
+            )
+    }
+}
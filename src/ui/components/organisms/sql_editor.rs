@@ -1,15 +1,44 @@
-use gpui::{App, AppContext, Entity, IntoElement, Render, SharedString, Styled, Window};
-use gpui_component::input::{Input, InputState};
+use std::{ops::Range, rc::Rc, time::Duration};
 
-use crate::database::AnySharedDatabase;
+use gpui::{
+    App, AppContext, Context, Entity, EntityInputHandler, IntoElement, ParentElement, Render,
+    SharedString, Styled, Task, Window, div, prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, StyledExt,
+    input::{Input, InputEvent, InputState},
+};
+use lsp_types::{Diagnostic, DiagnosticSeverity, Documentation, ParameterLabel, SignatureHelp};
+
+use crate::database::{AnySharedDatabase, DatabaseSchema};
 use crate::lsp::SqlLsp;
 use crate::lsp::create_sql_lsp;
+use crate::settings::AppSettings;
+use crate::state::DatabaseSession;
 use crate::state::async_resource::AsyncResource;
+use crate::utils::sql_format::{
+    byte_to_utf16_offset, format_sql, map_formatted_offset, text_position, utf16_to_byte_offset,
+};
+
+/// Delay after the last change before the diagnostics are updated
+const DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(500);
 
 /// Text editor using the SQL language
 pub struct SqlEditor {
     immutable: bool,
     pub input_state: Entity<InputState>,
+    /// Language server for the current database
+    lsp: Option<Rc<dyn SqlLsp>>,
+    /// Schema of the database used for completions
+    schema: Entity<AsyncResource<DatabaseSchema>>,
+    /// Pending update of the diagnostics
+    diagnostics_task: Option<Task<()>>,
+    /// Diagnostics from the language server
+    diagnostics: Vec<Diagnostic>,
+    /// Error from executing a query, shown until the text is changed
+    query_error: Option<Diagnostic>,
+    /// Signature of the function call being typed
+    signature_help: Option<SignatureHelp>,
 }
 
 impl SqlEditor {
@@ -19,51 +48,366 @@ impl SqlEditor {
         default_value: SharedString,
         immutable: bool,
         submit_on_enter: bool,
-        database: Entity<AsyncResource<AnySharedDatabase>>,
+        session: &DatabaseSession,
     ) -> Entity<Self> {
+        let database = session.database(cx);
+        let schema = session.database_schema(cx);
+
         cx.new(|cx| {
-            cx.observe(&database, move |this: &mut SqlEditor, database, cx| {
-                let lsp = match database.read(cx) {
-                    AsyncResource::Loaded(database) => {
-                        let lsp = match create_sql_lsp(database.clone()) {
-                            Ok(value) => value,
-                            Err(error) => {
-                                tracing::error!(?error, "failed to create lsp");
-                                return;
-                            }
-                        };
-
-                        Some(lsp)
+            cx.observe(&database, |this: &mut SqlEditor, database, cx| {
+                this.update_lsp(&database, cx);
+            })
+            .detach();
+
+            // Keep the completions up to date with changes to the schema
+            cx.observe(&schema, |this: &mut SqlEditor, _, cx| {
+                this.update_schema(cx);
+            })
+            .detach();
+
+            let input_state = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .code_editor("sql")
+                    .multi_line(true)
+                    .soft_wrap(true)
+                    .rows(6)
+                    .default_value(default_value)
+                    .submit_on_enter(submit_on_enter)
+            });
+
+            cx.subscribe(&input_state, |this: &mut SqlEditor, _, event, cx| {
+                if matches!(event, InputEvent::Change { .. }) {
+                    // The location of the error no longer matches the text
+                    if this.query_error.take().is_some() {
+                        this.show_diagnostics(cx);
                     }
-                    _ => None,
-                };
 
-                this.input_state.update(cx, move |this, _cx| {
-                    this.lsp.completion_provider = lsp.map(SqlLsp::into_completion_provider);
-                });
+                    this.update_signature_help(cx);
+                    this.update_diagnostics(cx);
+                }
             })
             .detach();
 
-            SqlEditor {
-                input_state: cx.new(|cx| {
-                    InputState::new(window, cx)
-                        .code_editor("sql")
-                        .multi_line(true)
-                        .soft_wrap(true)
-                        .rows(6)
-                        .default_value(default_value)
-                        .submit_on_enter(submit_on_enter)
-                }),
+            let mut editor = SqlEditor {
+                input_state,
                 immutable,
+                lsp: None,
+                schema,
+                diagnostics_task: None,
+                diagnostics: Vec::new(),
+                query_error: None,
+                signature_help: None,
+            };
+
+            editor.update_lsp(&database, cx);
+            editor
+        })
+    }
+
+    /// Create the language server for the current `database`
+    fn update_lsp(
+        &mut self,
+        database: &Entity<AsyncResource<AnySharedDatabase>>,
+        cx: &mut Context<Self>,
+    ) {
+        self.lsp = match database.read(cx) {
+            AsyncResource::Loaded(database) => match create_sql_lsp(database.clone()) {
+                Ok(value) => Some(value),
+                Err(error) => {
+                    tracing::error!(?error, "failed to create lsp");
+                    return;
+                }
+            },
+            _ => None,
+        };
+
+        let completion_provider = self.lsp.clone().map(SqlLsp::into_completion_provider);
+        let hover_provider = self.lsp.clone().map(SqlLsp::into_hover_provider);
+        self.input_state.update(cx, move |this, _cx| {
+            this.lsp.completion_provider = completion_provider;
+            this.lsp.hover_provider = hover_provider;
+        });
+
+        self.update_schema(cx);
+    }
+
+    /// Provide the language server with the latest loaded schema
+    fn update_schema(&mut self, cx: &mut Context<Self>) {
+        let Some(lsp) = &self.lsp else {
+            return;
+        };
+
+        if let AsyncResource::Loaded(schema) = self.schema.read(cx) {
+            lsp.set_schema(schema.clone());
+        }
+
+        // Objects referenced by the text may have been created or dropped
+        self.update_diagnostics(cx);
+    }
+
+    /// Update the signature help for the function call at the cursor
+    fn update_signature_help(&mut self, cx: &mut Context<Self>) {
+        let signature_help = self.lsp.as_ref().and_then(|lsp| {
+            let input_state = self.input_state.read(cx);
+            lsp.signature_help(&input_state.value(), input_state.cursor())
+        });
+
+        self.signature_help = signature_help;
+        cx.notify();
+    }
+
+    fn render_signature_help(signature_help: &SignatureHelp, cx: &App) -> impl IntoElement {
+        let signature = signature_help.signatures.first();
+        let label = signature
+            .map(|signature| signature.label.as_str())
+            .unwrap_or_default();
+
+        // Split the label around the active parameter to highlight it
+        let active = signature.and_then(|signature| {
+            let index = signature.active_parameter? as usize;
+            match signature.parameters.as_ref()?.get(index)?.label {
+                ParameterLabel::LabelOffsets([start, end]) => Some(start as usize..end as usize),
+                ParameterLabel::Simple(_) => None,
+            }
+        });
+        let (before, parameter, after) = match active {
+            Some(range) if label.get(range.clone()).is_some() => (
+                &label[..range.start],
+                &label[range.clone()],
+                &label[range.end..],
+            ),
+            _ => (label, "", ""),
+        };
+
+        let description = signature.and_then(|signature| match &signature.documentation {
+            Some(Documentation::String(value)) => Some(value.clone()),
+            _ => None,
+        });
+
+        div()
+            .h_flex()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(
+                div()
+                    .h_flex()
+                    .child(before.to_string())
+                    .child(
+                        div()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child(parameter.to_string()),
+                    )
+                    .child(after.to_string()),
+            )
+            .when_some(description, |this, description| this.child(description))
+    }
+
+    /// Byte range of the selection, or the statement containing the cursor
+    /// when nothing is selected
+    pub fn current_statement_range(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Range<usize> {
+        let lsp = self.lsp.clone();
+
+        self.input_state.update(cx, |state, cx| {
+            let text = state.value();
+            let selection = state.selected_text_range(false, window, cx);
+
+            if let Some(selection) = selection.filter(|selection| !selection.range.is_empty()) {
+                return utf16_to_byte_offset(&text, selection.range.start)
+                    ..utf16_to_byte_offset(&text, selection.range.end);
             }
+
+            lsp.and_then(|lsp| lsp.statement_range(&text, state.cursor()))
+                // Without a parsed statement fallback to the whole text
+                .unwrap_or(0..text.len())
         })
     }
+
+    /// Byte ranges of the statements within the editor text, the whole text
+    /// when it can't be parsed
+    pub fn statement_ranges(&self, cx: &App) -> Vec<Range<usize>> {
+        let text = self.input_state.read(cx).value();
+
+        match &self.lsp {
+            Some(lsp) => lsp.statement_ranges(&text),
+            None => vec![0..text.len()],
+        }
+    }
+
+    /// Move the cursor to the byte `offset` of an error and underline the
+    /// token at the offset with the error `message`
+    pub fn show_error(
+        &mut self,
+        offset: usize,
+        message: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let query_error = self.input_state.update(cx, |state, cx| {
+            let text = state.value();
+            let mut offset = offset.min(text.len());
+            while !text.is_char_boundary(offset) {
+                offset -= 1;
+            }
+
+            let token_length: usize = text[offset..]
+                .chars()
+                .take_while(|char| char.is_alphanumeric() || *char == '_')
+                .map(char::len_utf8)
+                .sum();
+            let end = match token_length {
+                0 => text[offset..]
+                    .chars()
+                    .next()
+                    .map(|char| offset + char.len_utf8())
+                    .unwrap_or(offset),
+                length => offset + length,
+            };
+
+            let diagnostic = Diagnostic {
+                range: lsp_types::Range {
+                    start: text_position(&text, offset),
+                    end: text_position(&text, end),
+                },
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("sqlite".to_string()),
+                message,
+                ..Default::default()
+            };
+
+            state.set_cursor_position(text_position(&text, offset), window, cx);
+            state.focus(window, cx);
+
+            diagnostic
+        });
+
+        self.query_error = Some(query_error);
+        self.show_diagnostics(cx);
+    }
+
+    /// Show the language server diagnostics along with the query error
+    fn show_diagnostics(&mut self, cx: &mut Context<Self>) {
+        let diagnostics: Vec<Diagnostic> = self
+            .diagnostics
+            .iter()
+            .chain(&self.query_error)
+            .cloned()
+            .collect();
+
+        self.input_state.update(cx, |state, cx| {
+            if let Some(set) = state.diagnostics_mut() {
+                set.clear();
+                set.extend(diagnostics);
+            }
+
+            cx.notify();
+        });
+    }
+
+    /// Format the SQL using the formatter settings, when `selection_only` is
+    /// set only the selected text is formatted
+    pub fn format(&mut self, selection_only: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if self.immutable {
+            return;
+        }
+
+        let settings = cx.global::<AppSettings>().formatter.clone();
+
+        self.input_state.update(cx, |state, cx| {
+            let text = state.value().to_string();
+            let Some(selection) = state.selected_text_range(false, window, cx) else {
+                return;
+            };
+
+            let selected = utf16_to_byte_offset(&text, selection.range.start)
+                ..utf16_to_byte_offset(&text, selection.range.end);
+            let cursor = match selection.reversed {
+                true => selected.start,
+                false => selected.end,
+            };
+
+            let range = match selection_only {
+                true if selected.is_empty() => return,
+                true => selected,
+                false => 0..text.len(),
+            };
+
+            let original = &text[range.clone()];
+            let formatted = format_sql(original, &settings);
+            if formatted == original {
+                return;
+            }
+
+            let cursor = range.start
+                + map_formatted_offset(
+                    original,
+                    &formatted,
+                    cursor.clamp(range.start, range.end) - range.start,
+                );
+
+            // Replacing the range keeps the formatting as a single undoable edit
+            let utf16_range =
+                byte_to_utf16_offset(&text, range.start)..byte_to_utf16_offset(&text, range.end);
+            state.replace_text_in_range(Some(utf16_range), &formatted, window, cx);
+
+            let position = text_position(&state.value(), cursor);
+            state.set_cursor_position(position, window, cx);
+        });
+    }
+
+    /// Update the diagnostics once the text hasn't changed for a moment
+    fn update_diagnostics(&mut self, cx: &mut Context<Self>) {
+        // Immutable editors only display existing SQL
+        if self.immutable {
+            return;
+        }
+
+        let Some(lsp) = self.lsp.clone() else {
+            self.diagnostics_task = None;
+            return;
+        };
+
+        self.diagnostics_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(DIAGNOSTICS_DEBOUNCE).await;
+
+            let task = this.update(cx, |this, cx| {
+                let text = this.input_state.read(cx).value();
+                lsp.diagnostics(&text, cx)
+            });
+
+            let Ok(task) = task else {
+                return;
+            };
+
+            let diagnostics = task.await;
+
+            _ = this.update(cx, |this, cx| {
+                this.diagnostics = diagnostics;
+                this.show_diagnostics(cx);
+            });
+        }));
+    }
 }
 
 impl Render for SqlEditor {
-    fn render(&mut self, _window: &mut Window, _cx: &mut gpui::Context<Self>) -> impl IntoElement {
-        Input::new(&self.input_state)
+    fn render(&mut self, _window: &mut Window, cx: &mut gpui::Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
             .size_full()
-            .disabled(self.immutable)
+            .child(
+                Input::new(&self.input_state)
+                    .size_full()
+                    .disabled(self.immutable),
+            )
+            .when_some(self.signature_help.as_ref(), |this, signature_help| {
+                this.child(Self::render_signature_help(signature_help, cx))
+            })
     }
 }
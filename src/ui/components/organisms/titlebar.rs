@@ -6,16 +6,27 @@ use gpui_component::{
     IconName, Sizable, TitleBar,
     button::{Button, ButtonVariants},
     label::Label,
-    menu::DropdownMenu,
+    menu::{DropdownMenu, PopupMenu},
 };
 
-use crate::ui::{
-    actions::{
-        new_database::NewDatabase, new_memory_database::NewMemoryDatabase,
-        open_encrypted_database::OpenFileEncrypted, open_file::OpenFile,
-    },
-    components::{
-        atoms::i18n::translated::ts, molecules::database_status_label::DatabaseStatusLabel,
+use crate::{
+    recent::RecentDatabases,
+    state::WindowSessions,
+    ui::{
+        actions::{
+            export_sql_dump::ExportSqlDump,
+            import_csv::ImportCsv,
+            import_sql::ImportSql,
+            new_database::NewDatabase,
+            new_memory_database::NewMemoryDatabase,
+            open_encrypted_database::OpenFileEncrypted,
+            open_file::OpenFile,
+            open_recent_database::{OpenRecentDatabase, RemoveMissingRecentDatabases},
+        },
+        components::{
+            atoms::i18n::translated::ts, molecules::database_status_label::DatabaseStatusLabel,
+        },
+        views::settings::open_settings_dialog,
     },
 };
 
@@ -24,15 +35,38 @@ pub struct AppTitleBar {
 }
 
 impl AppTitleBar {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(
+        sessions: Entity<WindowSessions>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
         cx.new(|cx| {
-            let label = DatabaseStatusLabel::new(window, cx);
+            let label = DatabaseStatusLabel::new(sessions, window, cx);
 
             AppTitleBar { label }
         })
     }
 }
 
+/// Submenu listing the recent databases to reopen
+fn recent_menu(menu: PopupMenu, _window: &mut Window, cx: &mut Context<PopupMenu>) -> PopupMenu {
+    let databases = cx.global::<RecentDatabases>().databases.clone();
+
+    let menu = databases.into_iter().fold(menu, |menu, database| {
+        menu.menu(
+            database.path.clone(),
+            Box::new(OpenRecentDatabase {
+                path: database.path,
+            }),
+        )
+    });
+
+    menu.separator().menu(
+        ts("recent-remove-missing"),
+        Box::new(RemoveMissingRecentDatabases),
+    )
+}
+
 impl Render for AppTitleBar {
     fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
         TitleBar::new()
@@ -48,14 +82,17 @@ impl Render for AppTitleBar {
                             .ghost()
                             .label(ts("file"))
                             .xsmall()
-                            .dropdown_menu(|menu, _, _| {
+                            .dropdown_menu(|menu, window, cx| {
                                 menu.menu(ts("new-database"), Box::new(NewDatabase))
                                     .menu(ts("new-in-memory-database"), Box::new(NewMemoryDatabase))
                                     .separator()
                                     .menu(ts("open-database"), Box::new(OpenFile::default()))
                                     .menu(
                                         ts("open-read-only-database"),
-                                        Box::new(OpenFile { read_only: true }),
+                                        Box::new(OpenFile {
+                                            read_only: true,
+                                            new_window: false,
+                                        }),
                                     )
                                     .menu(
                                         ts("open-encrypted-database"),
@@ -65,6 +102,18 @@ impl Render for AppTitleBar {
                                         ts("open-read-only-encrypted-database"),
                                         Box::new(OpenFileEncrypted { read_only: true }),
                                     )
+                                    .menu(
+                                        ts("open-in-new-window"),
+                                        Box::new(OpenFile {
+                                            read_only: false,
+                                            new_window: true,
+                                        }),
+                                    )
+                                    .submenu(ts("open-recent"), window, cx, recent_menu)
+                                    .separator()
+                                    .menu(ts("import-csv"), Box::new(ImportCsv))
+                                    .menu(ts("import-sql"), Box::new(ImportSql))
+                                    .menu(ts("export-sql-dump"), Box::new(ExportSqlDump))
                             }),
                     )
                     .child(
@@ -94,11 +143,13 @@ impl Render for AppTitleBar {
                     .child(self.label.clone()),
             )
             .child(
-                div()
-                    .flex()
-                    .items_center()
-                    .gap_2()
-                    .child(Button::new("settings").icon(IconName::Settings).ghost()),
+                div().flex().items_center().gap_2().child(
+                    Button::new("settings")
+                        .icon(IconName::Settings)
+                        .ghost()
+                        .tooltip(ts("settings"))
+                        .on_click(|_, window, cx| open_settings_dialog(window, cx)),
+                ),
             )
     }
 }
@@ -9,8 +9,8 @@ use gpui_component::{
 };
 
 use crate::{
-    database::DatabaseTable,
-    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    database::{AnySharedDatabase, DatabaseTable},
+    state::{DatabaseSession, async_resource::AsyncResource},
     ui::components::{
         atoms::{i18n::translated::ts, icons::CustomIconName},
         organisms::database::table_browser::{
@@ -20,6 +20,9 @@ use crate::{
 };
 
 pub struct DatabaseBrowseTableView {
+    /// Database the tables belong to
+    database: Entity<AsyncResource<AnySharedDatabase>>,
+
     /// Currently loaded set of database tables
     tables: Entity<AsyncResource<Vec<DatabaseTable>>>,
 
@@ -34,9 +37,9 @@ pub struct DatabaseBrowseTableView {
 }
 
 impl DatabaseBrowseTableView {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| {
-            let tables = cx.database_tables();
+            let tables = session.database_tables(cx);
 
             let toolbar = DatabaseBrowseDataViewToolbar::new(window, cx);
 
@@ -52,6 +55,7 @@ impl DatabaseBrowseTableView {
             );
 
             Self {
+                database: session.database(cx),
                 tables,
                 toolbar,
                 browser: None,
@@ -79,7 +83,8 @@ impl DatabaseBrowseTableView {
             .toolbar
             .update(cx, |this, cx| this.update_tables(tables, window, cx));
 
-        self.browser = table.map(|table| DatabaseTableBrowser::new(table, window, cx));
+        self.browser =
+            table.map(|table| DatabaseTableBrowser::new(self.database.clone(), table, window, cx));
     }
 
     /// Handles changes to the currently selected table
@@ -94,7 +99,7 @@ impl DatabaseBrowseTableView {
 
         self.browser = table
             .clone()
-            .map(|table| DatabaseTableBrowser::new(table, window, cx));
+            .map(|table| DatabaseTableBrowser::new(self.database.clone(), table, window, cx));
     }
 }
 
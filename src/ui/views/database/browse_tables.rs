@@ -1,6 +1,6 @@
 use crate::{
     database::DatabaseTable,
-    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    state::{DatabaseSession, async_resource::AsyncResource},
     ui::components::{
         atoms::i18n::translated::ts,
         organisms::database::tables_browser::tables_table_tree::DatabaseTablesTreeView,
@@ -17,11 +17,11 @@ pub struct DatabaseBrowseTablesView {
 }
 
 impl DatabaseBrowseTablesView {
-    pub fn new(cx: &mut App) -> Entity<Self> {
-        let tree = DatabaseTablesTreeView::new(cx);
+    pub fn new(session: DatabaseSession, cx: &mut App) -> Entity<Self> {
+        let tables = session.database_tables(cx);
+        let tree = DatabaseTablesTreeView::new(session, cx);
 
         cx.new(|cx| {
-            let tables = cx.database_tables();
             let tables_subscription = cx.observe(&tables, Self::on_tables_changed);
 
             Self {
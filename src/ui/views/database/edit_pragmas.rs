@@ -16,13 +16,17 @@ use parking_lot::Mutex;
 
 use crate::{
     database::{
-        AnySharedDatabase, PragmaDefinition, PragmaType, sqlite::pragma::PRAGMA_DEFINITIONS,
+        AnySharedDatabase, DatabaseValue, PragmaDefinition, PragmaType,
+        sqlite::pragma::PRAGMA_DEFINITIONS,
     },
-    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    state::{DatabaseSession, async_resource::AsyncResource},
     ui::components::atoms::icons::CustomIconName,
 };
 
 pub struct EditPragmasView {
+    /// Session of the database the pragmas are edited for
+    session: DatabaseSession,
+
     /// States for each of the pragma values, shared as the values
     /// must be accessible from a database task which updates the
     /// current values
@@ -53,7 +57,7 @@ enum PragmaState {
 }
 
 impl EditPragmasView {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| {
             let mut states = HashMap::new();
 
@@ -91,12 +95,12 @@ impl EditPragmasView {
             }
 
             let states = Rc::new(Mutex::new(states));
-            let database = cx.database();
+            let database = session.database(cx);
 
             cx.observe_in(&database, window, Self::on_database_change)
                 .detach();
 
-            Self { states }
+            Self { session, states }
         })
     }
 
@@ -149,8 +153,9 @@ impl EditPragmasView {
             }
         };
 
-        let value = match value.rows.pop().and_then(|mut row| row.values.pop()) {
-            Some(value) => value,
+        let value: SharedString = match value.rows.pop().and_then(|mut row| row.values.pop()) {
+            Some(DatabaseValue::Text(value)) => value.into(),
+            Some(value) => value.to_string().into(),
             None => {
                 return;
             }
@@ -226,7 +231,7 @@ impl EditPragmasView {
         window: &mut Window,
         cx: &mut Context<EditPragmasView>,
     ) {
-        let database = match cx.database_connection() {
+        let database = match self.session.database_connection(cx) {
             Some(value) => value,
             _ => return,
         };
@@ -1,6 +1,6 @@
 use crate::{
     database::AnySharedDatabase,
-    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    state::{DatabaseSession, async_resource::AsyncResource},
     ui::{
         components::atoms::{
             i18n::translated::{t, ts},
@@ -8,7 +8,7 @@ use crate::{
         },
         views::database::{
             browse_table::DatabaseBrowseTableView, browse_tables::DatabaseBrowseTablesView,
-            edit_pragmas::EditPragmasView, query_executor::DatabaseQueryExecutor,
+            edit_pragmas::EditPragmasView, query_tabs::QueryTabsView,
         },
     },
 };
@@ -25,27 +25,28 @@ mod browse_table;
 mod browse_tables;
 mod edit_pragmas;
 mod query_executor;
+mod query_tabs;
 
 pub struct DatabaseView {
     active_tab: usize,
     tables_view: Entity<DatabaseBrowseTablesView>,
-    executor: Entity<DatabaseQueryExecutor>,
+    executor: Entity<QueryTabsView>,
     browse_view: Entity<DatabaseBrowseTableView>,
     database: Entity<AsyncResource<AnySharedDatabase>>,
     pragmas_view: Entity<EditPragmasView>,
 }
 
 impl DatabaseView {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
         cx.new(|cx| {
-            let database = cx.database();
+            let database = session.database(cx);
 
             DatabaseView {
                 active_tab: 0,
-                tables_view: DatabaseBrowseTablesView::new(cx),
-                executor: DatabaseQueryExecutor::new(window, cx),
-                browse_view: DatabaseBrowseTableView::new(window, cx),
-                pragmas_view: EditPragmasView::new(window, cx),
+                tables_view: DatabaseBrowseTablesView::new(session.clone(), cx),
+                executor: QueryTabsView::new(session.clone(), window, cx),
+                browse_view: DatabaseBrowseTableView::new(session.clone(), window, cx),
+                pragmas_view: EditPragmasView::new(session, window, cx),
                 database,
             }
         })
@@ -1,18 +1,35 @@
 use crate::{
-    database::{AnySharedDatabase, DatabaseQueryResult, DatabaseRow},
+    database::{AnySharedDatabase, DatabaseQueryError, DatabaseQueryResult, DatabaseRow},
+    history::{QueryHistoryEntry, record_query},
+    import::sql::skip_leading_comments,
     state::{
+        DatabaseSession,
         async_resource::{AsyncResource, AsyncResourceEntityExt},
-        database::{DatabaseResourceExt, connection::QueryExecutedEvent},
+        database::connection::QueryExecutedEvent,
+    },
+    ui::{
+        components::{
+            atoms::i18n::translated::{ts, ts_args},
+            organisms::{
+                query_history_panel::{QueryHistoryEvent, QueryHistoryPanel},
+                saved_queries_panel::{SavedQueriesEvent, SavedQueriesPanel},
+                sql_editor::SqlEditor,
+            },
+        },
+        views::{
+            export::{ExportSource, open_export_dialog},
+            formatter_settings::open_formatter_settings_dialog,
+            save_query::open_save_query_dialog,
+        },
     },
-    ui::components::{atoms::i18n::translated::ts, organisms::sql_editor::SqlEditor},
 };
 use anyhow::Context as AnyhowContext;
 use gpui::{
     App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
-    Styled, Subscription, Window, div,
+    Styled, Subscription, Window, div, prelude::FluentBuilder, px,
 };
 use gpui_component::{
-    Sizable, StyledExt,
+    ActiveTheme, Selectable, Sizable, StyledExt,
     alert::Alert,
     button::Button,
     input::{InputEvent, InputState},
@@ -20,9 +37,15 @@ use gpui_component::{
     spinner::Spinner,
     table::{Column, DataTable, TableDelegate, TableState},
 };
-use sqlformat::FormatOptions;
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
 
 pub struct DatabaseQueryExecutor {
+    /// Session of the database queries are executed against
+    session: DatabaseSession,
+
     /// Query results
     results: Entity<AsyncResource<DatabaseQueryResult>>,
 
@@ -32,7 +55,23 @@ pub struct DatabaseQueryExecutor {
     // SQL Editor state
     editor: Entity<SqlEditor>,
 
-    _subscriptions: (Subscription, Subscription, Subscription),
+    /// Query that produced the current results
+    executed_query: SharedString,
+
+    /// Panel listing the previously executed queries
+    history_panel: Entity<QueryHistoryPanel>,
+    /// Panel listing the queries saved within the library
+    saved_queries_panel: Entity<SavedQueriesPanel>,
+    /// Panel currently shown beside the editor
+    side_panel: Option<SidePanel>,
+
+    _subscriptions: Vec<Subscription>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SidePanel {
+    History,
+    SavedQueries,
 }
 
 struct ResultsTableDelegate {
@@ -71,45 +110,89 @@ impl TableDelegate for ResultsTableDelegate {
     ) -> impl IntoElement {
         let row = &self.rows[row_ix];
         let value = row.values.get(col_ix);
-        value.cloned().unwrap_or_default()
+        value
+            .map(|value| SharedString::from(value.to_string()))
+            .unwrap_or_default()
     }
 }
 
 impl DatabaseQueryExecutor {
-    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+    pub fn new(
+        session: DatabaseSession,
+        text: SharedString,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
         let table_delegate = ResultsTableDelegate::new();
         let table_state = cx.new(|cx| TableState::new(table_delegate, window, cx));
-        let database = cx.database();
 
-        let editor = SqlEditor::new(window, cx, "".into(), false, true, database);
+        let editor = SqlEditor::new(window, cx, text, false, true, &session);
+        let history_panel = QueryHistoryPanel::new(session.clone(), window, cx);
+        let saved_queries_panel = SavedQueriesPanel::new(session.clone(), window, cx);
 
         cx.new(|cx| {
             let results: Entity<AsyncResource<DatabaseQueryResult>> = AsyncResource::new(cx);
-            let database = cx.database();
+            let database = session.database(cx);
 
             let tables_subscription = cx.observe(&database, Self::on_tables_changed);
 
             // Observe results changes to update the database table
             let results_subscription = cx.observe(&results, Self::on_results_changed);
 
-            // Handle CTRL + Enter to run the query
+            // Handle CTRL + Enter to run the current statement and CTRL + SHIFT + Enter to run
+            // the whole query
             let editor_input_state = editor.read(cx).input_state.clone();
             let editor_input_subscription =
                 cx.subscribe_in(&editor_input_state, window, Self::on_editor_input);
 
+            let history_subscription =
+                cx.subscribe_in(&history_panel, window, Self::on_history_event);
+            let saved_queries_subscription =
+                cx.subscribe_in(&saved_queries_panel, window, Self::on_saved_queries_event);
+
             Self {
+                session,
                 results,
                 table_state,
                 editor,
-                _subscriptions: (
+                executed_query: SharedString::default(),
+                history_panel,
+                saved_queries_panel,
+                side_panel: None,
+                _subscriptions: vec![
                     tables_subscription,
                     results_subscription,
                     editor_input_subscription,
-                ),
+                    history_subscription,
+                    saved_queries_subscription,
+                ],
             }
         })
     }
 
+    /// State of the editor input, for observing changes to the query text
+    pub fn editor_input_state(&self, cx: &App) -> Entity<InputState> {
+        self.editor.read(cx).input_state.clone()
+    }
+
+    /// Current text within the query editor
+    pub fn query_text(&self, cx: &App) -> String {
+        self.editor
+            .read(cx)
+            .input_state
+            .read(cx)
+            .value()
+            .to_string()
+    }
+
+    /// Replace the text within the query editor with `text`
+    pub fn set_query_text(this: &Entity<Self>, text: String, window: &mut Window, cx: &mut App) {
+        let input_state = this.read(cx).editor_input_state(cx);
+        input_state.update(cx, |state, cx| {
+            state.set_value(text, window, cx);
+        });
+    }
+
     /// Handles changes to the database connection
     fn on_tables_changed(
         &mut self,
@@ -142,8 +225,10 @@ impl DatabaseQueryExecutor {
         cx: &mut Context<Self>,
     ) {
         if let InputEvent::PressEnter { secondary, shift } = event {
-            if *secondary {
-                self.perform_current_query(cx);
+            if *secondary && *shift {
+                self.perform_current_query(window, cx);
+            } else if *secondary {
+                self.perform_current_statement(window, cx);
             }
             // Since we enable the submit on enter behavior in order to make CTRL + ENTER not
             // make a new line we need to mick the new line behavior when shift isn't held
@@ -155,6 +240,54 @@ impl DatabaseQueryExecutor {
         }
     }
 
+    fn on_history_event(
+        &mut self,
+        _panel: &Entity<QueryHistoryPanel>,
+        event: &QueryHistoryEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            QueryHistoryEvent::Insert(query) => {
+                let input_state = self.editor.read(cx).input_state.clone();
+                input_state.update(cx, |state, cx| {
+                    state.insert(query.clone(), window, cx);
+                    state.focus(window, cx);
+                });
+            }
+            QueryHistoryEvent::Run(query) => {
+                let statements = vec![0..query.len()];
+                self.perform_query(query.clone().into(), statements, None, window, cx);
+            }
+        }
+    }
+
+    fn on_saved_queries_event(
+        &mut self,
+        _panel: &Entity<SavedQueriesPanel>,
+        event: &SavedQueriesEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            SavedQueriesEvent::Open(query) => {
+                let input_state = self.editor.read(cx).input_state.clone();
+                input_state.update(cx, |state, cx| {
+                    state.insert(query.clone(), window, cx);
+                    state.focus(window, cx);
+                });
+            }
+            SavedQueriesEvent::SaveCurrent => {
+                let query = self.editor.read(cx).input_state.read(cx).value();
+                if query.trim().is_empty() {
+                    return;
+                }
+
+                open_save_query_dialog(query.to_string(), window, cx);
+            }
+        }
+    }
+
     fn update_result_rows(
         &mut self,
         rows: Vec<DatabaseRow>,
@@ -175,50 +308,192 @@ impl DatabaseQueryExecutor {
         });
     }
 
-    fn perform_current_query(&mut self, cx: &mut Context<'_, Self>) {
+    /// Run every statement within the editor in order
+    fn perform_current_query(&mut self, window: &mut Window, cx: &mut Context<'_, Self>) {
         let editor = self.editor.read(cx);
         let query = editor.input_state.read(cx).value();
-        self.perform_query(cx, query);
+        let statements = editor.statement_ranges(cx);
+        self.perform_query(query, statements, Some(0), window, cx);
+    }
+
+    /// Run the selected text or the statement containing the cursor
+    fn perform_current_statement(&mut self, window: &mut Window, cx: &mut Context<'_, Self>) {
+        let range = self
+            .editor
+            .update(cx, |editor, cx| editor.current_statement_range(window, cx));
+
+        let text = self.editor.read(cx).input_state.read(cx).value();
+        let Some(query) = text.get(range.clone()) else {
+            return;
+        };
+
+        if query.trim().is_empty() {
+            return;
+        }
+
+        let statements = vec![0..query.len()];
+        self.perform_query(
+            query.to_string().into(),
+            statements,
+            Some(range.start),
+            window,
+            cx,
+        );
     }
 
-    fn perform_query(&mut self, cx: &mut Context<'_, Self>, query: SharedString) {
-        let database_entity = cx.database_connection_resource();
-        let database = match cx.database_connection() {
+    /// Execute the `statements` (byte ranges) of the `query` in order,
+    /// stopping at the first error and showing the result of the last
+    ///
+    /// The `query` starts at the byte `editor_offset` within the editor text
+    /// when it came from the editor
+    fn perform_query(
+        &mut self,
+        query: SharedString,
+        statements: Vec<Range<usize>>,
+        editor_offset: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<'_, Self>,
+    ) {
+        if statements.is_empty() {
+            return;
+        }
+
+        let database_entity = self.session.connection.clone();
+        let database = match self.session.database_connection(cx) {
             Some(value) => value,
             None => return,
         };
 
-        // Notify our listeners that we are executing a query
-        database_entity.update(cx, |_, cx| {
-            cx.emit(QueryExecutedEvent {
-                query: query.clone(),
-            });
-        });
+        let this = cx.weak_entity();
+        let mut async_window = window.to_async(cx);
 
         self.results.load(cx, async move || {
-            database
-                .query(query.as_ref())
-                .await
-                .context("failed to execute query")
+            let mut last_result = None;
+            let mut first_error = None;
+
+            for range in statements {
+                let statement = &query[range.clone()];
+                let start = Instant::now();
+                let result = database.query(statement).await;
+
+                let entry = QueryHistoryEntry::new(
+                    statement.to_string(),
+                    database.options().location(),
+                    start.elapsed(),
+                    &result,
+                );
+
+                _ = this.update_in(&mut async_window, |this, window, cx| {
+                    record_query(cx, entry);
+                    this.executed_query = statement.to_string().into();
+
+                    // Point the editor at the location of the error
+                    if let Err(error) = &result
+                        && let Some(query_error) = error.downcast_ref::<DatabaseQueryError>()
+                        && let (Some(editor_offset), Some(offset)) =
+                            (editor_offset, query_error.offset)
+                    {
+                        let message = query_error.message.clone();
+                        this.editor.update(cx, |editor, cx| {
+                            editor.show_error(
+                                editor_offset + range.start + offset,
+                                message,
+                                window,
+                                cx,
+                            );
+                        });
+                    }
+                });
+
+                match result {
+                    Ok(result) => last_result = Some(result),
+                    Err(error) => {
+                        first_error = Some(error.context("failed to execute query"));
+                        break;
+                    }
+                }
+            }
+
+            // Notify our listeners once the statements have finished, so that
+            // schema reloads see the changes made by them
+            _ = database_entity.update(&mut async_window, |_, cx| {
+                cx.emit(QueryExecutedEvent {
+                    query: query.clone(),
+                });
+            });
+
+            match first_error {
+                Some(error) => Err(error),
+                None => last_result.context("no statements to execute"),
+            }
         });
     }
 
-    fn on_executor(&mut self, _event: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
-        self.perform_current_query(cx);
+    fn on_executor(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        self.perform_current_query(window, cx);
     }
 
     fn on_format_sql(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
-        let editor = self.editor.read(cx);
-        let input_state = editor.input_state.clone();
-
-        let query = input_state.read(cx).value();
-        let options = FormatOptions::default();
-        let formatted = sqlformat::format(&query, &sqlformat::QueryParams::None, &options);
+        self.editor.update(cx, |editor, cx| {
+            editor.format(false, window, cx);
+        });
+    }
 
-        input_state.update(cx, move |this, cx| {
-            this.set_value(formatted, window, cx);
+    fn on_format_selection(
+        &mut self,
+        _event: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.editor.update(cx, |editor, cx| {
+            editor.format(true, window, cx);
         });
     }
+
+    fn on_formatter_settings(
+        &mut self,
+        _event: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        open_formatter_settings_dialog(window, cx);
+    }
+
+    /// Show the `panel` beside the editor, hiding it when already shown
+    fn toggle_side_panel(&mut self, panel: SidePanel, cx: &mut Context<Self>) {
+        self.side_panel = match self.side_panel {
+            Some(current) if current == panel => None,
+            _ => Some(panel),
+        };
+        cx.notify();
+    }
+
+    fn on_toggle_history(
+        &mut self,
+        _event: &ClickEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_side_panel(SidePanel::History, cx);
+    }
+
+    fn on_toggle_saved_queries(
+        &mut self,
+        _event: &ClickEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_side_panel(SidePanel::SavedQueries, cx);
+    }
+
+    fn on_export(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let result = match self.results.read(cx) {
+            AsyncResource::Loaded(result) => result.clone(),
+            _ => return,
+        };
+
+        open_export_dialog(ExportSource::QueryResult(result), window, cx);
+    }
 }
 
 impl Render for DatabaseQueryExecutor {
@@ -244,9 +519,64 @@ impl Render for DatabaseQueryExecutor {
                                         .child(ts("format"))
                                         .small()
                                         .on_click(cx.listener(Self::on_format_sql)),
+                                )
+                                .child(
+                                    Button::new("format-selection")
+                                        .child(ts("format-selection"))
+                                        .small()
+                                        .on_click(cx.listener(Self::on_format_selection)),
+                                )
+                                .child(
+                                    Button::new("formatter-settings")
+                                        .child(ts("formatter-settings"))
+                                        .small()
+                                        .on_click(cx.listener(Self::on_formatter_settings)),
+                                )
+                                .child(
+                                    Button::new("history")
+                                        .child(ts("history"))
+                                        .small()
+                                        .selected(self.side_panel == Some(SidePanel::History))
+                                        .on_click(cx.listener(Self::on_toggle_history)),
+                                )
+                                .child(
+                                    Button::new("saved-queries")
+                                        .child(ts("library"))
+                                        .small()
+                                        .selected(self.side_panel == Some(SidePanel::SavedQueries))
+                                        .on_click(cx.listener(Self::on_toggle_saved_queries)),
+                                )
+                                .child(
+                                    Button::new("export")
+                                        .child(ts("export"))
+                                        .small()
+                                        .disabled(!matches!(
+                                            self.results.read(cx),
+                                            AsyncResource::Loaded(_)
+                                        ))
+                                        .on_click(cx.listener(Self::on_export)),
                                 ),
                         )
-                        .child(self.editor.clone())
+                        .child(
+                            div()
+                                .h_flex()
+                                .flex_1()
+                                .min_h_0()
+                                .w_full()
+                                .child(div().flex_1().h_full().child(self.editor.clone()))
+                                .when_some(self.side_panel, |this, panel| {
+                                    let panel = match panel {
+                                        SidePanel::History => {
+                                            self.history_panel.clone().into_any_element()
+                                        }
+                                        SidePanel::SavedQueries => {
+                                            self.saved_queries_panel.clone().into_any_element()
+                                        }
+                                    };
+
+                                    this.child(div().w(px(280.)).h_full().child(panel))
+                                }),
+                        )
                         .into_any_element(),
                 )
                 .child(
@@ -261,14 +591,37 @@ impl Render for DatabaseQueryExecutor {
                             .justify_center()
                             //
                             .child(Spinner::new()),
-                        AsyncResource::Loaded(_) => div()
+                        AsyncResource::Loaded(result) => div()
                             .size_full()
-                            //
+                            .v_flex()
+                            .child(match result.column_names.is_empty() {
+                                // Statements without columns (e.g. DDL) have nothing to show
+                                true => div()
+                                    .flex_1()
+                                    .p_3()
+                                    .text_sm()
+                                    .child(ts("query-no-rows"))
+                                    .into_any_element(),
+                                false => div()
+                                    .flex_1()
+                                    .min_h_0()
+                                    .child(
+                                        DataTable::new(&self.table_state)
+                                            .stripe(true)
+                                            .bordered(true)
+                                            .scrollbar_visible(true, true),
+                                    )
+                                    .into_any_element(),
+                            })
                             .child(
-                                DataTable::new(&self.table_state)
-                                    .stripe(true)
-                                    .bordered(true)
-                                    .scrollbar_visible(true, true),
+                                div()
+                                    .px_2()
+                                    .py_1()
+                                    .text_xs()
+                                    .border_t_1()
+                                    .border_color(cx.theme().border)
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(query_stats_text(&self.executed_query, result)),
                             ),
                         AsyncResource::Error(error) => div()
                             .p_3()
@@ -279,3 +632,138 @@ impl Render for DatabaseQueryExecutor {
         )
     }
 }
+
+/// Describe the outcome of executing the `query`, e.g. "UPDATE affected 42
+/// rows in 3.1 ms"
+fn query_stats_text(query: &str, result: &DatabaseQueryResult) -> SharedString {
+    let elapsed = format_elapsed(result.elapsed);
+
+    if result.readonly {
+        return ts_args(
+            "query-stats-returned",
+            &[
+                ("rows", result.rows.len().to_string()),
+                ("elapsed", elapsed),
+            ],
+        );
+    }
+
+    let verb = query_verb(query);
+    let modifies_rows = matches!(verb.as_str(), "INSERT" | "UPDATE" | "DELETE" | "REPLACE");
+
+    if !modifies_rows && result.changes == 0 {
+        return ts_args(
+            "query-stats-executed",
+            &[("verb", verb), ("elapsed", elapsed)],
+        );
+    }
+
+    let key = match matches!(verb.as_str(), "INSERT" | "REPLACE") && result.changes > 0 {
+        true => "query-stats-inserted",
+        false => "query-stats-affected",
+    };
+
+    ts_args(
+        key,
+        &[
+            ("verb", verb),
+            ("rows", result.changes.to_string()),
+            ("elapsed", elapsed),
+            ("rowid", result.last_insert_rowid.to_string()),
+        ],
+    )
+}
+
+/// Uppercase leading keyword of the `query` ignoring any comments before it,
+/// for WITH queries the keyword of the statement after the CTEs
+fn query_verb(query: &str) -> String {
+    let query = skip_leading_comments(query);
+    let word = |text: &str| {
+        let end = text
+            .find(|char: char| !char.is_alphanumeric() && char != '_')
+            .unwrap_or(text.len());
+        text[..end].to_ascii_uppercase()
+    };
+
+    let first = word(query);
+    if first != "WITH" {
+        return first;
+    }
+
+    // Words outside of the parentheses of the CTE bodies, skipping strings
+    // and quoted identifiers
+    let mut depth = 0usize;
+    let mut rest = &query[first.len()..];
+
+    loop {
+        rest = skip_leading_comments(rest);
+        let Some(char) = rest.chars().next() else {
+            return first;
+        };
+
+        let length = match char {
+            '(' | ')' => {
+                depth = match char {
+                    '(' => depth + 1,
+                    _ => depth.saturating_sub(1),
+                };
+                1
+            }
+            '\'' | '"' | '`' | '[' => {
+                let close = if char == '[' { ']' } else { char };
+                rest[1..].find(close).map_or(rest.len(), |end| end + 2)
+            }
+            char if char.is_alphanumeric() || char == '_' => {
+                let word = word(rest);
+                let is_verb = matches!(
+                    word.as_str(),
+                    "SELECT" | "INSERT" | "UPDATE" | "DELETE" | "REPLACE" | "VALUES"
+                );
+
+                if depth == 0 && is_verb {
+                    return word;
+                }
+
+                word.len()
+            }
+            char => char.len_utf8(),
+        };
+
+        rest = &rest[length..];
+    }
+}
+
+/// Format the `elapsed` time in milliseconds, or seconds for slower queries
+fn format_elapsed(elapsed: Duration) -> String {
+    match elapsed.as_secs_f64() {
+        seconds if seconds >= 1.0 => format!("{seconds:.2} s"),
+        seconds => format!("{:.1} ms", seconds * 1000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::query_verb;
+
+    #[test]
+    fn test_query_verb() {
+        assert_eq!(query_verb("update t SET a = 1"), "UPDATE");
+        assert_eq!(
+            query_verb("-- comment\n/* block\ncomment */ DELETE FROM t"),
+            "DELETE"
+        );
+        assert_eq!(
+            query_verb(
+                "WITH recursive \"select\"(n) AS (SELECT 1 UNION SELECT n + 1 FROM \"select\")\n\
+                INSERT INTO t SELECT n FROM \"select\""
+            ),
+            "INSERT"
+        );
+        assert_eq!(
+            query_verb("WITH a AS (SELECT ')') UPDATE t SET x = 1"),
+            "UPDATE"
+        );
+        assert_eq!(query_verb("WITH"), "WITH");
+        assert_eq!(query_verb(""), "");
+    }
+}
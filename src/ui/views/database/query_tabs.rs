@@ -0,0 +1,825 @@
+//! [QueryTabsView] tab strip of query editors each with their own text,
+//! results and execution state, optionally associated with a SQL file
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use gpui::{
+    App, AppContext, Context, Entity, InteractiveElement, IntoElement, MouseButton, ParentElement,
+    PathPromptOptions, Render, SharedString, Styled, Subscription, Task, Window, div, px,
+};
+use gpui_component::{
+    IconName, Sizable, StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+    input::{Input, InputEvent, InputState},
+    notification::{Notification, NotificationType},
+    tab::{Tab, TabBar},
+};
+
+use crate::{
+    cli::take_startup_sql_file,
+    database::AnySharedDatabase,
+    keybindings::QUERY_TABS_CONTEXT,
+    query_tabs::{DatabaseQueryTabs, QueryTabContent, SavedQueryTabs, save_query_tabs},
+    state::{DatabaseSession, async_resource::AsyncResource},
+    ui::{
+        actions::sql_file::{OpenSqlFile, SaveSqlFile, SaveSqlFileAs},
+        components::atoms::i18n::translated::ts,
+        views::{
+            database::query_executor::DatabaseQueryExecutor,
+            unsaved_changes::open_unsaved_changes_dialog,
+        },
+    },
+};
+
+/// Delay after the last change before the tabs are saved
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// File name suggested when saving a tab without a file
+const DEFAULT_FILE_NAME: &str = "query.sql";
+
+struct QueryTab {
+    title: SharedString,
+    executor: Entity<DatabaseQueryExecutor>,
+    /// SQL file the tab is associated with
+    file: Option<QueryTabFile>,
+
+    /// Subscription to changes of the query text
+    _subscription: Subscription,
+}
+
+impl QueryTab {
+    /// Whether the text differs from the contents of the associated file,
+    /// or has any text when there is no file
+    fn is_dirty(&self, cx: &App) -> bool {
+        let text = self.executor.read(cx).query_text(cx);
+
+        match &self.file {
+            Some(file) => file.saved_text != text,
+            None => !text.trim().is_empty(),
+        }
+    }
+}
+
+struct QueryTabFile {
+    path: PathBuf,
+    /// Contents of the file when it was last opened or saved
+    saved_text: String,
+    /// Modification time of the file when it was last opened or saved
+    modified: Option<SystemTime>,
+}
+
+impl QueryTabFile {
+    /// Read the current state of the file at `path`, a file that can't be
+    /// read is treated as empty
+    fn read(path: PathBuf) -> Self {
+        let saved_text = fs::read_to_string(&path).unwrap_or_default();
+        let modified = file_modified(&path);
+
+        Self {
+            path,
+            saved_text,
+            modified,
+        }
+    }
+}
+
+/// Modification time of the file at `path`
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Name of the file at `path`
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+pub struct QueryTabsView {
+    /// Session of the database queries are executed against
+    session: DatabaseSession,
+
+    tabs: Vec<QueryTab>,
+    /// Index of the focused tab
+    active: usize,
+    /// Number used within the title of the next created tab
+    next_tab_number: usize,
+
+    /// Loaded database the tabs belong to
+    database: Option<AnySharedDatabase>,
+    /// Full path of the database the tabs are saved for, [None] when the
+    /// database can't be reopened
+    database_path: Option<String>,
+    /// Pending save of the tabs
+    save_task: Option<Task<()>>,
+
+    _subscriptions: (Subscription, Subscription),
+}
+
+impl QueryTabsView {
+    pub fn new(session: DatabaseSession, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let database = session.database(cx);
+            let database_subscription = cx.observe_in(&database, window, Self::on_database_changed);
+
+            // Files may be changed by other programs while the window isn't focused
+            let activation_subscription =
+                cx.observe_window_activation(window, |this: &mut Self, window, cx| {
+                    if window.is_window_active() {
+                        this.check_external_changes(window, cx);
+                    }
+                });
+
+            let mut this = Self {
+                session,
+                tabs: Vec::new(),
+                active: 0,
+                next_tab_number: 1,
+                database: None,
+                database_path: None,
+                save_task: None,
+                _subscriptions: (database_subscription, activation_subscription),
+            };
+
+            this.restore_tabs(&database, window, cx);
+
+            this
+        })
+    }
+
+    /// Handles changes to the database connection
+    fn on_database_changed(
+        &mut self,
+        database: Entity<AsyncResource<AnySharedDatabase>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.restore_tabs(&database, window, cx);
+    }
+
+    /// Restore the saved tabs of the loaded `database`, creating an empty
+    /// tab when there are no saved tabs
+    fn restore_tabs(
+        &mut self,
+        database: &Entity<AsyncResource<AnySharedDatabase>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let loaded = match database.read(cx) {
+            AsyncResource::Loaded(database) => Some(database.clone()),
+            _ => None,
+        };
+
+        if let Some(loaded) = loaded
+            && !self
+                .database
+                .as_ref()
+                .is_some_and(|database| Rc::ptr_eq(database, &loaded))
+        {
+            // Persist pending changes before the tabs of the previous database are dropped
+            if self.save_task.is_some() {
+                self.save(cx);
+            }
+
+            // Tabs of a previously open database don't carry over
+            self.tabs.clear();
+            self.active = 0;
+            self.next_tab_number = 1;
+
+            self.database_path = loaded.options().full_path;
+            self.database = Some(loaded);
+
+            let saved = self
+                .database_path
+                .as_ref()
+                .and_then(|path| cx.global::<SavedQueryTabs>().get(path))
+                .cloned();
+
+            if let Some(saved) = saved.filter(|saved| !saved.tabs.is_empty()) {
+                for tab in saved.tabs {
+                    let file = tab.path.map(|path| QueryTabFile::read(PathBuf::from(path)));
+                    self.push_tab(tab.title.into(), tab.text.into(), file, window, cx);
+                }
+
+                self.active = saved.active.min(self.tabs.len() - 1);
+            }
+
+            self.update_unsaved_changes(cx);
+            cx.notify();
+        }
+
+        if self.tabs.is_empty() {
+            self.new_tab(window, cx);
+        }
+
+        // Open the SQL file provided on the command line once the database loads
+        if self.database.is_some()
+            && let Some(path) = take_startup_sql_file(cx)
+        {
+            self.open_file(path, window, cx);
+        }
+    }
+
+    /// Add a tab with the provided `title` and query `text`
+    fn push_tab(
+        &mut self,
+        title: SharedString,
+        text: SharedString,
+        file: Option<QueryTabFile>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let executor = DatabaseQueryExecutor::new(self.session.clone(), text, window, cx);
+        let input_state = executor.read(cx).editor_input_state(cx);
+
+        let subscription = cx.subscribe_in(
+            &input_state,
+            window,
+            |this: &mut Self, _, event: &InputEvent, _window, cx| {
+                if matches!(event, InputEvent::Change { .. }) {
+                    this.schedule_save(cx);
+                    // Update the dirty state within the title
+                    this.update_unsaved_changes(cx);
+                    cx.notify();
+                }
+            },
+        );
+
+        self.tabs.push(QueryTab {
+            title,
+            executor,
+            file,
+            _subscription: subscription,
+        });
+        self.next_tab_number += 1;
+    }
+
+    /// Create an empty tab and focus it
+    fn new_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let title = format!("{} {}", ts("query-tab"), self.next_tab_number);
+        self.push_tab(title.into(), SharedString::default(), None, window, cx);
+        self.active = self.tabs.len() - 1;
+        self.save(cx);
+        cx.notify();
+    }
+
+    /// Close the tab at `index`, asking for confirmation when the tab has
+    /// unsaved changes
+    fn request_close_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.get(index) else {
+            return;
+        };
+
+        if !tab.is_dirty(cx) {
+            self.close_tab(index, window, cx);
+            return;
+        }
+
+        let message = format!("{} {}", ts("sql-file-unsaved"), tab.title);
+        let this = cx.weak_entity();
+
+        open_unsaved_changes_dialog(window, cx, message, move |window, cx| {
+            _ = this.update(cx, |this, cx| this.close_tab(index, window, cx));
+        });
+    }
+
+    /// Record whether any of the tabs has unsaved changes on the session
+    fn update_unsaved_changes(&self, cx: &App) {
+        let unsaved = self.tabs.iter().any(|tab| tab.is_dirty(cx));
+        self.session.unsaved_changes.set(unsaved);
+    }
+
+    /// Close the tab at `index`, an empty tab replaces the last tab
+    fn close_tab(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index >= self.tabs.len() {
+            return;
+        }
+
+        self.tabs.remove(index);
+
+        if self.active > index || self.active == self.tabs.len() {
+            self.active = self.active.saturating_sub(1);
+        }
+
+        match self.tabs.is_empty() {
+            true => self.new_tab(window, cx),
+            false => self.save(cx),
+        }
+
+        self.update_unsaved_changes(cx);
+        cx.notify();
+    }
+
+    /// Rename the tab at `index` to `title`
+    fn rename_tab(&mut self, index: usize, title: String, cx: &mut Context<Self>) {
+        let title = title.trim();
+        if title.is_empty() {
+            return;
+        }
+
+        if let Some(tab) = self.tabs.get_mut(index) {
+            tab.title = SharedString::from(title.to_string());
+            self.save(cx);
+            cx.notify();
+        }
+    }
+
+    /// Save the tabs once no changes have been made for a short time
+    fn schedule_save(&mut self, cx: &mut Context<Self>) {
+        if self.database_path.is_none() {
+            return;
+        }
+
+        self.save_task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            _ = this.update(cx, |this, cx| this.save(cx));
+        }));
+    }
+
+    /// Save the tabs for the database
+    fn save(&mut self, cx: &mut Context<Self>) {
+        self.save_task = None;
+
+        let Some(database_path) = self.database_path.clone() else {
+            return;
+        };
+
+        let tabs = DatabaseQueryTabs {
+            tabs: self
+                .tabs
+                .iter()
+                .map(|tab| QueryTabContent {
+                    title: tab.title.to_string(),
+                    text: tab.executor.read(cx).query_text(cx),
+                    path: tab
+                        .file
+                        .as_ref()
+                        .map(|file| file.path.to_string_lossy().to_string()),
+                })
+                .collect(),
+            active: self.active,
+        };
+
+        save_query_tabs(cx, database_path, tabs);
+    }
+
+    /// Open the SQL file at `path`, replacing the focused tab when it is an
+    /// empty tab without a file
+    fn open_file(&mut self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let text = match fs::read_to_string(&path) {
+            Ok(value) => value,
+            Err(error) => {
+                tracing::error!(?error, "failed to read sql file");
+                window.push_notification(
+                    Notification::new()
+                        .message(format!("{}: {error}", ts("sql-file-open-failed")))
+                        .with_type(NotificationType::Error),
+                    cx,
+                );
+                return;
+            }
+        };
+
+        // Focus the tab that already has the file open
+        if let Some(index) = self
+            .tabs
+            .iter()
+            .position(|tab| tab.file.as_ref().is_some_and(|file| file.path == path))
+        {
+            self.active = index;
+            self.save(cx);
+            cx.notify();
+            return;
+        }
+
+        let title: SharedString = file_name(&path).into();
+        let file = QueryTabFile {
+            saved_text: text.clone(),
+            modified: file_modified(&path),
+            path,
+        };
+
+        let replace_active = self.tabs.get(self.active).is_some_and(|tab| {
+            tab.file.is_none() && tab.executor.read(cx).query_text(cx).trim().is_empty()
+        });
+
+        match replace_active {
+            true => {
+                let tab = &mut self.tabs[self.active];
+                tab.title = title;
+                tab.file = Some(file);
+                DatabaseQueryExecutor::set_query_text(&tab.executor, text, window, cx);
+            }
+            false => {
+                self.push_tab(title, text.into(), Some(file), window, cx);
+                self.active = self.tabs.len() - 1;
+            }
+        }
+
+        self.save(cx);
+        self.update_unsaved_changes(cx);
+        cx.notify();
+    }
+
+    /// Write the text of the tab at `index` to `path` associating the tab
+    /// with the file
+    fn write_file(
+        &mut self,
+        index: usize,
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.get_mut(index) else {
+            return;
+        };
+
+        let text = tab.executor.read(cx).query_text(cx);
+
+        if let Err(error) = fs::write(&path, &text) {
+            tracing::error!(?error, "failed to write sql file");
+            window.push_notification(
+                Notification::new()
+                    .message(format!("{}: {error}", ts("sql-file-save-failed")))
+                    .with_type(NotificationType::Error),
+                cx,
+            );
+            return;
+        }
+
+        let renamed = tab.file.as_ref().is_none_or(|file| file.path != path);
+        if renamed {
+            tab.title = file_name(&path).into();
+        }
+
+        tab.file = Some(QueryTabFile {
+            modified: file_modified(&path),
+            saved_text: text,
+            path,
+        });
+
+        self.save(cx);
+        self.update_unsaved_changes(cx);
+        cx.notify();
+    }
+
+    /// Check the files of the tabs for changes made by other programs,
+    /// reloading tabs without unsaved changes and asking whether to reload
+    /// or keep the text of tabs with unsaved changes
+    fn check_external_changes(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let mut conflict = None;
+
+        for (index, tab) in self.tabs.iter_mut().enumerate() {
+            let dirty = tab.is_dirty(cx);
+
+            let Some(file) = &mut tab.file else {
+                continue;
+            };
+
+            let modified = file_modified(&file.path);
+            if modified == file.modified {
+                continue;
+            }
+
+            let Ok(text) = fs::read_to_string(&file.path) else {
+                continue;
+            };
+
+            if text == file.saved_text {
+                file.modified = modified;
+                continue;
+            }
+
+            // Unsaved changes are kept until a choice is made
+            if dirty {
+                conflict.get_or_insert(index);
+                continue;
+            }
+
+            file.modified = modified;
+            file.saved_text = text.clone();
+            DatabaseQueryExecutor::set_query_text(&tab.executor, text, window, cx);
+
+            window.push_notification(
+                Notification::new()
+                    .message(format!("{} {}", ts("sql-file-reloaded"), tab.title))
+                    .with_type(NotificationType::Info),
+                cx,
+            );
+        }
+
+        if let Some(index) = conflict {
+            self.open_external_change_dialog(index, window, cx);
+        }
+
+        self.update_unsaved_changes(cx);
+        cx.notify();
+    }
+
+    /// Ask whether to reload the changed file of the tab at `index` or keep
+    /// its unsaved changes
+    fn open_external_change_dialog(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.get(index) else {
+            return;
+        };
+
+        let message = format!("{} {}", ts("sql-file-changed"), tab.title);
+        let this = cx.weak_entity();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title(ts("sql-file-changed-title"))
+                .width(px(400.))
+                .child(message.clone())
+                .footer(
+                    div()
+                        .h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("reload")
+                                .primary()
+                                .label(ts("sql-file-reload"))
+                                .on_click({
+                                    let this = this.clone();
+
+                                    move |_, window, cx| {
+                                        window.close_dialog(cx);
+
+                                        _ = this.update(cx, |this, cx| {
+                                            this.resolve_external_change(index, true, window, cx)
+                                        });
+                                    }
+                                }),
+                        )
+                        .child(Button::new("keep").label(ts("sql-file-keep")).on_click({
+                            let this = this.clone();
+
+                            move |_, window, cx| {
+                                window.close_dialog(cx);
+
+                                _ = this.update(cx, |this, cx| {
+                                    this.resolve_external_change(index, false, window, cx)
+                                });
+                            }
+                        })),
+                )
+        });
+    }
+
+    /// Accept the changed file of the tab at `index` as the saved state,
+    /// replacing the text of the tab when `reload` is set otherwise keeping
+    /// the text as unsaved changes
+    fn resolve_external_change(
+        &mut self,
+        index: usize,
+        reload: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(tab) = self.tabs.get_mut(index)
+            && let Some(file) = &mut tab.file
+            && let Ok(text) = fs::read_to_string(&file.path)
+        {
+            file.modified = file_modified(&file.path);
+            file.saved_text = text.clone();
+
+            if reload {
+                DatabaseQueryExecutor::set_query_text(&tab.executor, text, window, cx);
+            }
+        }
+
+        // Continue with any other changed files
+        self.check_external_changes(window, cx);
+    }
+
+    fn on_change_tab(&mut self, index: &usize, _window: &mut Window, cx: &mut Context<Self>) {
+        self.active = *index;
+        self.save(cx);
+        cx.notify();
+    }
+
+    fn on_open_file(&mut self, _: &OpenSqlFile, window: &mut Window, cx: &mut Context<Self>) {
+        let prompt_recv = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            multiple: false,
+            directories: false,
+            prompt: Some("SQL files (*.sql)".into()),
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(paths))) => match paths.into_iter().next() {
+                    Some(value) => value,
+                    None => return,
+                },
+                // Cancelled picking the file or picked nothing
+                _ => return,
+            };
+
+            _ = this.update_in(cx, |this, window, cx| this.open_file(path, window, cx));
+        })
+        .detach();
+    }
+
+    fn on_save_file(&mut self, _: &SaveSqlFile, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.active;
+        let path = self
+            .tabs
+            .get(index)
+            .and_then(|tab| tab.file.as_ref())
+            .map(|file| file.path.clone());
+
+        match path {
+            Some(path) => self.write_file(index, path, window, cx),
+            None => self.on_save_file_as(&SaveSqlFileAs, window, cx),
+        }
+    }
+
+    fn on_save_file_as(&mut self, _: &SaveSqlFileAs, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.active;
+        let Some(tab) = self.tabs.get(index) else {
+            return;
+        };
+
+        let (directory, suggested_name) = match &tab.file {
+            Some(file) => (
+                file.path.parent().map(Path::to_path_buf),
+                file_name(&file.path),
+            ),
+            None => (None, DEFAULT_FILE_NAME.to_string()),
+        };
+
+        let directory = directory
+            .or_else(|| directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf()))
+            .unwrap_or(PathBuf::from("."));
+        let prompt_recv = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn_in(window, async move |this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(value))) => value,
+                // Cancelled picking the file or picked nothing
+                _ => return,
+            };
+
+            _ = this.update_in(cx, |this, window, cx| {
+                this.write_file(index, path, window, cx)
+            });
+        })
+        .detach();
+    }
+
+    /// Open a dialog for renaming the focused tab
+    fn on_rename(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.active;
+        let Some(tab) = self.tabs.get(index) else {
+            return;
+        };
+
+        let input = cx.new(|cx| InputState::new(window, cx).default_value(tab.title.clone()));
+        let this = cx.weak_entity();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title(ts("query-tab-rename"))
+                .width(px(360.))
+                .child(Input::new(&input))
+                .footer(
+                    div()
+                        .h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("rename")
+                                .primary()
+                                .label(ts("rename"))
+                                .on_click({
+                                    let input = input.clone();
+                                    let this = this.clone();
+
+                                    move |_, window, cx| {
+                                        let title = input.read(cx).value().to_string();
+                                        window.close_dialog(cx);
+
+                                        _ = this.update(cx, |this, cx| {
+                                            this.rename_tab(index, title, cx)
+                                        });
+                                    }
+                                }),
+                        )
+                        .child(Button::new("cancel").label(ts("cancel")).on_click(
+                            |_, window, cx| {
+                                window.close_dialog(cx);
+                            },
+                        )),
+                )
+        });
+    }
+}
+
+impl Render for QueryTabsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let tabs: Vec<Tab> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(index, tab)| {
+                let title = match tab.is_dirty(cx) {
+                    true => format!("{} •", tab.title),
+                    false => tab.title.to_string(),
+                };
+
+                Tab::new().label(title).suffix(
+                    Button::new(("close-query-tab", index))
+                        .icon(IconName::Close)
+                        .ghost()
+                        .xsmall()
+                        .on_mouse_down(
+                            MouseButton::Left,
+                            cx.listener(move |this, _event, window, cx| {
+                                cx.stop_propagation();
+                                this.request_close_tab(index, window, cx);
+                            }),
+                        ),
+                )
+            })
+            .collect();
+
+        let executor = self.tabs.get(self.active).map(|tab| tab.executor.clone());
+
+        div()
+            .v_flex()
+            .size_full()
+            .key_context(QUERY_TABS_CONTEXT)
+            .on_action(cx.listener(Self::on_open_file))
+            .on_action(cx.listener(Self::on_save_file))
+            .on_action(cx.listener(Self::on_save_file_as))
+            .child(
+                div()
+                    .h_flex()
+                    .child(
+                        div().flex_1().overflow_hidden().child(
+                            TabBar::new("query-tabs")
+                                .selected_index(self.active)
+                                .on_click(cx.listener(Self::on_change_tab))
+                                .children(tabs),
+                        ),
+                    )
+                    .child(
+                        Button::new("new-query-tab")
+                            .icon(IconName::Plus)
+                            .ghost()
+                            .small()
+                            .tooltip(ts("query-tab-new"))
+                            .on_click(cx.listener(|this, _, window, cx| this.new_tab(window, cx))),
+                    )
+                    .child(
+                        Button::new("rename-query-tab")
+                            .label(ts("rename"))
+                            .ghost()
+                            .small()
+                            .on_click(
+                                cx.listener(|this, _, window, cx| this.on_rename(window, cx)),
+                            ),
+                    )
+                    .child(
+                        Button::new("open-sql-file")
+                            .label(ts("sql-file-open"))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.on_open_file(&OpenSqlFile, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("save-sql-file")
+                            .label(ts("sql-file-save"))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.on_save_file(&SaveSqlFile, window, cx)
+                            })),
+                    )
+                    .child(
+                        Button::new("save-sql-file-as")
+                            .label(ts("sql-file-save-as"))
+                            .ghost()
+                            .small()
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.on_save_file_as(&SaveSqlFileAs, window, cx)
+                            })),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().children(executor))
+    }
+}
@@ -0,0 +1,373 @@
+//! [ExportView] dialog for exporting a table or query result to a file
+
+use std::{fs::File, io::BufWriter, path::PathBuf};
+
+use anyhow::Context as AnyhowContext;
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Subscription, Task, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    StyledExt, WindowExt,
+    alert::Alert,
+    button::{Button, ButtonVariants},
+    input::{Input, InputState},
+    notification::{Notification, NotificationType},
+    select::{SearchableVec, Select, SelectEvent},
+    switch::Switch,
+};
+
+use crate::{
+    database::{
+        DatabaseQueryResult, DatabaseRowSink, sqlite::quote_identifier, stream_query_result,
+    },
+    export::{
+        BlobEncoding, ExportFormat,
+        csv::{CsvExportOptions, CsvExporter, CsvQuoting, LineEnding},
+        json::{JsonExportOptions, JsonExporter, JsonFormat},
+    },
+    state::database::DatabaseResourceExt,
+    ui::components::{
+        atoms::i18n::translated::ts,
+        molecules::form::{StringSelectState, labelled, options_select, selected_option},
+    },
+};
+
+const FORMAT_OPTIONS: &[(&str, ExportFormat)] = &[
+    ("export-format-csv", ExportFormat::Csv),
+    ("export-format-json", ExportFormat::Json),
+    ("export-format-ndjson", ExportFormat::Ndjson),
+];
+
+const QUOTING_OPTIONS: &[(&str, CsvQuoting)] = &[
+    ("export-quoting-necessary", CsvQuoting::Necessary),
+    ("export-quoting-always", CsvQuoting::Always),
+    ("export-quoting-non-numeric", CsvQuoting::NonNumeric),
+    ("export-quoting-never", CsvQuoting::Never),
+];
+
+const LINE_ENDING_OPTIONS: &[(&str, LineEnding)] = &[
+    ("export-line-ending-lf", LineEnding::Lf),
+    ("export-line-ending-crlf", LineEnding::CrLf),
+];
+
+const BLOB_ENCODING_OPTIONS: &[(&str, BlobEncoding)] = &[
+    ("export-blob-hex", BlobEncoding::Hex),
+    ("export-blob-base64", BlobEncoding::Base64),
+];
+
+/// Source of the rows to export
+#[derive(Clone)]
+pub enum ExportSource {
+    /// Every row within a table, streamed from the database
+    Table(String),
+    /// An already loaded query result
+    QueryResult(DatabaseQueryResult),
+}
+
+impl ExportSource {
+    /// Name to suggest for the exported file (without extension)
+    fn file_stem(&self) -> String {
+        match self {
+            ExportSource::Table(table) => table.clone(),
+            ExportSource::QueryResult(_) => "query".to_string(),
+        }
+    }
+}
+
+pub struct ExportView {
+    /// Source of the rows to export
+    source: ExportSource,
+
+    /// File format to export as
+    format_select: Entity<StringSelectState>,
+
+    /// Options for CSV exports
+    delimiter_input: Entity<InputState>,
+    null_value_input: Entity<InputState>,
+    quoting_select: Entity<StringSelectState>,
+    line_ending_select: Entity<StringSelectState>,
+    blob_encoding_select: Entity<StringSelectState>,
+    header: bool,
+
+    /// Options for JSON exports
+    parse_json_text: bool,
+
+    /// State of the export
+    export_state: ExportState,
+
+    _subscription: Subscription,
+}
+
+enum ExportState {
+    Idle,
+    Running(#[allow(unused)] Task<()>),
+    Error(SharedString),
+}
+
+/// Open the export dialog for the `source` within the `window`
+pub fn open_export_dialog(source: ExportSource, window: &mut Window, cx: &mut App) {
+    let title = match &source {
+        ExportSource::Table(table) => format!("{} {table}", ts("export-table")),
+        ExportSource::QueryResult(_) => ts("export-query-result").to_string(),
+    };
+
+    let view = ExportView::new(source, window, cx);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(title.clone())
+            .width(px(520.))
+            .child(view.clone())
+    });
+}
+
+impl ExportView {
+    pub fn new(source: ExportSource, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let format_select =
+                options_select(FORMAT_OPTIONS, &ExportFormat::default(), window, cx);
+            let format_subscription =
+                cx.subscribe_in(&format_select, window, Self::on_format_changed);
+
+            Self {
+                source,
+                format_select,
+                delimiter_input: cx.new(|cx| InputState::new(window, cx).default_value(",")),
+                null_value_input: cx.new(|cx| InputState::new(window, cx)),
+                quoting_select: options_select(QUOTING_OPTIONS, &CsvQuoting::default(), window, cx),
+                line_ending_select: options_select(
+                    LINE_ENDING_OPTIONS,
+                    &LineEnding::default(),
+                    window,
+                    cx,
+                ),
+                blob_encoding_select: options_select(
+                    BLOB_ENCODING_OPTIONS,
+                    &BlobEncoding::default(),
+                    window,
+                    cx,
+                ),
+                header: true,
+                parse_json_text: false,
+                export_state: ExportState::Idle,
+                _subscription: format_subscription,
+            }
+        })
+    }
+
+    fn format(&self, cx: &App) -> ExportFormat {
+        selected_option(FORMAT_OPTIONS, &self.format_select, cx).unwrap_or_default()
+    }
+
+    fn on_format_changed(
+        &mut self,
+        _state: &Entity<StringSelectState>,
+        _event: &SelectEvent<SearchableVec<String>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Show the options for the newly selected format
+        cx.notify();
+    }
+
+    /// Create the CSV options from the current input values
+    fn csv_options(&self, cx: &App) -> CsvExportOptions {
+        let defaults = CsvExportOptions::default();
+
+        let delimiter = self.delimiter_input.read(cx).value();
+        let delimiter = match delimiter.as_ref() {
+            "\\t" => b'\t',
+            value => value.bytes().next().unwrap_or(defaults.delimiter),
+        };
+
+        CsvExportOptions {
+            delimiter,
+            quoting: selected_option(QUOTING_OPTIONS, &self.quoting_select, cx).unwrap_or_default(),
+            header: self.header,
+            null_value: self.null_value_input.read(cx).value().to_string(),
+            line_ending: selected_option(LINE_ENDING_OPTIONS, &self.line_ending_select, cx)
+                .unwrap_or_default(),
+            blob_encoding: self.blob_encoding(cx),
+        }
+    }
+
+    /// Create the JSON options from the current input values
+    fn json_options(&self, format: JsonFormat, cx: &App) -> JsonExportOptions {
+        JsonExportOptions {
+            format,
+            parse_json_text: self.parse_json_text,
+            blob_encoding: self.blob_encoding(cx),
+        }
+    }
+
+    fn blob_encoding(&self, cx: &App) -> BlobEncoding {
+        selected_option(BLOB_ENCODING_OPTIONS, &self.blob_encoding_select, cx).unwrap_or_default()
+    }
+
+    /// Create the sink writing the exported rows to `file`
+    fn create_sink(&self, file: File, cx: &App) -> Box<dyn DatabaseRowSink> {
+        let writer = BufWriter::new(file);
+
+        match self.format(cx) {
+            ExportFormat::Csv => Box::new(CsvExporter::new(writer, self.csv_options(cx))),
+            ExportFormat::Json => Box::new(JsonExporter::new(
+                writer,
+                self.json_options(JsonFormat::Array, cx),
+            )),
+            ExportFormat::Ndjson => Box::new(JsonExporter::new(
+                writer,
+                self.json_options(JsonFormat::Ndjson, cx),
+            )),
+        }
+    }
+
+    fn on_export(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if matches!(self.export_state, ExportState::Running(_)) {
+            return;
+        }
+
+        let database = match cx.database_connection() {
+            Some(value) => value,
+            None => return,
+        };
+
+        let source = self.source.clone();
+
+        let directory = directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or(PathBuf::from("."));
+        let file_name = format!("{}.{}", source.file_stem(), self.format(cx).extension());
+        let prompt_recv = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(value))) => value,
+                // Cancelled picking the file or picked nothing
+                _ => {
+                    _ = this.update(cx, |this, cx| {
+                        this.export_state = ExportState::Idle;
+                        cx.notify();
+                    });
+                    return;
+                }
+            };
+
+            let result = async {
+                let file = File::create(&path).context("failed to create export file")?;
+                let mut sink = this.read_with(cx, |this, cx| this.create_sink(file, cx))?;
+
+                match source {
+                    ExportSource::Table(table) => {
+                        let query = format!("SELECT * FROM {}", quote_identifier(&table));
+                        database.stream_query(&query, sink).await
+                    }
+                    ExportSource::QueryResult(result) => {
+                        cx.background_spawn(
+                            async move { stream_query_result(&result, sink.as_mut()) },
+                        )
+                        .await
+                    }
+                }
+            }
+            .await;
+
+            _ = this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(count) => {
+                        this.export_state = ExportState::Idle;
+                        window.close_dialog(cx);
+                        window.push_notification(
+                            Notification::new()
+                                .message(format!("{} {count} {}", ts("exported"), ts("rows")))
+                                .with_type(NotificationType::Success),
+                            cx,
+                        );
+                    }
+                    Err(error) => {
+                        tracing::error!(?error, "failed to export");
+                        this.export_state = ExportState::Error(format!("{error:?}").into());
+                    }
+                }
+
+                cx.notify();
+            });
+        });
+
+        self.export_state = ExportState::Running(task);
+        cx.notify();
+    }
+}
+
+impl Render for ExportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = matches!(self.export_state, ExportState::Running(_));
+        let format = self.format(cx);
+
+        div()
+            .v_flex()
+            .gap_2()
+            .w_full()
+            .child(labelled(
+                ts("export-format"),
+                Select::new(&self.format_select),
+            ))
+            .when(format == ExportFormat::Csv, |this| {
+                this.child(labelled(
+                    ts("csv-delimiter"),
+                    Input::new(&self.delimiter_input),
+                ))
+                .child(labelled(
+                    ts("export-quoting"),
+                    Select::new(&self.quoting_select),
+                ))
+                .child(labelled(
+                    ts("export-null-value"),
+                    Input::new(&self.null_value_input),
+                ))
+                .child(labelled(
+                    ts("export-line-ending"),
+                    Select::new(&self.line_ending_select),
+                ))
+                .child(
+                    Switch::new("export-header")
+                        .label(ts("export-header"))
+                        .checked(self.header)
+                        .on_click(cx.listener(|this, checked, _window, cx| {
+                            this.header = *checked;
+                            cx.notify();
+                        })),
+                )
+            })
+            .child(labelled(
+                ts("export-blob-encoding"),
+                Select::new(&self.blob_encoding_select),
+            ))
+            .when(format != ExportFormat::Csv, |this| {
+                this.child(
+                    Switch::new("export-parse-json")
+                        .label(ts("export-parse-json-text"))
+                        .checked(self.parse_json_text)
+                        .on_click(cx.listener(|this, checked, _window, cx| {
+                            this.parse_json_text = *checked;
+                            cx.notify();
+                        })),
+                )
+            })
+            .child(match &self.export_state {
+                ExportState::Error(error) => {
+                    div().child(Alert::error("export-error", error.clone()).title(ts("error")))
+                }
+                _ => div(),
+            })
+            .child(
+                div().h_flex().justify_end().child(
+                    Button::new("export")
+                        .primary()
+                        .label(ts("export"))
+                        .loading(running)
+                        .on_click(cx.listener(Self::on_export)),
+                ),
+            )
+    }
+}
@@ -0,0 +1,178 @@
+//! [FormatterSettingsView] dialog for configuring the SQL formatter
+
+use gpui::{
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, Styled, Subscription,
+    Window, div, px,
+};
+use gpui_component::{
+    StyledExt, WindowExt,
+    input::{Input, InputEvent, InputState},
+    select::{SearchableVec, Select, SelectEvent},
+};
+
+use crate::{
+    settings::{
+        AppSettings,
+        formatter::{FormatterSettings, KeywordCase},
+        update_settings,
+    },
+    ui::components::{
+        atoms::i18n::translated::ts,
+        molecules::form::{StringSelectState, labelled, options_select, selected_option},
+    },
+};
+
+/// Indentation options as the indent width and whether to use tabs
+const INDENT_OPTIONS: &[(&str, (u8, bool))] = &[
+    ("formatter-indent-2-spaces", (2, false)),
+    ("formatter-indent-4-spaces", (4, false)),
+    ("formatter-indent-tabs", (4, true)),
+];
+
+const KEYWORD_CASE_OPTIONS: &[(&str, KeywordCase)] = &[
+    ("formatter-keyword-case-preserve", KeywordCase::Preserve),
+    ("formatter-keyword-case-upper", KeywordCase::Upper),
+    ("formatter-keyword-case-lower", KeywordCase::Lower),
+];
+
+pub struct FormatterSettingsView {
+    indent_select: Entity<StringSelectState>,
+    keyword_case_select: Entity<StringSelectState>,
+    lines_between_input: Entity<InputState>,
+    inline_width_input: Entity<InputState>,
+
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Open the formatter settings dialog within the `window`
+pub fn open_formatter_settings_dialog(window: &mut Window, cx: &mut App) {
+    let view = FormatterSettingsView::new(window, cx);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(ts("formatter-settings"))
+            .width(px(420.))
+            .child(view.clone())
+    });
+}
+
+impl FormatterSettingsView {
+    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let settings = cx.global::<AppSettings>().formatter.clone();
+
+        cx.new(|cx| {
+            let indent_select = options_select(
+                INDENT_OPTIONS,
+                &(settings.indent_width, settings.use_tabs),
+                window,
+                cx,
+            );
+            let keyword_case_select =
+                options_select(KEYWORD_CASE_OPTIONS, &settings.keyword_case, window, cx);
+            let lines_between_input = cx.new(|cx| {
+                InputState::new(window, cx)
+                    .default_value(settings.lines_between_statements.to_string())
+            });
+            let inline_width_input = cx.new(|cx| {
+                InputState::new(window, cx).default_value(settings.inline_width.to_string())
+            });
+
+            let subscriptions = vec![
+                cx.subscribe_in(&indent_select, window, Self::on_select_changed),
+                cx.subscribe_in(&keyword_case_select, window, Self::on_select_changed),
+                cx.subscribe_in(&lines_between_input, window, Self::on_input_changed),
+                cx.subscribe_in(&inline_width_input, window, Self::on_input_changed),
+            ];
+
+            Self {
+                indent_select,
+                keyword_case_select,
+                lines_between_input,
+                inline_width_input,
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    /// Update the `settings` from the current input values, invalid
+    /// numbers keep their previous value
+    fn apply(&self, settings: &mut FormatterSettings, cx: &App) {
+        if let Some((indent_width, use_tabs)) =
+            selected_option(INDENT_OPTIONS, &self.indent_select, cx)
+        {
+            settings.indent_width = indent_width;
+            settings.use_tabs = use_tabs;
+        }
+
+        if let Some(keyword_case) =
+            selected_option(KEYWORD_CASE_OPTIONS, &self.keyword_case_select, cx)
+        {
+            settings.keyword_case = keyword_case;
+        }
+
+        if let Ok(lines) = self.lines_between_input.read(cx).value().trim().parse() {
+            settings.lines_between_statements = lines;
+        }
+
+        if let Ok(width) = self.inline_width_input.read(cx).value().trim().parse() {
+            settings.inline_width = width;
+        }
+    }
+
+    /// Save the settings when any of the inputs change
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let mut formatter = cx.global::<AppSettings>().formatter.clone();
+        self.apply(&mut formatter, cx);
+
+        update_settings(cx, move |settings| {
+            settings.formatter = formatter;
+        });
+    }
+
+    fn on_select_changed(
+        &mut self,
+        _state: &Entity<StringSelectState>,
+        _event: &SelectEvent<SearchableVec<String>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.save(cx);
+    }
+
+    fn on_input_changed(
+        &mut self,
+        _state: &Entity<InputState>,
+        event: &InputEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(event, InputEvent::Change { .. }) {
+            self.save(cx);
+        }
+    }
+}
+
+impl Render for FormatterSettingsView {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_2()
+            .w_full()
+            .child(labelled(
+                ts("formatter-indent"),
+                Select::new(&self.indent_select),
+            ))
+            .child(labelled(
+                ts("formatter-keyword-case"),
+                Select::new(&self.keyword_case_select),
+            ))
+            .child(labelled(
+                ts("formatter-lines-between"),
+                Input::new(&self.lines_between_input),
+            ))
+            .child(labelled(
+                ts("formatter-inline-width"),
+                Input::new(&self.inline_width_input),
+            ))
+    }
+}
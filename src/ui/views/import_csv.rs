@@ -0,0 +1,756 @@
+//! [CsvImportView] wizard for importing the contents of a CSV file into a
+//! new or existing table within the current database
+
+use std::path::PathBuf;
+
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Subscription, Task, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, IndexPath, Sizable, StyledExt,
+    alert::Alert,
+    button::{Button, ButtonVariants},
+    input::{Input, InputState},
+    progress::Progress,
+    scroll::ScrollableElement,
+    select::{SearchableVec, Select, SelectEvent, SelectState},
+    switch::Switch,
+    table::{Column, DataTable, TableDelegate, TableState},
+};
+use tokio::sync::watch;
+
+use crate::{
+    database::{
+        DatabaseInsertProgress, DatabaseInsertReport, DatabaseInsertRequest, DatabaseInsertRow,
+        DatabaseTable,
+    },
+    import::csv::{
+        CSV_ENCODINGS, CsvColumnType, CsvData, CsvFileRecords, CsvFileSummary, CsvImportOptions,
+        convert_value, create_table_sql, infer_column_types, read_csv_file, summarize_csv_file,
+    },
+    state::{
+        async_resource::AsyncResource,
+        database::{DatabaseResourceExt, connection::QueryExecutedEvent},
+    },
+    ui::components::{
+        atoms::i18n::translated::ts,
+        molecules::form::{StringSelectState, labelled},
+    },
+};
+
+/// Number of records to show in the preview table
+const PREVIEW_ROWS: usize = 50;
+
+/// Maximum number of row errors to display after an import
+const MAX_DISPLAYED_ERRORS: usize = 100;
+
+pub struct CsvImportView {
+    /// Path to the CSV file being imported
+    path: PathBuf,
+
+    /// Input states for the file options
+    delimiter_input: Entity<InputState>,
+    quote_input: Entity<InputState>,
+    null_marker_input: Entity<InputState>,
+    encoding_select: Entity<StringSelectState>,
+    has_header: bool,
+
+    /// Whether to import into a new table rather than an existing one
+    create_table: bool,
+    new_table_input: Entity<InputState>,
+    table_select: Entity<StringSelectState>,
+
+    /// Tables within the current database
+    tables: Entity<AsyncResource<Vec<DatabaseTable>>>,
+
+    /// Current preview of the file contents
+    preview: CsvPreviewState,
+    preview_table: Entity<TableState<CsvPreviewDelegate>>,
+
+    /// Target column selection for each of the CSV columns when importing
+    /// into an existing table
+    mappings: Vec<Entity<StringSelectState>>,
+
+    /// State of the import itself
+    import_state: CsvImportState,
+
+    _subscriptions: (Subscription,),
+}
+
+enum CsvPreviewState {
+    Loading(#[allow(unused)] Task<()>),
+    Loaded(CsvData),
+    Error(SharedString),
+}
+
+enum CsvImportState {
+    Idle,
+    Running {
+        progress: DatabaseInsertProgress,
+        _tasks: (Task<()>, Task<()>),
+    },
+    Finished(DatabaseInsertReport),
+    Error(SharedString),
+}
+
+/// Target for the imported rows
+enum CsvImportTarget {
+    /// Create a new table with the inferred column types
+    NewTable { table: String },
+    /// Insert into an existing table using the column mapping, each
+    /// mapping is the index of the CSV column and the target column name
+    ExistingTable {
+        table: String,
+        mapping: Vec<(usize, String)>,
+    },
+}
+
+#[derive(Default)]
+struct CsvPreviewDelegate {
+    rows: Vec<Vec<SharedString>>,
+    columns: Vec<Column>,
+}
+
+impl TableDelegate for CsvPreviewDelegate {
+    fn columns_count(&self, _: &App) -> usize {
+        self.columns.len()
+    }
+
+    fn rows_count(&self, _: &App) -> usize {
+        self.rows.len()
+    }
+
+    fn column(&self, col_ix: usize, _: &App) -> Column {
+        self.columns[col_ix].clone()
+    }
+
+    fn render_td(
+        &mut self,
+        row_ix: usize,
+        col_ix: usize,
+        _window: &mut Window,
+        _cx: &mut Context<TableState<Self>>,
+    ) -> impl IntoElement {
+        let row = &self.rows[row_ix];
+        let value = row.get(col_ix);
+        value.cloned().unwrap_or_default()
+    }
+}
+
+impl CsvImportView {
+    pub fn new(path: PathBuf, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let tables = cx.database_tables();
+            let table_names = Self::table_names(&tables, cx);
+
+            let new_table_name = path
+                .file_stem()
+                .map(|value| value.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            let encoding_select = cx.new(|cx| {
+                SelectState::new(
+                    SearchableVec::new(
+                        CSV_ENCODINGS
+                            .iter()
+                            .map(|value| value.to_string())
+                            .collect::<Vec<_>>(),
+                    ),
+                    Some(IndexPath::new(0)),
+                    window,
+                    cx,
+                )
+            });
+
+            let table_select = cx.new(|cx| {
+                SelectState::new(
+                    SearchableVec::new(table_names),
+                    Some(IndexPath::new(0)),
+                    window,
+                    cx,
+                )
+            });
+
+            // Changing the target table changes the available columns for mapping
+            let table_select_subscription =
+                cx.subscribe_in(&table_select, window, Self::on_target_table_changed);
+
+            let preview_table =
+                cx.new(|cx| TableState::new(CsvPreviewDelegate::default(), window, cx));
+
+            let mut this = Self {
+                path,
+                delimiter_input: cx.new(|cx| InputState::new(window, cx).default_value(",")),
+                quote_input: cx.new(|cx| InputState::new(window, cx).default_value("\"")),
+                null_marker_input: cx.new(|cx| {
+                    InputState::new(window, cx).placeholder(ts("csv-null-marker-placeholder"))
+                }),
+                encoding_select,
+                has_header: true,
+                create_table: true,
+                new_table_input: cx
+                    .new(|cx| InputState::new(window, cx).default_value(new_table_name)),
+                table_select,
+                tables,
+                preview: CsvPreviewState::Error(SharedString::default()),
+                preview_table,
+                mappings: Vec::new(),
+                import_state: CsvImportState::Idle,
+                _subscriptions: (table_select_subscription,),
+            };
+
+            this.load_preview(window, cx);
+
+            this
+        })
+    }
+
+    /// Collect the names of the currently loaded tables
+    fn table_names(tables: &Entity<AsyncResource<Vec<DatabaseTable>>>, cx: &App) -> Vec<String> {
+        match tables.read(cx) {
+            AsyncResource::Loaded(tables) => {
+                tables.iter().map(|table| table.name.clone()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Create the import options from the current input values
+    fn options(&self, cx: &App) -> CsvImportOptions {
+        let defaults = CsvImportOptions::default();
+
+        let delimiter = self.delimiter_input.read(cx).value();
+        let delimiter = match delimiter.as_ref() {
+            "\\t" => b'\t',
+            value => value.bytes().next().unwrap_or(defaults.delimiter),
+        };
+
+        let quote = self.quote_input.read(cx).value();
+        let quote = quote.bytes().next().unwrap_or(defaults.quote);
+
+        let null_marker = self.null_marker_input.read(cx).value();
+        let null_marker = (!null_marker.is_empty()).then(|| null_marker.to_string());
+
+        let encoding = self
+            .encoding_select
+            .read(cx)
+            .selected_value()
+            .and_then(|value| encoding_rs::Encoding::for_label(value.as_bytes()))
+            .unwrap_or(defaults.encoding);
+
+        CsvImportOptions {
+            delimiter,
+            quote,
+            has_header: self.has_header,
+            encoding,
+            null_marker,
+        }
+    }
+
+    /// Load the preview of the file using the current options
+    fn load_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = self.path.clone();
+        let options = self.options(cx);
+
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let result = cx
+                .background_spawn(async move {
+                    let data = read_csv_file(&path, &options, Some(PREVIEW_ROWS))?;
+                    let types = infer_column_types(&data, &options);
+                    Ok::<_, anyhow::Error>((data, types))
+                })
+                .await;
+
+            _ = this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok((data, types)) => this.set_preview(data, types, window, cx),
+                    Err(error) => {
+                        this.preview = CsvPreviewState::Error(format!("{error:?}").into())
+                    }
+                }
+
+                cx.notify();
+            });
+        });
+
+        self.preview = CsvPreviewState::Loading(task);
+        cx.notify();
+    }
+
+    fn set_preview(
+        &mut self,
+        data: CsvData,
+        types: Vec<CsvColumnType>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.preview_table.update(cx, |this, cx| {
+            let delegate = this.delegate_mut();
+
+            delegate.columns = data
+                .columns
+                .iter()
+                .zip(&types)
+                .map(|(column, column_type)| {
+                    Column::new(
+                        column.clone(),
+                        format!("{column} ({})", column_type.sql_type()),
+                    )
+                })
+                .collect();
+
+            delegate.rows = data
+                .records
+                .iter()
+                .map(|record| {
+                    record
+                        .values
+                        .iter()
+                        .map(|value| SharedString::from(value.clone()))
+                        .collect()
+                })
+                .collect();
+
+            this.refresh(cx);
+        });
+
+        self.preview = CsvPreviewState::Loaded(data);
+        self.update_mappings(window, cx);
+    }
+
+    /// Handles changes to the target table for the import
+    fn on_target_table_changed(
+        &mut self,
+        _entity: &Entity<StringSelectState>,
+        _event: &SelectEvent<SearchableVec<String>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.update_mappings(window, cx);
+    }
+
+    /// Rebuild the column mapping selectors for the current target table, CSV
+    /// columns are mapped to the table column with a matching name by default
+    fn update_mappings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let csv_columns = match &self.preview {
+            CsvPreviewState::Loaded(data) => data.columns.clone(),
+            _ => Vec::new(),
+        };
+
+        let selected_table = self.table_select.read(cx).selected_value().cloned();
+        let table_columns: Vec<String> = match (selected_table, self.tables.read(cx)) {
+            (Some(selected_table), AsyncResource::Loaded(tables)) => tables
+                .iter()
+                .find(|table| table.name == selected_table)
+                .map(|table| {
+                    table
+                        .columns
+                        .iter()
+                        .map(|column| column.name.clone())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        let mut options = vec![ts("csv-skip-column").to_string()];
+        options.extend(table_columns.iter().cloned());
+
+        self.mappings = csv_columns
+            .iter()
+            .map(|csv_column| {
+                let selected = table_columns
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(csv_column))
+                    .map(|index| index + 1)
+                    .unwrap_or_default();
+
+                let options = options.clone();
+                cx.new(|cx| {
+                    SelectState::new(
+                        SearchableVec::new(options),
+                        Some(IndexPath::new(selected)),
+                        window,
+                        cx,
+                    )
+                })
+            })
+            .collect();
+
+        cx.notify();
+    }
+
+    /// Determine the import target from the current inputs
+    fn target(&self, cx: &App) -> Option<CsvImportTarget> {
+        if self.create_table {
+            let table = self.new_table_input.read(cx).value().trim().to_string();
+            if table.is_empty() {
+                return None;
+            }
+
+            return Some(CsvImportTarget::NewTable { table });
+        }
+
+        let table = self.table_select.read(cx).selected_value()?.clone();
+        let mapping: Vec<(usize, String)> = self
+            .mappings
+            .iter()
+            .enumerate()
+            .filter_map(|(index, state)| {
+                let state = state.read(cx);
+                // Index zero is the "skip" option
+                if state.selected_index(cx).is_none_or(|value| value.row == 0) {
+                    return None;
+                }
+
+                state.selected_value().map(|column| (index, column.clone()))
+            })
+            .collect();
+
+        if mapping.is_empty() {
+            return None;
+        }
+
+        Some(CsvImportTarget::ExistingTable { table, mapping })
+    }
+
+    fn on_reload_preview(
+        &mut self,
+        _event: &ClickEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.load_preview(window, cx);
+    }
+
+    fn on_import(&mut self, _event: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if matches!(self.import_state, CsvImportState::Running { .. }) {
+            return;
+        }
+
+        let database_entity = cx.database_connection_resource();
+        let database = match cx.database_connection() {
+            Some(value) => value,
+            None => return,
+        };
+
+        let target = match self.target(cx) {
+            Some(value) => value,
+            None => {
+                self.import_state = CsvImportState::Error(ts("csv-import-missing-target"));
+                cx.notify();
+                return;
+            }
+        };
+
+        let path = self.path.clone();
+        let options = self.options(cx);
+        let (progress_tx, mut progress_rx) = watch::channel(DatabaseInsertProgress::default());
+
+        // Forward progress updates to the view
+        let progress_task = cx.spawn(async move |this, cx| {
+            while progress_rx.changed().await.is_ok() {
+                let value = *progress_rx.borrow_and_update();
+                let result = this.update(cx, |this, cx| {
+                    if let CsvImportState::Running { progress, .. } = &mut this.import_state {
+                        *progress = value;
+                        cx.notify();
+                    }
+                });
+
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let import_task = cx.spawn(async move |this, cx| {
+            let request = cx
+                .background_spawn(async move {
+                    // Read through the file once for the types and row count, then
+                    // stream the records while inserting
+                    let summary = summarize_csv_file(&path, &options)?;
+                    let records = CsvFileRecords::open(&path, &options)?;
+                    Ok::<_, anyhow::Error>(Self::create_insert_request(
+                        summary, records, options, target,
+                    ))
+                })
+                .await;
+
+            let result = match request {
+                Ok(request) => database.insert_rows(request, progress_tx).await,
+                Err(error) => Err(error),
+            };
+
+            _ = this.update(cx, |this, cx| {
+                this.import_state = match result {
+                    Ok(report) => {
+                        // Notify our listeners that the database contents have changed
+                        database_entity.update(cx, |_, cx| {
+                            cx.emit(QueryExecutedEvent {
+                                query: "-- import csv".into(),
+                            });
+                        });
+
+                        CsvImportState::Finished(report)
+                    }
+                    Err(error) => CsvImportState::Error(format!("{error:?}").into()),
+                };
+                cx.notify();
+            });
+        });
+
+        self.import_state = CsvImportState::Running {
+            progress: DatabaseInsertProgress::default(),
+            _tasks: (progress_task, import_task),
+        };
+        cx.notify();
+    }
+
+    /// Create an insert request for the `target` streaming the CSV `records`
+    ///
+    /// Inferred types only apply when creating a new table, values for an
+    /// existing table are inserted as text leaving the conversion to the
+    /// affinity of the columns (e.g. "007" stays as is within a TEXT column)
+    fn create_insert_request(
+        summary: CsvFileSummary,
+        records: CsvFileRecords,
+        options: CsvImportOptions,
+        target: CsvImportTarget,
+    ) -> DatabaseInsertRequest {
+        let (table, create_sql, mapping, types) = match target {
+            CsvImportTarget::NewTable { table } => {
+                let create_sql = create_table_sql(&table, &summary.columns, &summary.types);
+                let mapping = summary.columns.iter().cloned().enumerate().collect();
+                (table, Some(create_sql), mapping, summary.types)
+            }
+            CsvImportTarget::ExistingTable { table, mapping } => {
+                let types = vec![CsvColumnType::Text; summary.columns.len()];
+                (table, None, mapping, types)
+            }
+        };
+
+        let indexes: Vec<usize> = mapping.iter().map(|(index, _)| *index).collect();
+        let rows = records.map(move |record| {
+            let record = record?;
+
+            Ok(DatabaseInsertRow {
+                source: record.line,
+                values: indexes
+                    .iter()
+                    .map(|index| {
+                        convert_value(
+                            record.values.get(*index).map(String::as_str),
+                            types.get(*index).copied().unwrap_or(CsvColumnType::Text),
+                            &options,
+                        )
+                    })
+                    .collect(),
+            })
+        });
+
+        DatabaseInsertRequest {
+            table,
+            create_sql,
+            columns: mapping.into_iter().map(|(_, column)| column).collect(),
+            rows: Box::new(rows),
+            total: summary.records,
+        }
+    }
+
+    fn render_options(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .h_flex()
+            .gap_2()
+            .items_end()
+            .child(labelled(
+                ts("csv-delimiter"),
+                Input::new(&self.delimiter_input).w_16(),
+            ))
+            .child(labelled(
+                ts("csv-quote"),
+                Input::new(&self.quote_input).w_16(),
+            ))
+            .child(labelled(
+                ts("csv-null-marker"),
+                Input::new(&self.null_marker_input).w_32(),
+            ))
+            .child(labelled(
+                ts("csv-encoding"),
+                Select::new(&self.encoding_select).w_40(),
+            ))
+            .child(
+                Switch::new("csv-header")
+                    .label(ts("csv-header-row"))
+                    .checked(self.has_header)
+                    .on_click(cx.listener(|this, checked, window, cx| {
+                        this.has_header = *checked;
+                        this.load_preview(window, cx);
+                    })),
+            )
+            .child(
+                Button::new("csv-reload-preview")
+                    .label(ts("csv-reload-preview"))
+                    .small()
+                    .on_click(cx.listener(Self::on_reload_preview)),
+            )
+    }
+
+    fn render_preview(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .h(px(240.))
+            .w_full()
+            .border_1()
+            .border_color(cx.theme().border)
+            .child(match &self.preview {
+                CsvPreviewState::Loading(_) => div()
+                    .size_full()
+                    .p_3()
+                    .child(ts("csv-loading-preview"))
+                    .into_any_element(),
+                CsvPreviewState::Loaded(_) => DataTable::new(&self.preview_table)
+                    .stripe(true)
+                    .bordered(true)
+                    .scrollbar_visible(true, true)
+                    .into_any_element(),
+                CsvPreviewState::Error(error) => div()
+                    .p_3()
+                    .child(Alert::error("csv-preview-error", error.clone()).title(ts("error")))
+                    .into_any_element(),
+            })
+    }
+
+    fn render_target(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let csv_columns = match &self.preview {
+            CsvPreviewState::Loaded(data) => data.columns.clone(),
+            _ => Vec::new(),
+        };
+
+        div()
+            .v_flex()
+            .gap_2()
+            .child(
+                Switch::new("csv-create-table")
+                    .label(ts("csv-create-new-table"))
+                    .checked(self.create_table)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.create_table = *checked;
+                        cx.notify();
+                    })),
+            )
+            .when(self.create_table, |this| {
+                this.child(labelled(
+                    ts("csv-new-table-name"),
+                    Input::new(&self.new_table_input).w_80(),
+                ))
+            })
+            .when(!self.create_table, |this| {
+                this.child(labelled(
+                    ts("csv-target-table"),
+                    Select::new(&self.table_select).w_80(),
+                ))
+                .child(
+                    div()
+                        .v_flex()
+                        .gap_1()
+                        .max_h(px(160.))
+                        .overflow_y_scrollbar()
+                        .children(csv_columns.into_iter().zip(&self.mappings).map(
+                            |(column, mapping)| {
+                                div()
+                                    .h_flex()
+                                    .gap_2()
+                                    .child(div().w_40().text_sm().child(column))
+                                    .child(Select::new(mapping).small().w_60())
+                            },
+                        )),
+                )
+            })
+    }
+
+    fn render_import_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        match &self.import_state {
+            CsvImportState::Idle => div(),
+            CsvImportState::Running { progress, .. } => {
+                let value = match progress.total {
+                    0 => 0.,
+                    total => progress.processed as f32 / total as f32 * 100.,
+                };
+
+                div()
+                    .v_flex()
+                    .gap_1()
+                    .text_sm()
+                    .child(Progress::new().value(value))
+                    .child(format!("{} / {}", progress.processed, progress.total))
+            }
+            CsvImportState::Finished(report) => div()
+                .v_flex()
+                .gap_1()
+                .child(Alert::success(
+                    "csv-import-finished",
+                    format!(
+                        "{} {}, {} {}",
+                        report.inserted,
+                        ts("csv-rows-imported"),
+                        report.errors.len(),
+                        ts("csv-rows-failed")
+                    ),
+                ))
+                .when(!report.errors.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .v_flex()
+                            .max_h(px(120.))
+                            .overflow_y_scrollbar()
+                            .text_xs()
+                            .text_color(cx.theme().danger)
+                            .children(report.errors.iter().take(MAX_DISPLAYED_ERRORS).map(
+                                |error| {
+                                    div().child(format!(
+                                        "{} {}: {}",
+                                        ts("csv-line"),
+                                        error.source,
+                                        error.message
+                                    ))
+                                },
+                            )),
+                    )
+                }),
+            CsvImportState::Error(error) => {
+                div().child(Alert::error("csv-import-error", error.clone()).title(ts("error")))
+            }
+        }
+    }
+}
+
+impl Render for CsvImportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = matches!(self.import_state, CsvImportState::Running { .. });
+
+        div()
+            .v_flex()
+            .gap_3()
+            .w_full()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(self.path.display().to_string()),
+            )
+            .child(self.render_options(cx))
+            .child(self.render_preview(cx))
+            .child(self.render_target(cx))
+            .child(self.render_import_state(cx))
+            .child(
+                div().h_flex().justify_end().child(
+                    Button::new("csv-import")
+                        .primary()
+                        .label(ts("import"))
+                        .loading(running)
+                        .on_click(cx.listener(Self::on_import)),
+                ),
+            )
+    }
+}
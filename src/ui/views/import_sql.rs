@@ -0,0 +1,281 @@
+//! [SqlImportView] dialog for executing the statements of a SQL file against
+//! the current database
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as AnyhowContext;
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Task, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, StyledExt,
+    alert::Alert,
+    button::{Button, ButtonVariants},
+    progress::Progress,
+    scroll::ScrollableElement,
+    switch::Switch,
+};
+use tokio::sync::watch;
+
+use crate::{
+    database::{
+        DatabaseScriptProgress, DatabaseScriptReport, DatabaseScriptRequest,
+        DatabaseScriptStatements,
+    },
+    import::sql::{SqlFileStatements, is_transaction_statement},
+    state::database::{DatabaseResourceExt, connection::QueryExecutedEvent},
+    ui::components::atoms::i18n::translated::ts,
+};
+
+/// Maximum number of statement errors to display after an import
+const MAX_DISPLAYED_ERRORS: usize = 100;
+
+pub struct SqlImportView {
+    /// Path to the SQL file being imported
+    path: PathBuf,
+
+    /// Whether to stop at the first failing statement
+    stop_on_error: bool,
+    /// Whether to execute the file within a transaction
+    transaction: bool,
+
+    /// State of the import itself
+    import_state: SqlImportState,
+}
+
+enum SqlImportState {
+    Idle,
+    Running {
+        progress: DatabaseScriptProgress,
+        _tasks: (Task<()>, Task<()>),
+    },
+    Finished(DatabaseScriptReport),
+    Error(SharedString),
+}
+
+impl SqlImportView {
+    pub fn new(path: PathBuf, cx: &mut App) -> Entity<Self> {
+        cx.new(|_cx| Self {
+            path,
+            stop_on_error: true,
+            transaction: true,
+            import_state: SqlImportState::Idle,
+        })
+    }
+
+    /// Create the script request reading the statements from the file
+    fn create_script_request(
+        path: &Path,
+        stop_on_error: bool,
+        transaction: bool,
+    ) -> anyhow::Result<DatabaseScriptRequest> {
+        let file = File::open(path).context("failed to open sql file")?;
+        let total_bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        let statements = SqlFileStatements::new(BufReader::new(file));
+        let statements: DatabaseScriptStatements = if transaction {
+            // Transaction statements within the file (e.g. from a dump) would
+            // conflict with the transaction wrapping the import
+            Box::new(statements.filter(|statement| match statement {
+                Ok(statement) => !is_transaction_statement(&statement.sql),
+                Err(_) => true,
+            }))
+        } else {
+            Box::new(statements)
+        };
+
+        Ok(DatabaseScriptRequest {
+            statements,
+            total_bytes,
+            stop_on_error,
+            transaction,
+        })
+    }
+
+    fn on_import(&mut self, _event: &ClickEvent, _window: &mut Window, cx: &mut Context<Self>) {
+        if matches!(self.import_state, SqlImportState::Running { .. }) {
+            return;
+        }
+
+        let database_entity = cx.database_connection_resource();
+        let database = match cx.database_connection() {
+            Some(value) => value,
+            None => return,
+        };
+
+        let request =
+            match Self::create_script_request(&self.path, self.stop_on_error, self.transaction) {
+                Ok(value) => value,
+                Err(error) => {
+                    self.import_state = SqlImportState::Error(format!("{error:?}").into());
+                    cx.notify();
+                    return;
+                }
+            };
+
+        let (progress_tx, mut progress_rx) = watch::channel(DatabaseScriptProgress {
+            total_bytes: request.total_bytes,
+            ..Default::default()
+        });
+
+        // Forward progress updates to the view
+        let progress_task = cx.spawn(async move |this, cx| {
+            while progress_rx.changed().await.is_ok() {
+                let value = *progress_rx.borrow_and_update();
+                let result = this.update(cx, |this, cx| {
+                    if let SqlImportState::Running { progress, .. } = &mut this.import_state {
+                        *progress = value;
+                        cx.notify();
+                    }
+                });
+
+                if result.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let import_task = cx.spawn(async move |this, cx| {
+            let result = database.execute_script(request, progress_tx).await;
+
+            _ = this.update(cx, |this, cx| {
+                // Statements may have changed the database even when failing
+                database_entity.update(cx, |_, cx| {
+                    cx.emit(QueryExecutedEvent {
+                        query: "-- import sql".into(),
+                    });
+                });
+
+                this.import_state = match result {
+                    Ok(report) => SqlImportState::Finished(report),
+                    Err(error) => SqlImportState::Error(format!("{error:?}").into()),
+                };
+                cx.notify();
+            });
+        });
+
+        self.import_state = SqlImportState::Running {
+            progress: DatabaseScriptProgress::default(),
+            _tasks: (progress_task, import_task),
+        };
+        cx.notify();
+    }
+
+    fn render_import_state(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        match &self.import_state {
+            SqlImportState::Idle => div(),
+            SqlImportState::Running { progress, .. } => {
+                let value = match progress.total_bytes {
+                    0 => 0.,
+                    total => progress.bytes_read as f32 / total as f32 * 100.,
+                };
+
+                div()
+                    .v_flex()
+                    .gap_1()
+                    .text_sm()
+                    .child(Progress::new().value(value))
+                    .child(format!(
+                        "{} / {} {}, {} {}",
+                        progress.bytes_read,
+                        progress.total_bytes,
+                        ts("sql-import-bytes"),
+                        progress.statements,
+                        ts("sql-import-statements"),
+                    ))
+            }
+            SqlImportState::Finished(report) => div()
+                .v_flex()
+                .gap_1()
+                .child(if report.rolled_back {
+                    Alert::warning("sql-import-finished", ts("sql-import-rolled-back"))
+                } else {
+                    Alert::success(
+                        "sql-import-finished",
+                        format!(
+                            "{} {}, {} {}",
+                            report.executed,
+                            ts("sql-import-statements-executed"),
+                            report.errors.len(),
+                            ts("sql-import-statements-failed")
+                        ),
+                    )
+                })
+                .when(!report.errors.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .v_flex()
+                            .max_h(px(120.))
+                            .overflow_y_scrollbar()
+                            .text_xs()
+                            .text_color(cx.theme().danger)
+                            .children(report.errors.iter().take(MAX_DISPLAYED_ERRORS).map(
+                                |error| {
+                                    div().child(format!(
+                                        "{} {}: {}",
+                                        ts("sql-import-line"),
+                                        error.line,
+                                        error.message
+                                    ))
+                                },
+                            )),
+                    )
+                }),
+            SqlImportState::Error(error) => {
+                div().child(Alert::error("sql-import-error", error.clone()).title(ts("error")))
+            }
+        }
+    }
+}
+
+impl Render for SqlImportView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = matches!(self.import_state, SqlImportState::Running { .. });
+
+        div()
+            .v_flex()
+            .gap_3()
+            .w_full()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(self.path.display().to_string()),
+            )
+            .child(
+                Switch::new("sql-import-stop-on-error")
+                    .label(ts("sql-import-stop-on-error"))
+                    .checked(self.stop_on_error)
+                    .disabled(running)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.stop_on_error = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Switch::new("sql-import-transaction")
+                    .label(ts("sql-import-transaction"))
+                    .checked(self.transaction)
+                    .disabled(running)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.transaction = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(self.render_import_state(cx))
+            .child(
+                div().h_flex().justify_end().child(
+                    Button::new("sql-import")
+                        .primary()
+                        .label(ts("import"))
+                        .loading(running)
+                        .on_click(cx.listener(Self::on_import)),
+                ),
+            )
+    }
+}
@@ -1,2 +1,10 @@
 pub mod database;
+pub mod export;
+pub mod formatter_settings;
+pub mod import_csv;
+pub mod import_sql;
+pub mod save_query;
+pub mod settings;
+pub mod sql_dump;
+pub mod unsaved_changes;
 pub mod welcome;
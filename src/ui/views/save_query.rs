@@ -0,0 +1,165 @@
+//! [SaveQueryView] dialog for saving a query into the query library
+
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    StyledExt, WindowExt,
+    alert::Alert,
+    button::{Button, ButtonVariants},
+    input::{Input, InputState},
+    switch::Switch,
+};
+
+use crate::{
+    library::{SavedQuery, SavedQueryParameter, SavedQueryScope, save_query},
+    state::database::DatabaseResourceExt,
+    ui::components::{atoms::i18n::translated::ts, molecules::form::labelled},
+};
+
+pub struct SaveQueryView {
+    /// SQL of the query to save
+    query: String,
+
+    name_input: Entity<InputState>,
+    folder_input: Entity<InputState>,
+    description_input: Entity<InputState>,
+    parameters_input: Entity<InputState>,
+
+    /// Only make the query available for the current database
+    database_only: bool,
+
+    error: Option<SharedString>,
+}
+
+/// Open the dialog for saving the `query` within the `window`
+pub fn open_save_query_dialog(query: String, window: &mut Window, cx: &mut App) {
+    let view = SaveQueryView::new(query, window, cx);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(ts("library-save"))
+            .width(px(480.))
+            .child(view.clone())
+    });
+}
+
+/// Parse the comma separated parameters in the form "name" or "name=default"
+fn parse_parameters(value: &str) -> Vec<SavedQueryParameter> {
+    value
+        .split(',')
+        .filter_map(|parameter| {
+            let (name, default_value) = match parameter.split_once('=') {
+                Some((name, default_value)) => (name, Some(default_value.trim().to_string())),
+                None => (parameter, None),
+            };
+
+            let name = name.trim().trim_start_matches(':');
+            if name.is_empty() {
+                return None;
+            }
+
+            Some(SavedQueryParameter {
+                name: name.to_string(),
+                default_value,
+            })
+        })
+        .collect()
+}
+
+impl SaveQueryView {
+    pub fn new(query: String, window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self {
+            query,
+            name_input: cx.new(|cx| InputState::new(window, cx)),
+            folder_input: cx.new(|cx| InputState::new(window, cx)),
+            description_input: cx.new(|cx| InputState::new(window, cx)),
+            parameters_input: cx
+                .new(|cx| InputState::new(window, cx).placeholder("name, limit=10")),
+            database_only: false,
+            error: None,
+        })
+    }
+
+    fn on_save(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_input.read(cx).value().trim().to_string();
+        if name.is_empty() {
+            self.error = Some(ts("library-name-required"));
+            cx.notify();
+            return;
+        }
+
+        let scope = match self.database_only {
+            true => match cx.database_connection() {
+                Some(database) => SavedQueryScope::Database(database.options().location()),
+                None => SavedQueryScope::Global,
+            },
+            false => SavedQueryScope::Global,
+        };
+
+        let folder = self
+            .folder_input
+            .read(cx)
+            .value()
+            .split('/')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let query = SavedQuery {
+            name,
+            description: self.description_input.read(cx).value().trim().to_string(),
+            folder,
+            query: self.query.clone(),
+            parameters: parse_parameters(&self.parameters_input.read(cx).value()),
+            scope,
+        };
+
+        save_query(cx, query);
+        window.close_dialog(cx);
+    }
+}
+
+impl Render for SaveQueryView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_2()
+            .w_full()
+            .child(labelled(ts("name"), Input::new(&self.name_input)))
+            .child(labelled(
+                ts("library-folder"),
+                Input::new(&self.folder_input),
+            ))
+            .child(labelled(
+                ts("library-description"),
+                Input::new(&self.description_input),
+            ))
+            .child(labelled(
+                ts("library-parameters"),
+                Input::new(&self.parameters_input),
+            ))
+            .child(
+                Switch::new("library-database-only")
+                    .label(ts("library-database-only"))
+                    .checked(self.database_only)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.database_only = *checked;
+                        cx.notify();
+                    })),
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(Alert::error("save-query-error", error).title(ts("error")))
+            })
+            .child(
+                div().h_flex().justify_end().child(
+                    Button::new("save-query")
+                        .primary()
+                        .label(ts("library-save"))
+                        .on_click(cx.listener(Self::on_save)),
+                ),
+            )
+    }
+}
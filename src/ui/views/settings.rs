@@ -0,0 +1,340 @@
+//! [SettingsView] dialog for configuring the application settings
+
+use gpui::{
+    App, AppContext, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Subscription, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, IndexPath, StyledExt, ThemeMode, WindowExt,
+    input::{Input, InputEvent, InputState},
+    select::{SearchableVec, Select, SelectEvent, SelectState},
+};
+
+use crate::{
+    settings::{
+        AppSettings,
+        interface::{TableSettings, ThemeModeSetting},
+        update_settings,
+    },
+    theme::theme_names,
+    ui::{
+        components::{
+            atoms::i18n::translated::ts,
+            molecules::form::{StringSelectState, labelled, options_select},
+        },
+        views::formatter_settings::FormatterSettingsView,
+    },
+};
+
+const THEME_MODE_OPTIONS: &[(&str, ThemeModeSetting)] = &[
+    ("settings-theme-light", ThemeModeSetting::Light),
+    ("settings-theme-dark", ThemeModeSetting::Dark),
+    ("settings-theme-system", ThemeModeSetting::System),
+];
+
+pub struct SettingsView {
+    theme_mode_select: Entity<StringSelectState>,
+    /// Names of the themes available for light mode
+    light_themes: Vec<SharedString>,
+    light_theme_select: Entity<StringSelectState>,
+    /// Names of the themes available for dark mode
+    dark_themes: Vec<SharedString>,
+    dark_theme_select: Entity<StringSelectState>,
+    page_size_input: Entity<InputState>,
+    window_width_input: Entity<InputState>,
+    window_height_input: Entity<InputState>,
+
+    formatter: Entity<FormatterSettingsView>,
+
+    _subscriptions: Vec<Subscription>,
+}
+
+/// Open the settings dialog within the `window`
+pub fn open_settings_dialog(window: &mut Window, cx: &mut App) {
+    let view = SettingsView::new(window, cx);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(ts("settings"))
+            .width(px(480.))
+            .child(view.clone())
+    });
+}
+
+/// Create the state for a select over the theme `names` preceded by the
+/// default theme, with the `selected` theme initially selected
+fn theme_select(
+    names: &[SharedString],
+    selected: Option<&str>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Entity<StringSelectState> {
+    let labels: Vec<String> = std::iter::once(ts("settings-theme-default").to_string())
+        .chain(names.iter().map(|name| name.to_string()))
+        .collect();
+
+    // Themes that are no longer available fall back to the default
+    let selected = selected
+        .and_then(|selected| names.iter().position(|name| name.as_ref() == selected))
+        .map(|index| index + 1)
+        .unwrap_or_default();
+
+    cx.new(|cx| {
+        SelectState::new(
+            SearchableVec::new(labels),
+            Some(IndexPath::new(selected)),
+            window,
+            cx,
+        )
+    })
+}
+
+/// Get the theme name for the currently selected item of `state`, [None]
+/// when the default theme is selected
+fn selected_theme(
+    names: &[SharedString],
+    state: &Entity<StringSelectState>,
+    cx: &App,
+) -> Option<String> {
+    let index = state.read(cx).selected_index(cx)?.row;
+    index
+        .checked_sub(1)
+        .and_then(|index| names.get(index))
+        .map(|name| name.to_string())
+}
+
+impl SettingsView {
+    pub fn new(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let settings = cx.global::<AppSettings>().clone();
+        let formatter = FormatterSettingsView::new(window, cx);
+
+        cx.new(|cx| {
+            let theme_mode_select = options_select(
+                THEME_MODE_OPTIONS,
+                &settings.appearance.theme_mode,
+                window,
+                cx,
+            );
+            let light_themes = theme_names(ThemeMode::Light, cx);
+            let light_theme_select = theme_select(
+                &light_themes,
+                settings.appearance.light_theme.as_deref(),
+                window,
+                cx,
+            );
+            let dark_themes = theme_names(ThemeMode::Dark, cx);
+            let dark_theme_select = theme_select(
+                &dark_themes,
+                settings.appearance.dark_theme.as_deref(),
+                window,
+                cx,
+            );
+            let page_size_input = cx.new(|cx| {
+                InputState::new(window, cx).default_value(settings.table.page_size.to_string())
+            });
+            let window_width_input = cx.new(|cx| {
+                InputState::new(window, cx).default_value(settings.window.width.to_string())
+            });
+            let window_height_input = cx.new(|cx| {
+                InputState::new(window, cx).default_value(settings.window.height.to_string())
+            });
+
+            let subscriptions = vec![
+                cx.subscribe_in(&theme_mode_select, window, Self::on_select_changed),
+                cx.subscribe_in(&light_theme_select, window, Self::on_select_changed),
+                cx.subscribe_in(&dark_theme_select, window, Self::on_select_changed),
+                cx.subscribe_in(&page_size_input, window, Self::on_input_changed),
+                cx.subscribe_in(&window_width_input, window, Self::on_input_changed),
+                cx.subscribe_in(&window_height_input, window, Self::on_input_changed),
+            ];
+
+            Self {
+                theme_mode_select,
+                light_themes,
+                light_theme_select,
+                dark_themes,
+                dark_theme_select,
+                page_size_input,
+                window_width_input,
+                window_height_input,
+                formatter,
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    /// Update the `settings` from the current input values, invalid or
+    /// zero numbers keep their previous value and the page size is limited
+    /// to [TableSettings::MAX_PAGE_SIZE]
+    fn apply(&self, settings: &mut AppSettings, cx: &App) {
+        if let Some(theme_mode) = self
+            .theme_mode_select
+            .read(cx)
+            .selected_index(cx)
+            .and_then(|index| THEME_MODE_OPTIONS.get(index.row))
+            .map(|(_, value)| *value)
+        {
+            settings.appearance.theme_mode = theme_mode;
+        }
+
+        settings.appearance.light_theme =
+            selected_theme(&self.light_themes, &self.light_theme_select, cx);
+        settings.appearance.dark_theme =
+            selected_theme(&self.dark_themes, &self.dark_theme_select, cx);
+
+        if let Ok(page_size) = self.page_size_input.read(cx).value().trim().parse::<u32>()
+            && page_size > 0
+        {
+            settings.table.page_size = page_size.min(TableSettings::MAX_PAGE_SIZE);
+        }
+
+        if let Ok(width) = self
+            .window_width_input
+            .read(cx)
+            .value()
+            .trim()
+            .parse::<f32>()
+            && width > 0.0
+        {
+            settings.window.width = width;
+        }
+
+        if let Ok(height) = self
+            .window_height_input
+            .read(cx)
+            .value()
+            .trim()
+            .parse::<f32>()
+            && height > 0.0
+        {
+            settings.window.height = height;
+        }
+    }
+
+    /// Save the settings when any of the selects change or an input is
+    /// committed
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let mut updated = cx.global::<AppSettings>().clone();
+        self.apply(&mut updated, cx);
+
+        update_settings(cx, move |settings| {
+            *settings = updated;
+        });
+    }
+
+    fn on_select_changed(
+        &mut self,
+        _state: &Entity<StringSelectState>,
+        _event: &SelectEvent<SearchableVec<String>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.save(cx);
+    }
+
+    /// Reset the numeric inputs to the saved values, showing where an
+    /// invalid or out of range value wasn't applied
+    fn reset_inputs(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let settings = cx.global::<AppSettings>().clone();
+
+        for (input, value) in [
+            (&self.page_size_input, settings.table.page_size.to_string()),
+            (&self.window_width_input, settings.window.width.to_string()),
+            (
+                &self.window_height_input,
+                settings.window.height.to_string(),
+            ),
+        ] {
+            if input.read(cx).value().trim() != value {
+                input.update(cx, |input, cx| input.set_value(value, window, cx));
+            }
+        }
+    }
+
+    /// Apply the numeric inputs once editing is finished rather than on
+    /// every keystroke
+    fn on_input_changed(
+        &mut self,
+        _state: &Entity<InputState>,
+        event: &InputEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if matches!(event, InputEvent::Blur | InputEvent::PressEnter { .. }) {
+            self.save(cx);
+            self.reset_inputs(window, cx);
+        }
+    }
+}
+
+/// Helper to render the heading of a settings section
+fn section_heading(label: SharedString, cx: &App) -> impl IntoElement {
+    div()
+        .pt_2()
+        .pb_1()
+        .border_b_1()
+        .border_color(cx.theme().border)
+        .text_sm()
+        .font_semibold()
+        .child(label)
+}
+
+impl Render for SettingsView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_2()
+            .w_full()
+            .child(section_heading(ts("settings-appearance"), cx))
+            .child(labelled(
+                ts("settings-theme"),
+                Select::new(&self.theme_mode_select),
+            ))
+            .child(
+                div()
+                    .h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(labelled(
+                        ts("settings-light-theme"),
+                        Select::new(&self.light_theme_select),
+                    )))
+                    .child(div().flex_1().child(labelled(
+                        ts("settings-dark-theme"),
+                        Select::new(&self.dark_theme_select),
+                    ))),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(ts("settings-themes-hint")),
+            )
+            .child(section_heading(ts("settings-tables"), cx))
+            .child(labelled(
+                ts("settings-page-size"),
+                Input::new(&self.page_size_input),
+            ))
+            .child(section_heading(ts("settings-window"), cx))
+            .child(
+                div()
+                    .h_flex()
+                    .gap_2()
+                    .child(div().flex_1().child(labelled(
+                        ts("settings-window-width"),
+                        Input::new(&self.window_width_input),
+                    )))
+                    .child(div().flex_1().child(labelled(
+                        ts("settings-window-height"),
+                        Input::new(&self.window_height_input),
+                    ))),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(ts("settings-window-hint")),
+            )
+            .child(section_heading(ts("formatter-settings"), cx))
+            .child(self.formatter.clone())
+    }
+}
@@ -0,0 +1,253 @@
+//! [SqlDumpView] dialog for exporting the database as a SQL dump
+
+use std::{collections::HashSet, fs::File, io::BufWriter, path::PathBuf};
+
+use anyhow::Context as AnyhowContext;
+use gpui::{
+    App, AppContext, ClickEvent, Context, Entity, IntoElement, ParentElement, Render, SharedString,
+    Styled, Task, Window, div, prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    StyledExt, WindowExt,
+    alert::Alert,
+    button::{Button, ButtonVariants},
+    checkbox::Checkbox,
+    notification::{Notification, NotificationType},
+    scroll::ScrollableElement,
+    switch::Switch,
+};
+
+use crate::{
+    export::sql::{SqlDumpOptions, write_sql_dump},
+    state::{async_resource::AsyncResource, database::DatabaseResourceExt},
+    ui::components::atoms::i18n::translated::ts,
+};
+
+pub struct SqlDumpView {
+    /// Names of all the tables within the database
+    tables: Vec<String>,
+    /// Names of the tables selected for the dump
+    selected_tables: HashSet<String>,
+
+    schema: bool,
+    data: bool,
+    transaction: bool,
+
+    /// State of the export
+    export_state: SqlDumpState,
+}
+
+enum SqlDumpState {
+    Idle,
+    Running(#[allow(unused)] Task<()>),
+    Error(SharedString),
+}
+
+/// Open the SQL dump dialog within the `window`
+pub fn open_sql_dump_dialog(window: &mut Window, cx: &mut App) {
+    let view = SqlDumpView::new(cx);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(ts("export-sql-dump"))
+            .width(px(520.))
+            .child(view.clone())
+    });
+}
+
+impl SqlDumpView {
+    pub fn new(cx: &mut App) -> Entity<Self> {
+        let tables: Vec<String> = match cx.database_tables().read(cx) {
+            AsyncResource::Loaded(tables) => {
+                tables.iter().map(|table| table.name.clone()).collect()
+            }
+            _ => Vec::new(),
+        };
+
+        cx.new(|_cx| Self {
+            selected_tables: tables.iter().cloned().collect(),
+            tables,
+            schema: true,
+            data: true,
+            transaction: true,
+            export_state: SqlDumpState::Idle,
+        })
+    }
+
+    /// Create the dump options from the current input values
+    fn options(&self) -> SqlDumpOptions {
+        // Only filter the tables when some have been deselected so that the
+        // views are included when dumping the entire database
+        let tables = if self.selected_tables.len() == self.tables.len() {
+            None
+        } else {
+            Some(
+                self.tables
+                    .iter()
+                    .filter(|table| self.selected_tables.contains(*table))
+                    .cloned()
+                    .collect(),
+            )
+        };
+
+        SqlDumpOptions {
+            schema: self.schema,
+            data: self.data,
+            tables,
+            transaction: self.transaction,
+        }
+    }
+
+    fn on_toggle_table(&mut self, table: &str, checked: bool, cx: &mut Context<Self>) {
+        if checked {
+            self.selected_tables.insert(table.to_string());
+        } else {
+            self.selected_tables.remove(table);
+        }
+
+        cx.notify();
+    }
+
+    fn on_export(&mut self, _event: &ClickEvent, window: &mut Window, cx: &mut Context<Self>) {
+        if matches!(self.export_state, SqlDumpState::Running(_)) {
+            return;
+        }
+
+        let database = match cx.database_connection() {
+            Some(value) => value,
+            None => return,
+        };
+
+        let options = self.options();
+
+        let directory = directories::UserDirs::new()
+            .map(|dirs| dirs.home_dir().to_path_buf())
+            .unwrap_or(PathBuf::from("."));
+        let file_stem = PathBuf::from(database.options().path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or("database".to_string());
+        let file_name = format!("{file_stem}.sql");
+        let prompt_recv = cx.prompt_for_new_path(&directory, Some(&file_name));
+
+        let task = cx.spawn_in(window, async move |this, cx| {
+            let path = match prompt_recv.await {
+                Ok(Ok(Some(value))) => value,
+                // Cancelled picking the file or picked nothing
+                _ => {
+                    _ = this.update(cx, |this, cx| {
+                        this.export_state = SqlDumpState::Idle;
+                        cx.notify();
+                    });
+                    return;
+                }
+            };
+
+            let result = async {
+                let file = File::create(&path).context("failed to create dump file")?;
+                write_sql_dump(database, BufWriter::new(file), options).await
+            }
+            .await;
+
+            _ = this.update_in(cx, |this, window, cx| {
+                match result {
+                    Ok(count) => {
+                        this.export_state = SqlDumpState::Idle;
+                        window.close_dialog(cx);
+                        window.push_notification(
+                            Notification::new()
+                                .message(format!("{} {count} {}", ts("exported"), ts("rows")))
+                                .with_type(NotificationType::Success),
+                            cx,
+                        );
+                    }
+                    Err(error) => {
+                        tracing::error!(?error, "failed to export sql dump");
+                        this.export_state = SqlDumpState::Error(format!("{error:?}").into());
+                    }
+                }
+
+                cx.notify();
+            });
+        });
+
+        self.export_state = SqlDumpState::Running(task);
+        cx.notify();
+    }
+
+    fn render_tables(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_1()
+            .max_h(px(240.))
+            .overflow_y_scrollbar()
+            .children(self.tables.iter().enumerate().map(|(index, table)| {
+                let name = table.clone();
+
+                Checkbox::new(("sql-dump-table", index))
+                    .label(table.clone())
+                    .checked(self.selected_tables.contains(table))
+                    .on_click(cx.listener(move |this, checked, _window, cx| {
+                        this.on_toggle_table(&name, *checked, cx);
+                    }))
+            }))
+    }
+}
+
+impl Render for SqlDumpView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = matches!(self.export_state, SqlDumpState::Running(_));
+        let can_export = (self.schema || self.data) && !self.selected_tables.is_empty();
+
+        div()
+            .v_flex()
+            .gap_2()
+            .w_full()
+            .child(
+                Switch::new("sql-dump-schema")
+                    .label(ts("sql-dump-schema"))
+                    .checked(self.schema)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.schema = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Switch::new("sql-dump-data")
+                    .label(ts("sql-dump-data"))
+                    .checked(self.data)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.data = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(
+                Switch::new("sql-dump-transaction")
+                    .label(ts("sql-dump-transaction"))
+                    .checked(self.transaction)
+                    .on_click(cx.listener(|this, checked, _window, cx| {
+                        this.transaction = *checked;
+                        cx.notify();
+                    })),
+            )
+            .child(div().text_xs().child(ts("sql-dump-tables")))
+            .child(self.render_tables(cx))
+            .when_some(
+                match &self.export_state {
+                    SqlDumpState::Error(error) => Some(error.clone()),
+                    _ => None,
+                },
+                |this, error| this.child(Alert::error("sql-dump-error", error).title(ts("error"))),
+            )
+            .child(
+                div().h_flex().justify_end().child(
+                    Button::new("export")
+                        .primary()
+                        .label(ts("export"))
+                        .loading(running)
+                        .disabled(!can_export)
+                        .on_click(cx.listener(Self::on_export)),
+                ),
+            )
+    }
+}
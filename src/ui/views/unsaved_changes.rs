@@ -0,0 +1,54 @@
+//! Dialog confirming unsaved changes are discarded before closing
+
+use std::rc::Rc;
+
+use gpui::{App, ParentElement, Styled, Window, div, px};
+use gpui_component::{
+    StyledExt, WindowExt,
+    button::{Button, ButtonVariants},
+};
+
+use crate::ui::components::atoms::i18n::translated::ts;
+
+/// Open a dialog asking whether to discard unsaved changes, described by the
+/// `message`, calling `on_discard` when they are discarded
+pub fn open_unsaved_changes_dialog(
+    window: &mut Window,
+    cx: &mut App,
+    message: String,
+    on_discard: impl Fn(&mut Window, &mut App) + 'static,
+) {
+    let on_discard = Rc::new(on_discard);
+
+    window.open_dialog(cx, move |dialog, _, _| {
+        dialog
+            .title(ts("sql-file-unsaved-title"))
+            .width(px(400.))
+            .child(message.clone())
+            .footer(
+                div()
+                    .h_flex()
+                    .gap_2()
+                    .child(
+                        Button::new("discard")
+                            .danger()
+                            .label(ts("discard"))
+                            .on_click({
+                                let on_discard = on_discard.clone();
+
+                                move |_, window, cx| {
+                                    window.close_dialog(cx);
+                                    on_discard(window, cx);
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new("cancel")
+                            .label(ts("cancel"))
+                            .on_click(|_, window, cx| {
+                                window.close_dialog(cx);
+                            }),
+                    ),
+            )
+    });
+}
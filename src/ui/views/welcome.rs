@@ -1,13 +1,28 @@
-use crate::ui::{
-    actions::{
-        new_database::NewDatabase, new_memory_database::NewMemoryDatabase,
-        open_encrypted_database::OpenFileEncrypted, open_file::OpenFile,
+use crate::{
+    recent::{RecentDatabase, RecentDatabases, update_recent_databases},
+    ui::{
+        actions::{
+            new_database::NewDatabase,
+            new_memory_database::NewMemoryDatabase,
+            open_encrypted_database::OpenFileEncrypted,
+            open_file::OpenFile,
+            open_recent_database::{OpenRecentDatabase, RemoveMissingRecentDatabases},
+        },
+        components::{
+            atoms::i18n::translated::ts, molecules::welcome::welcome_button::WelcomeButton,
+        },
     },
-    components::{atoms::i18n::translated::ts, molecules::welcome::welcome_button::WelcomeButton},
 };
-use gpui::{App, FontWeight, IntoElement, ParentElement, RenderOnce, Styled, Window, div, img, px};
+use gpui::{
+    App, FontWeight, InteractiveElement, IntoElement, ParentElement, RenderOnce, SharedString,
+    StatefulInteractiveElement, Styled, Window, div, img, prelude::FluentBuilder, px,
+};
 use gpui::{StyledImage, rems};
-use gpui_component::{ActiveTheme, Colorize, Icon, IconName, StyledExt};
+use gpui_component::{
+    ActiveTheme, Colorize, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    tag::Tag,
+};
 
 #[derive(IntoElement)]
 pub struct WelcomeView;
@@ -26,7 +41,8 @@ impl RenderOnce for WelcomeView {
                     .gap_2()
                     .w(px(400.0))
                     .child(welcome_intro(cx))
-                    .child(welcome_actions(cx)),
+                    .child(welcome_actions(cx))
+                    .child(welcome_recent(cx)),
             )
     }
 }
@@ -37,10 +53,14 @@ fn welcome_intro(cx: &mut App) -> impl IntoElement {
         .w_full()
         .gap_2()
         .child(
-            img("icons/logo-light.svg")
-                .object_fit(gpui::ObjectFit::ScaleDown)
-                .w(px(64.))
-                .h(px(64.)),
+            // Light logo stands out against the dark theme and vice versa
+            img(match cx.theme().mode.is_dark() {
+                true => "icons/logo-light.svg",
+                false => "icons/logo-dark.svg",
+            })
+            .object_fit(gpui::ObjectFit::ScaleDown)
+            .w(px(64.))
+            .h(px(64.)),
         )
         .child(
             div()
@@ -61,26 +81,29 @@ fn welcome_intro(cx: &mut App) -> impl IntoElement {
         .mb_4()
 }
 
+/// Heading of a section on the welcome screen
+fn section_heading(label: SharedString, cx: &mut App) -> gpui::Div {
+    div()
+        .gap_1()
+        .text_xs()
+        .font_weight(FontWeight::BOLD)
+        .h_flex()
+        .child(label.to_uppercase())
+        .child(
+            div().flex_auto().h(px(1.0)).bg(cx
+                .theme()
+                .secondary_foreground
+                .lighten(0.1)
+                .opacity(0.3)),
+        )
+}
+
 fn welcome_actions(cx: &mut App) -> impl IntoElement {
     div()
         .v_flex()
         .w_full()
         .gap_2()
-        .child(
-            div()
-                .gap_1()
-                .text_xs()
-                .font_weight(FontWeight::BOLD)
-                .h_flex()
-                .child(ts("get-started").to_uppercase())
-                .child(
-                    div().flex_auto().h(px(1.0)).bg(cx
-                        .theme()
-                        .secondary_foreground
-                        .lighten(0.1)
-                        .opacity(0.3)),
-                ),
-        )
+        .child(section_heading(ts("get-started"), cx))
         .child(
             WelcomeButton::new(
                 "new-database",
@@ -104,7 +127,10 @@ fn welcome_actions(cx: &mut App) -> impl IntoElement {
                 menu.menu(ts("open-database"), Box::new(OpenFile::default()))
                     .menu(
                         ts("open-read-only-database"),
-                        Box::new(OpenFile { read_only: true }),
+                        Box::new(OpenFile {
+                            read_only: true,
+                            new_window: false,
+                        }),
                     )
             }),
         )
@@ -127,3 +153,120 @@ fn welcome_actions(cx: &mut App) -> impl IntoElement {
             }),
         )
 }
+
+fn welcome_recent(cx: &mut App) -> impl IntoElement {
+    let databases = cx.global::<RecentDatabases>().databases.clone();
+    let any_missing = databases.iter().any(RecentDatabase::is_missing);
+
+    div()
+        .v_flex()
+        .w_full()
+        .gap_1()
+        .mt_4()
+        .when(!databases.is_empty(), |this| {
+            this.child(
+                section_heading(ts("recent-databases"), cx).when(any_missing, |this| {
+                    this.child(
+                        Button::new("remove-missing-recent")
+                            .label(ts("recent-remove-missing"))
+                            .ghost()
+                            .xsmall()
+                            .on_click(|_, window, cx| {
+                                window.dispatch_action(Box::new(RemoveMissingRecentDatabases), cx);
+                            }),
+                    )
+                }),
+            )
+        })
+        .children(
+            databases
+                .into_iter()
+                .enumerate()
+                .map(|(index, database)| recent_database_item(index, database, cx)),
+        )
+}
+
+fn recent_database_item(index: usize, database: RecentDatabase, cx: &mut App) -> impl IntoElement {
+    let missing = database.is_missing();
+    let open_path = database.path.clone();
+    let pin_path = database.path.clone();
+    let remove_path = database.path.clone();
+
+    div()
+        .id(("recent-database", index))
+        .h_flex()
+        .gap_2()
+        .w_full()
+        .px_2()
+        .py_1()
+        .rounded_sm()
+        .cursor_pointer()
+        .text_sm()
+        .hover(|style| style.bg(cx.theme().secondary))
+        .when(missing, |this| this.opacity(0.5))
+        .on_click(move |_event, window, cx| {
+            window.dispatch_action(
+                Box::new(OpenRecentDatabase {
+                    path: open_path.clone(),
+                }),
+                cx,
+            );
+        })
+        .child(Icon::new(IconName::File))
+        .child(
+            div()
+                .v_flex()
+                .flex_auto()
+                .overflow_hidden()
+                .child(
+                    div()
+                        .h_flex()
+                        .gap_1()
+                        .child(database.file_name())
+                        .when(database.readonly, |this| {
+                            this.child(Tag::secondary().small().child(ts("recent-readonly")))
+                        })
+                        .when(database.encrypted, |this| {
+                            this.child(Tag::secondary().small().child(ts("recent-encrypted")))
+                        })
+                        .when(missing, |this| {
+                            this.child(Tag::danger().small().child(ts("recent-missing")))
+                        }),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_ellipsis()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(database.path.clone()),
+                ),
+        )
+        .child(
+            Button::new(("recent-database-pin", index))
+                .icon(match database.pinned {
+                    true => IconName::StarOff,
+                    false => IconName::Star,
+                })
+                .ghost()
+                .xsmall()
+                .tooltip(match database.pinned {
+                    true => ts("recent-unpin"),
+                    false => ts("recent-pin"),
+                })
+                .on_click(move |_, _, cx| {
+                    cx.stop_propagation();
+                    update_recent_databases(cx, |recent| recent.toggle_pinned(&pin_path));
+                }),
+        )
+        .child(
+            Button::new(("recent-database-remove", index))
+                .icon(IconName::Close)
+                .ghost()
+                .xsmall()
+                .tooltip(ts("recent-remove"))
+                .on_click(move |_, _, cx| {
+                    cx.stop_propagation();
+                    update_recent_databases(cx, |recent| recent.remove(&remove_path));
+                }),
+        )
+}
@@ -0,0 +1,19 @@
+//! Directories the application stores its files within
+
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+
+fn project_dirs() -> Option<ProjectDirs> {
+    ProjectDirs::from("com", "jacobtread", "crabbyqlite")
+}
+
+/// Directory the application configuration is stored within
+pub fn config_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Directory the application data (e.g. query history) is stored within
+pub fn data_dir() -> Option<PathBuf> {
+    project_dirs().map(|dirs| dirs.data_dir().to_path_buf())
+}
@@ -1,3 +1,5 @@
 pub mod async_utils;
 pub mod defer;
+pub mod dirs;
 pub mod gpui_tokio;
+pub mod sql_format;
@@ -0,0 +1,100 @@
+//! Formatting of SQL text using the formatter settings
+
+use lsp_types::Position;
+use sqlformat::QueryParams;
+
+use crate::settings::formatter::FormatterSettings;
+
+/// Format the `sql` using the formatter `settings`
+pub fn format_sql(sql: &str, settings: &FormatterSettings) -> String {
+    sqlformat::format(sql, &QueryParams::None, &settings.format_options())
+}
+
+/// Map the byte `offset` within the `original` text to the equivalent
+/// offset within the `formatted` text
+///
+/// Formatting only changes whitespace and the case of keywords, so the
+/// offset is placed after the same number of non-whitespace characters
+pub fn map_formatted_offset(original: &str, formatted: &str, offset: usize) -> usize {
+    let count = original
+        .get(..offset)
+        .unwrap_or(original)
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .count();
+
+    if count == 0 {
+        return 0;
+    }
+
+    formatted
+        .char_indices()
+        .filter(|(_, char)| !char.is_whitespace())
+        .nth(count - 1)
+        .map(|(index, char)| index + char.len_utf8())
+        .unwrap_or(formatted.len())
+}
+
+/// Convert a UTF-16 offset within the `text` into a byte offset
+pub fn utf16_to_byte_offset(text: &str, offset: usize) -> usize {
+    let mut utf16_offset = 0;
+
+    for (index, char) in text.char_indices() {
+        if utf16_offset >= offset {
+            return index;
+        }
+        utf16_offset += char.len_utf16();
+    }
+
+    text.len()
+}
+
+/// Convert a byte offset within the `text` into a UTF-16 offset
+pub fn byte_to_utf16_offset(text: &str, offset: usize) -> usize {
+    text.get(..offset)
+        .unwrap_or(text)
+        .chars()
+        .map(char::len_utf16)
+        .sum()
+}
+
+/// Line and character position of the byte `offset` within the `text`
+pub fn text_position(text: &str, offset: usize) -> Position {
+    let before = text.get(..offset).unwrap_or(text);
+    let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+    Position {
+        line: before.matches('\n').count() as u32,
+        character: before[line_start..].chars().count() as u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{byte_to_utf16_offset, map_formatted_offset, utf16_to_byte_offset};
+
+    #[test]
+    fn test_map_formatted_offset() {
+        let original = "select a,b from t where a = 1";
+        let formatted = "select\n  a,\n  b\nfrom\n  t\nwhere\n  a = 1";
+
+        // Cursor after "fr"
+        let offset = original.find("from").unwrap() + 2;
+        let mapped = map_formatted_offset(original, formatted, offset);
+        assert_eq!(&formatted[..mapped], "select\n  a,\n  b\nfr");
+
+        assert_eq!(map_formatted_offset(original, formatted, 0), 0);
+        assert_eq!(
+            map_formatted_offset(original, formatted, original.len()),
+            formatted.len()
+        );
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        let text = "a😀b";
+        assert_eq!(byte_to_utf16_offset(text, 5), 3);
+        assert_eq!(utf16_to_byte_offset(text, 3), 5);
+        assert_eq!(utf16_to_byte_offset(text, 10), text.len());
+    }
+}